@@ -7,49 +7,25 @@
 #include <stdint.h>
 #include <stdlib.h>
 
-#if !defined(GROUP_SWITCHED)
 /**
- * Size for serialized commitment.
+ * Size for serialized commitment; groups are not switched.
  */
 #define COMMIT_LEN 49
-#endif
 
-#if defined(GROUP_SWITCHED)
 /**
- * Size for serialized commitment.
+ * Size for serialized proof; groups are not switched.
  */
-#define COMMIT_LEN 97
-#endif
-
-#if !defined(GROUP_SWITCHED)
-#define POINTPROOFSG1_LEN 48
-#endif
-
-#if defined(GROUP_SWITCHED)
-#define POINTPROOFSG1_LEN 96
-#endif
-
-#if !defined(GROUP_SWITCHED)
-#define POINTPROOFSG2_LEN 96
-#endif
-
-#if defined(GROUP_SWITCHED)
-#define POINTPROOFSG2_LEN 48
-#endif
+#define PROOF_LEN 49
 
-#if !defined(GROUP_SWITCHED)
 /**
- * Size for serialized proof.
+ * Size for serialized commitment; groups are switched.
  */
-#define PROOF_LEN 49
-#endif
+#define SWITCHED_COMMIT_LEN 97
 
-#if defined(GROUP_SWITCHED)
 /**
- * Size for serialized proof.
+ * Size for serialized proof; groups are switched.
  */
-#define PROOF_LEN 97
-#endif
+#define SWITCHED_PROOF_LEN 97
 
 /**
  * deserialized prover parameter struct
@@ -6,8 +6,6 @@
 #include "pointproofs_c.h"
 
 
-//#define GROUP_SWITCHED
-
 // #define DEBUG
 
 // Credit: https://stackoverflow.com/questions/7775991/how-to-get-hexdump-of-a-structure-data
@@ -1,9 +1,23 @@
 #![cfg_attr(feature = "cargo-clippy", allow(clippy::cognitive_complexity))]
 #![cfg_attr(feature = "cargo-clippy", allow(clippy::missing_safety_doc))]
 extern crate bigint;
+extern crate blake2;
 extern crate ff_zeroize as ff;
+#[cfg(feature = "mmap")]
+extern crate memmap2;
 extern crate pairing_plus as pairing;
 extern crate pointproofs_paramgen;
+extern crate rand_core;
+#[cfg(test)]
+extern crate rand_xorshift;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_cbor;
+#[cfg(test)]
+extern crate serde_json;
 extern crate sha2;
 pub mod pairings;
 
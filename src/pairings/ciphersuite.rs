@@ -0,0 +1,151 @@
+//! this file is part of the pointproofs.
+//! It defines the registry of supported ciphersuites.
+//!
+//! A ciphersuite id is a single byte:
+//!     * the highest bit selects the group orientation
+//!       (0: commitments and proofs are in G1; 1: they are in G2)
+//!     * the remaining 7 bits select the hash function and the domain separation tag
+//!
+//! Ciphersuite 0 is the original suite (SHA-512, no domain separation tag),
+//! so that existing serialized commitments and proofs remain valid.
+//! Ciphersuite 3 hashes with `expand_message_xmd`, and separates the hashes
+//! of values, of aggregation coefficients and of parameter seeds.
+//! Ciphersuite 4 also encodes the transcripts of the aggregation coefficients
+//! with fixed width indices and length-prefixed values.
+
+use pairings::param::Ciphersuite;
+
+/// Hash functions that are used to hash values into field elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashFunction {
+    /// SHA-512; 64 bytes of output.
+    Sha512,
+    /// SHA-256; the 64 bytes of output are obtained from two
+    /// invocations with a one byte counter appended to the tag.
+    Sha256,
+    /// BLAKE2b; 64 bytes of output.
+    Blake2b,
+    /// `expand_message_xmd` of RFC 9380 with SHA-256; 64 bytes of output.
+    /// The tag is extended with the tag of the `HashDomain`.
+    ExpandMessageXmdSha256,
+}
+
+/// The purposes for which inputs are hashed into field elements.
+/// Only `HashFunction::ExpandMessageXmdSha256` separates them;
+/// the other hash functions use the same tag for all of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashDomain {
+    /// the committed values
+    Value,
+    /// the coefficients t_i of same commit aggregation
+    SameCommit,
+    /// the coefficients t_j of cross commit aggregation
+    CrossCommit,
+    /// the seed of `paramgen_from_seed`
+    ParamSeed,
+}
+
+impl HashDomain {
+    /// The tag that extends the domain separation tag of the ciphersuite.
+    pub fn tag(self) -> &'static [u8] {
+        match self {
+            HashDomain::Value => b"VALUE_",
+            HashDomain::SameCommit => b"SAME_COMMIT_",
+            HashDomain::CrossCommit => b"CROSS_COMMIT_",
+            HashDomain::ParamSeed => b"PARAM_SEED_",
+        }
+    }
+}
+
+/// The encodings of the transcripts `C | S | m[S]` hashed by `hash_to_ti` and `hash_to_tj`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TranscriptEncoding {
+    /// the indices are `usize` in big endian, i.e., 4 or 8 bytes depending on the platform,
+    /// and the values are concatenated
+    Raw,
+    /// the number of indices, the indices and the lengths of the values are `u64`
+    /// in big endian, and each value is prefixed by its length
+    LengthPrefixed,
+}
+
+/// The groups in which commitments and proofs live.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupOrientation {
+    /// commitments and proofs are in G1 of the pairing engine
+    Standard,
+    /// commitments and proofs are in G2 of the pairing engine
+    Switched,
+}
+
+/// The combination of choices selected by a ciphersuite id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CiphersuiteParams {
+    /// the ciphersuite id
+    pub id: Ciphersuite,
+    /// the hash function used by `hash_to_field_pointproofs`
+    pub hash: HashFunction,
+    /// the domain separation tag that is prepended to every hash input
+    pub dst: &'static [u8],
+    /// the encoding of the transcripts of the aggregation coefficients
+    pub transcript: TranscriptEncoding,
+    /// the groups in which commitments and proofs live
+    pub orientation: GroupOrientation,
+}
+
+/// The bit of the ciphersuite id that selects the switched group orientation.
+pub const SWITCHED_GROUPS_BIT: Ciphersuite = 0x80;
+
+/// Hash function, domain separation tag and transcript encoding,
+/// indexed by the lower 7 bits of the id.
+const HASH_SUITES: [(HashFunction, &[u8], TranscriptEncoding); 5] = [
+    (HashFunction::Sha512, b"", TranscriptEncoding::Raw),
+    (
+        HashFunction::Sha256,
+        b"POINTPROOFS_V1_SHA256_",
+        TranscriptEncoding::Raw,
+    ),
+    (
+        HashFunction::Blake2b,
+        b"POINTPROOFS_V1_BLAKE2B_",
+        TranscriptEncoding::Raw,
+    ),
+    (
+        HashFunction::ExpandMessageXmdSha256,
+        b"POINTPROOFS_V1_XMD:SHA-256_",
+        TranscriptEncoding::Raw,
+    ),
+    (
+        HashFunction::ExpandMessageXmdSha256,
+        b"POINTPROOFS_V2_XMD:SHA-256_",
+        TranscriptEncoding::LengthPrefixed,
+    ),
+];
+
+/// Returns the group orientation selected by the highest bit of a ciphersuite id.
+pub fn group_orientation(csid: Ciphersuite) -> GroupOrientation {
+    if csid & SWITCHED_GROUPS_BIT == 0 {
+        GroupOrientation::Standard
+    } else {
+        GroupOrientation::Switched
+    }
+}
+
+/// Looks up a ciphersuite id in the registry.
+/// Returns None if the id is not registered.
+pub fn ciphersuite_params(csid: Ciphersuite) -> Option<CiphersuiteParams> {
+    let (hash, dst, transcript) = *HASH_SUITES.get((csid & !SWITCHED_GROUPS_BIT) as usize)?;
+    Some(CiphersuiteParams {
+        id: csid,
+        hash,
+        dst,
+        transcript,
+        orientation: group_orientation(csid),
+    })
+}
+
+/// Returns the ids of all registered ciphersuites, in both group orientations.
+pub fn supported_ciphersuites() -> Vec<Ciphersuite> {
+    (0..=255u8)
+        .filter(|csid| ciphersuite_params(*csid).is_some())
+        .collect()
+}
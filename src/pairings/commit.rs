@@ -2,14 +2,15 @@
 //! It defines APIs for constructing and updating commitments.
 
 use ff::{Field, PrimeField};
-use pairing::{bls12_381::*, CurveAffine, CurveProjective};
-use pairings::err::*;
+use pairing::Engine;
+use pairings::ciphersuite::group_orientation;
 use pairings::hash_to_field_pointproofs::*;
 use pairings::misc::*;
 use pairings::param::*;
+use pairings::params_id::check_params_id;
 use pairings::*;
 
-impl Commitment {
+impl<E: Engine> GenericCommitment<E> {
     /// generate a new commitment.
     ///     * input: prover parameter set
     ///     * input: a list of n values
@@ -22,31 +23,129 @@ impl Commitment {
     /// In this scenario, the caller should define the right
     /// format for padding.
     pub fn new<Blob: AsRef<[u8]>>(
-        prover_params: &ProverParams,
+        prover_params: &GenericProverParams<E>,
         values: &[Blob],
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
         };
 
-        if prover_params.n != values.len() {
-            return Err(ERR_INVALID_VALUE.to_owned());
+        // hash the values into scalars
+        let scalars: Vec<E::Fr> = hash_to_field_vec_pointproofs(prover_params.ciphersuite, values);
+        Self::new_from_scalars(prover_params, &scalars)
+    }
+
+    /// generate a new commitment to a list of field elements,
+    /// which are committed to as they are, without hashing.
+    ///     * input: prover parameter set
+    ///     * input: a list of n field elements
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters
+    /// `Commitment::new(values)` is the same as
+    /// `Commitment::new_from_scalars(hash_to_field(values))`.
+    pub fn new_from_scalars(
+        prover_params: &GenericProverParams<E>,
+        scalars: &[E::Fr],
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
         };
 
-        // hash the values into scalars
-        let scalars_fr_repr: Vec<FrRepr> = values
+        if prover_params.n != scalars.len() {
+            return Err(PointproofsError::InvalidValueCount {
+                expected: prover_params.n,
+                found: scalars.len(),
+            });
+        };
+
+        let scalars_u64: Vec<[u64; 4]> = scalars
             .iter()
-            .map(|s| hash_to_field_repr_pointproofs(s.as_ref()))
+            .map(|s| repr_to_u64(&s.into_repr()))
             .collect();
-        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
 
         // commit = \prod pp[i]^scalar[i]
-        let commit = pp_sum_of_prod_helper(&prover_params, &scalars_u64, 0, prover_params.n);
+        let commit = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            wrap_pp_sum_of_prod::<E, G>(&prover_params, &scalars_u64_ref, 0, prover_params.n)
+        });
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            commit,
+            params_id: None,
+        })
+    }
+
+    /// generate a new commitment to a sparse vector.
+    ///     * input: prover parameter set
+    ///     * input: a list of (index, value) pairs for the populated entries
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// Empty entries contribute the identity, as if their
+    /// field elements were 0; only populated entries are hashed
+    /// and exponentiated.
+    /// To fill an empty entry later, call `Commitment::update_scalar`
+    /// with a 0 old field element.
+    pub fn new_sparse<Blob: AsRef<[u8]>>(
+        prover_params: &GenericProverParams<E>,
+        entries: &[(usize, Blob)],
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        };
+
+        // hash the values into scalars
+        let scalar_entries: Vec<(usize, E::Fr)> = entries
+            .iter()
+            .map(|(i, v)| (*i, hash_to_field_pointproofs(prover_params.ciphersuite, v)))
+            .collect();
+        Self::new_sparse_from_scalars(prover_params, &scalar_entries)
+    }
+
+    /// generate a new commitment to a sparse vector of field elements.
+    ///     * input: prover parameter set
+    ///     * input: a list of (index, field element) pairs for the populated entries
+    ///     * output: a commitment
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// Empty entries contribute the identity, i.e., this is the same as
+    /// `Commitment::new_from_scalars` with 0s in the empty entries.
+    pub fn new_sparse_from_scalars(
+        prover_params: &GenericProverParams<E>,
+        entries: &[(usize, E::Fr)],
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        };
+        check_sparse_indices(entries, prover_params.n)?;
+
+        let indices: Vec<usize> = entries.iter().map(|e| e.0).collect();
+        let scalars_u64: Vec<[u64; 4]> = entries
+            .iter()
+            .map(|e| repr_to_u64(&e.1.into_repr()))
+            .collect();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
+
+        // commit = \prod_{populated i} pp[i]^scalar[i]
+        let commit = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            wrap_pp_subset_sum_of_prod::<E, G>(&prover_params, &indices, &scalars_u64_ref)
+        });
 
         Ok(Self {
             ciphersuite: prover_params.ciphersuite,
             commit,
+            params_id: None,
         })
     }
 
@@ -60,30 +159,52 @@ impl Commitment {
     ///     * error: invalid ciphersuite, parameters
     pub fn update<Blob: AsRef<[u8]>>(
         &mut self,
-        prover_params: &ProverParams,
+        prover_params: &GenericProverParams<E>,
         changed_index: usize,
         value_before: Blob,
         value_after: Blob,
-    ) -> Result<(), String> {
-        // checks that cipersuite is supported
-        if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
+    ) -> Result<(), PointproofsError> {
+        check_update_ciphersuites(self, prover_params)?;
+
+        self.update_scalar(
+            prover_params,
+            changed_index,
+            hash_to_field_pointproofs(prover_params.ciphersuite, &value_before),
+            hash_to_field_pointproofs(prover_params.ciphersuite, &value_after),
+        )
+    }
+
+    /// upated an existing commitment to field elements
+    ///     * input: commitment
+    ///     * input: prover parameter set
+    ///     * input: the index of the field element to be updated
+    ///     * input: the old field element
+    ///     * input: the new field element
+    ///     * output: mutate self to the new commitment
+    ///     * error: invalid ciphersuite, parameters
+    pub fn update_scalar(
+        &mut self,
+        prover_params: &GenericProverParams<E>,
+        changed_index: usize,
+        scalar_before: E::Fr,
+        scalar_after: E::Fr,
+    ) -> Result<(), PointproofsError> {
+        check_update_ciphersuites(self, prover_params)?;
         if prover_params.n <= changed_index {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex {
+                index: changed_index,
+                n: prover_params.n,
+            });
         };
 
-        // multiplier = hash(new_value) - hash(old_value)
-        let mut multiplier = hash_to_field_pointproofs(&value_before);
-        multiplier.negate();
-        multiplier.add_assign(&hash_to_field_pointproofs(&value_after));
+        // multiplier = new_scalar - old_scalar
+        let mut multiplier = scalar_after;
+        multiplier.sub_assign(&scalar_before);
 
         // new_commit = old_commit * g[index]^multiplier
-        let res = pp_single_exp_helper(&prover_params, multiplier, changed_index);
-        self.commit.add_assign(&res);
+        self.commit = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            add_single_exp::<E, G>(&self.commit, &prover_params, multiplier, changed_index)
+        });
 
         Ok(())
     }
@@ -103,75 +224,116 @@ impl Commitment {
     /// n is invalid -- in this case the caller should call Commitment::new
     pub fn batch_update<Blob: AsRef<[u8]>>(
         &mut self,
-        prover_params: &ProverParams,
+        prover_params: &GenericProverParams<E>,
         changed_index: &[usize],
         value_before: &[Blob],
         value_after: &[Blob],
-    ) -> Result<(), String> {
-        // checks that cipersuite is supported
-        if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
-        if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
+    ) -> Result<(), PointproofsError> {
+        check_update_ciphersuites(self, prover_params)?;
+
+        let scalars_before: Vec<E::Fr> =
+            hash_to_field_vec_pointproofs(prover_params.ciphersuite, value_before);
+        let scalars_after: Vec<E::Fr> =
+            hash_to_field_vec_pointproofs(prover_params.ciphersuite, value_after);
+        self.batch_update_scalars(
+            prover_params,
+            changed_index,
+            &scalars_before,
+            &scalars_after,
+        )
+    }
+
+    /// upated an existing commitment to field elements with a list of field elements
+    ///     * input: commitment
+    ///     * input: prover parameter set
+    ///     * input: the indices of the field elements to be updated
+    ///     * input: the old field elements
+    ///     * input: the new field elements
+    ///     * output: mutate self to the new commitment
+    ///     * error: invalid ciphersuite, parameters
+    /// The same restrictions on the indices as for `Commitment::batch_update` apply.
+    pub fn batch_update_scalars(
+        &mut self,
+        prover_params: &GenericProverParams<E>,
+        changed_index: &[usize],
+        scalars_before: &[E::Fr],
+        scalars_after: &[E::Fr],
+    ) -> Result<(), PointproofsError> {
+        check_update_ciphersuites(self, prover_params)?;
         // check the parameters are valid
         for index in changed_index {
             if prover_params.n <= *index {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::InvalidIndex {
+                    index: *index,
+                    n: prover_params.n,
+                });
             };
         }
         if changed_index.len() >= prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndexCount {
+                count: changed_index.len(),
+                n: prover_params.n,
+            });
         }
-        if changed_index.len() != value_before.len() || changed_index.len() != value_after.len() {
-            return Err(ERR_INDEX_VALUE_NOT_MATCH.to_owned());
+        if changed_index.len() != scalars_before.len() {
+            return Err(PointproofsError::IndexValueMismatch {
+                indices: changed_index.len(),
+                values: scalars_before.len(),
+            });
         }
-        if !misc::has_unique_elements(changed_index) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+        if changed_index.len() != scalars_after.len() {
+            return Err(PointproofsError::IndexValueMismatch {
+                indices: changed_index.len(),
+                values: scalars_after.len(),
+            });
         }
+        misc::check_unique_indices(changed_index)?;
 
-        // get the scalars from the hashes
-        let mut multiplier_set: Vec<FrRepr> = Vec::with_capacity(value_before.len());
-        for i in 0..value_before.len() {
-            // multiplier = hash(new_value) - hash(old_value)
-            let mut multiplier = hash_to_field_pointproofs(&value_before[i]);
-            multiplier.negate();
-            multiplier.add_assign(&hash_to_field_pointproofs(&value_after[i]));
-            multiplier_set.push(multiplier.into_repr());
-        }
-        let scalars_u64: Vec<&[u64; 4]> = multiplier_set.iter().map(|s| &s.0).collect();
-
-        // form the basis for `sum_of_products`
-        let basis = changed_index
+        // multiplier = new_scalar - old_scalar
+        let multiplier_set: Vec<[u64; 4]> = scalars_before
             .iter()
-            .map(|i| prover_params.generators[*i])
-            .collect::<Vec<PointproofsG1Affine>>();
-
-        // compute delta = \prod g[index]^multiplier
-        let delta = {
-            // to use sum_of_products with pre_computation,
-            // we need to form the right basis
-            if prover_params.precomp.len() == 256 * prover_params.generators.len() {
-                let mut pre: Vec<PointproofsG1Affine> =
-                    Vec::with_capacity(changed_index.len() * 256);
-                for e in changed_index.iter() {
-                    pre = [
-                        pre,
-                        prover_params.precomp
-                            [changed_index[*e] * 256..(changed_index[*e] + 1) * 256]
-                            .to_vec(),
-                    ]
-                    .concat();
-                }
-                PointproofsG1Affine::sum_of_products_precomp_256(&basis, &scalars_u64, &pre)
-            } else {
-                // without pre_computation
-                PointproofsG1Affine::sum_of_products(&basis[..], &scalars_u64)
-            }
-        };
+            .zip(scalars_after.iter())
+            .map(|(before, after)| {
+                let mut multiplier = *after;
+                multiplier.sub_assign(before);
+                repr_to_u64(&multiplier.into_repr())
+            })
+            .collect();
+        let scalars_u64: Vec<&[u64; 4]> = multiplier_set.iter().collect();
+
         // new_commit = old_commit * \prod g[index]^multiplier
-        self.commit.add_assign(&delta);
+        self.commit = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            add_pp_subset_sum_of_prod::<E, G>(&self.commit, &prover_params, changed_index, &scalars_u64)
+        });
         Ok(())
     }
 }
+
+impl<E: Engine> std::cmp::PartialEq for GenericCommitment<E> {
+    /// Convenient function to compare commitments;
+    /// the binding to a `params_id` is not compared
+    fn eq(&self, other: &Self) -> bool {
+        self.ciphersuite == other.ciphersuite && self.commit == other.commit
+    }
+}
+
+/// Checks that the commitment and the prover parameters
+/// carry a same, supported ciphersuite, and that the commitment
+/// is not bound to other parameters.
+fn check_update_ciphersuites<E: Engine>(
+    com: &GenericCommitment<E>,
+    prover_params: &GenericProverParams<E>,
+) -> Result<(), PointproofsError> {
+    if com.ciphersuite != prover_params.ciphersuite {
+        return Err(PointproofsError::CiphersuiteMismatch {
+            expected: prover_params.ciphersuite,
+            found: com.ciphersuite,
+        });
+    }
+    if !check_ciphersuite(prover_params.ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite(
+            prover_params.ciphersuite,
+        ));
+    }
+    check_params_id(com, prover_params.params_id)
+}
@@ -0,0 +1,177 @@
+//! this file is part of the pointproofs.
+//! It defines the versioned container of serialized parameters.
+//!
+//! The format is
+//!     `| header | payload |`
+//! where the payload is the `params_id` and the digest of the other half of the
+//! parameters (see `params_id`), then the headerless format of `serdes`,
+//! and the header is `HEADER_LEN` bytes, little endian:
+//!     `| magic | version | kind | ciphersuite id | precomputation | window bits |
+//!       compressed | 0: [u8; 2] | n: u32 | 0: u32 | payload length: u64 |
+//!       SHA-256(payload) |`
+//! The first byte of the magic is not a valid ciphersuite id,
+//! so that the headerless format, which starts with the ciphersuite id,
+//! is still read.
+
+use pairings::param::*;
+use pairings::precomp::*;
+use pairings::*;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+
+const MAGIC: [u8; 8] = *b"PPPARAMS";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 64;
+
+/// The parameters held by a container.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ParamsKind {
+    Prover = 0,
+    Verifier = 1,
+}
+
+/// The header of a container.
+pub(crate) struct ContainerHeader {
+    pub(crate) kind: ParamsKind,
+    pub(crate) ciphersuite: Ciphersuite,
+    pub(crate) n: usize,
+    pub(crate) precomputation: Precomputation,
+    pub(crate) compressed: bool,
+}
+
+impl ContainerHeader {
+    /// Writes the header of `payload`, then the payload.
+    pub(crate) fn write<W: Write>(&self, writer: &mut W, payload: &[u8]) -> std::io::Result<()> {
+        let (kind, bits) = precomputation_code(self.precomputation);
+        let mut header = [0u8; HEADER_LEN];
+        header[0..8].copy_from_slice(&MAGIC);
+        header[8] = VERSION;
+        header[9] = self.kind as u8;
+        header[10] = self.ciphersuite;
+        header[11] = kind;
+        header[12] = bits;
+        header[13] = self.compressed as u8;
+        header[16..20].copy_from_slice(&(self.n as u32).to_le_bytes());
+        header[24..32].copy_from_slice(&(payload.len() as u64).to_le_bytes());
+        header[32..64].copy_from_slice(&Sha256::digest(payload));
+        writer.write_all(&header)?;
+        writer.write_all(payload)
+    }
+
+    /// Checks that the header describes the parameters read from its payload.
+    pub(crate) fn check(
+        &self,
+        ciphersuite: Ciphersuite,
+        n: usize,
+        precomputation: Option<Precomputation>,
+    ) -> Result<(), PointproofsError> {
+        if self.ciphersuite != ciphersuite {
+            return Err(PointproofsError::CiphersuiteMismatch {
+                expected: self.ciphersuite,
+                found: ciphersuite,
+            });
+        }
+        if self.n != n || precomputation.map_or(false, |p| p != self.precomputation) {
+            return Err(PointproofsError::MalformedEncoding(
+                "the header does not match the payload".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Reads parameters of the given kind with `parse`, either from a container
+/// or, for backward compatibility, in the headerless format;
+/// `parse` is given the payload, and whether it is the one of a container.
+/// Returns the header of the container, if any, along with the parameters;
+/// the caller shall check the parameters against it.
+///
+/// The payload of a container is checked against its length and checksum
+/// before it is parsed, and must be parsed entirely.
+pub(crate) fn read_container<R: Read, T, F>(
+    reader: &mut R,
+    kind: ParamsKind,
+    compressed: bool,
+    parse: F,
+) -> std::io::Result<(T, Option<ContainerHeader>)>
+where
+    F: FnOnce(&mut dyn Read, bool) -> std::io::Result<T>,
+{
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        // the headerless format
+        let mut legacy = (&magic[..]).chain(reader);
+        return Ok((parse(&mut legacy, false)?, None));
+    }
+
+    let mut header = [0u8; HEADER_LEN];
+    reader.read_exact(&mut header[8..])?;
+    if header[8] != VERSION {
+        return Err(PointproofsError::MalformedEncoding(format!(
+            "unsupported container version: {}",
+            header[8]
+        ))
+        .into());
+    }
+    if header[9] != kind as u8 {
+        return Err(PointproofsError::MalformedEncoding(format!(
+            "unexpected kind of parameters: {}",
+            header[9]
+        ))
+        .into());
+    }
+    if !check_ciphersuite(header[10]) {
+        return Err(PointproofsError::InvalidCiphersuite(header[10]).into());
+    }
+    let precomputation = match precomputation_from_code(header[11], header[12]) {
+        Some(p) => p,
+        None => return Err(PointproofsError::InvalidParam.into()),
+    };
+    if header[13] != compressed as u8 {
+        return Err(PointproofsError::MalformedEncoding(
+            "the points are not in the expected (un)compressed form".to_string(),
+        )
+        .into());
+    }
+    let mut n = [0u8; 4];
+    n.copy_from_slice(&header[16..20]);
+    let mut payload_len = [0u8; 8];
+    payload_len.copy_from_slice(&header[24..32]);
+    let payload_len = u64::from_le_bytes(payload_len);
+
+    // the payload is not pre-allocated from its announced length
+    let mut payload: Vec<u8> = vec![];
+    reader.take(payload_len).read_to_end(&mut payload)?;
+    if payload.len() as u64 != payload_len {
+        return Err(PointproofsError::MalformedEncoding(format!(
+            "truncated payload: {} of {} bytes",
+            payload.len(),
+            payload_len
+        ))
+        .into());
+    }
+    if Sha256::digest(&payload)[..] != header[32..64] {
+        return Err(PointproofsError::ChecksumMismatch.into());
+    }
+
+    let mut rest = payload.as_slice();
+    let params = parse(&mut rest, true)?;
+    if !rest.is_empty() {
+        return Err(PointproofsError::MalformedEncoding(format!(
+            "{} trailing bytes in the payload",
+            rest.len()
+        ))
+        .into());
+    }
+    Ok((
+        params,
+        Some(ContainerHeader {
+            kind,
+            ciphersuite: header[10],
+            n: u32::from_le_bytes(n) as usize,
+            precomputation,
+            compressed,
+        }),
+    ))
+}
@@ -0,0 +1,556 @@
+//! this file is part of the pointproofs.
+//! It reads the CRS of `pointproofs_paramgen` as a stream,
+//! and checks its consistency in chunks, reporting the progress
+//! and the first inconsistent element.
+//!
+//! The CRS is over BLS12-381, and its group elements are compressed:
+//!     `| n: u32 | g1^{alpha^i} | g2^{alpha^i} | e(g1, g2)^{alpha^{n+1}} |`
+//! with i = 1, ..., n, n+2, ..., 2n in both groups.
+//! It is consistent if its elements are the powers of the same alpha != 0;
+//! this is checked with the pairing relations
+//!     * e(g1^alpha, g2) = e(g1, g2^alpha)
+//!     * e(g1^{alpha^i}, g2) = e(g1^{alpha^{i-s}}, g2^{alpha^s}), and the same in G2,
+//!       where alpha^{i-s} is the previous power in the CRS
+//!     * e(g1, g2)^{alpha^{n+1}} = e(g1^alpha, g2^{alpha^n})
+//! The relations of a chunk are batched with a random linear combination;
+//! the coefficients are derived from a hash of the CRS.
+//! Verifiers may read only their part of the CRS with `read_verifier_param`,
+//! and check the rest against a published digest.
+
+use pairing::bls12_381::*;
+use pairing::serdes::SerDes;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pairings::ciphersuite::*;
+use pairings::misc::sum_of_prod;
+use pairings::param::*;
+use pairings::params_id::*;
+use pairings::*;
+use sha2::{Digest, Sha256, Sha512};
+use std::io::Read;
+use std::ops::Range;
+
+/// The number of elements decoded, or relations checked, between two progress reports.
+const CRS_CHUNK: usize = 256;
+
+/// The progress of `read_param_with_progress`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrsProgress {
+    /// `done` of the `total` elements of the CRS are decoded.
+    Decoding { done: usize, total: usize },
+    /// `done` of the `total` elements of the CRS are checked.
+    Checking { done: usize, total: usize },
+}
+
+/// The powers of alpha of a CRS; see `param::read_param` for the fields.
+pub(crate) struct CrsPowers {
+    pub(crate) n: usize,
+    pub(crate) g1_alpha_1_to_n: Vec<G1Affine>,
+    pub(crate) g1_alpha_nplus2_to_2n: Vec<G1Affine>,
+    pub(crate) g2_alpha_1_to_n: Vec<G2Affine>,
+    pub(crate) g2_alpha_nplus2_to_2n: Vec<G2Affine>,
+    pub(crate) gt_alpha_nplus1: Fq12,
+}
+
+impl CrsPowers {
+    /// Builds the parameters of the ciphersuite from the powers of alpha.
+    /// The ciphersuite is assumed to be supported.
+    pub(crate) fn into_params(self, ciphersuite: Ciphersuite) -> (ProverParams, VerifierParams) {
+        let orientation = group_orientation(ciphersuite);
+        let (generators, vp_generators) = match orientation {
+            // groups are NOT switched
+            GroupOrientation::Standard => (
+                PointproofsG1Vec::Standard(Points::Owned(
+                    [
+                        self.g1_alpha_1_to_n,
+                        vec![G1::zero().into_affine()],
+                        self.g1_alpha_nplus2_to_2n,
+                    ]
+                    .concat(),
+                )),
+                PointproofsG2Vec::Standard(self.g2_alpha_1_to_n),
+            ),
+            // groups switched
+            GroupOrientation::Switched => (
+                PointproofsG1Vec::Switched(Points::Owned(
+                    [
+                        self.g2_alpha_1_to_n,
+                        vec![G2::zero().into_affine()],
+                        self.g2_alpha_nplus2_to_2n,
+                    ]
+                    .concat(),
+                )),
+                PointproofsG2Vec::Switched(self.g1_alpha_1_to_n),
+            ),
+        };
+
+        let mut pp = GenericProverParams {
+            ciphersuite,
+            n: self.n,
+            generators,
+            precomputation: Precomputation::None,
+            precomp_range: 0..0,
+            precomp: PointproofsG1Vec::empty(orientation),
+            precomp_threshold: DEFAULT_PRECOMP_THRESHOLD,
+            params_id: None,
+            verifier_digest: [0u8; 32],
+        };
+
+        let mut vp = verifier_params(
+            ciphersuite,
+            self.n,
+            vp_generators,
+            self.gt_alpha_nplus1,
+            None,
+            [0u8; 32],
+        );
+        set_params_id(&mut pp, &mut vp);
+        (pp, vp)
+    }
+}
+
+/// Builds the verifier parameters of the ciphersuite, without pre-computation.
+fn verifier_params(
+    ciphersuite: Ciphersuite,
+    n: usize,
+    generators: PointproofsG2Vec,
+    gt_elt: Fq12,
+    params_id: Option<ParamsId>,
+    prover_digest: [u8; 32],
+) -> GenericVerifierParams {
+    GenericVerifierParams {
+        ciphersuite,
+        n,
+        generators,
+        precomputation: Precomputation::None,
+        precomp_range: 0..0,
+        precomp: PointproofsG2Vec::empty(group_orientation(ciphersuite)),
+        precomp_threshold: DEFAULT_PRECOMP_THRESHOLD,
+        gt_elt,
+        params_id,
+        prover_digest,
+    }
+}
+
+/// Reads a CRS of `pointproofs_paramgen` as `read_param` does,
+/// decoding and checking it in chunks.
+///     * input: a reader of the CRS
+///     * input: the ciphersuite of the parameters
+///     * input: a callback, called with the progress after every chunk
+///     * output: the prover and verifier parameters
+///     * error: invalid ciphersuite/n, malformed encoding with the failing element,
+///       or `InconsistentCrsElement` with the first element that fails the relations
+pub fn read_param_with_progress<R: Read, F: FnMut(CrsProgress)>(
+    reader: &mut R,
+    ciphersuite: Ciphersuite,
+    mut progress: F,
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    if !check_ciphersuite(ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite(ciphersuite));
+    }
+    let mut reader = HashingReader {
+        reader,
+        hasher: Sha512::new(),
+    };
+    let n = read_n(&mut reader)?;
+
+    // 2n - 1 elements in each group, and the target group element
+    let total = 4 * n - 1;
+    let mut done = 0;
+    let mut g1: Vec<G1Affine> = read_powers(
+        &mut reader,
+        CrsGroup::G1,
+        n,
+        &mut done,
+        total,
+        &mut progress,
+    )?;
+    let mut g2: Vec<G2Affine> = read_powers(
+        &mut reader,
+        CrsGroup::G2,
+        n,
+        &mut done,
+        total,
+        &mut progress,
+    )?;
+    let gt = Fq12::deserialize(&mut reader, true).map_err(|e| malformed(CrsGroup::Gt, n + 1, e))?;
+    progress(CrsProgress::Decoding { done: total, total });
+
+    let seed = reader.hasher.result();
+    check_powers(&g1, &g2, &gt, n, &seed, &mut progress)?;
+
+    let g1_alpha_nplus2_to_2n = g1.split_off(n);
+    let g2_alpha_nplus2_to_2n = g2.split_off(n);
+    Ok(CrsPowers {
+        n,
+        g1_alpha_1_to_n: g1,
+        g1_alpha_nplus2_to_2n,
+        g2_alpha_1_to_n: g2,
+        g2_alpha_nplus2_to_2n,
+        gt_alpha_nplus1: gt,
+    }
+    .into_params(ciphersuite))
+}
+
+/// Reads the verifier parameters from a CRS of `pointproofs_paramgen`,
+/// with the digest `D` of the whole CRS.
+/// Only the powers of alpha in PointproofsG2, g1^alpha and g2^alpha are decoded;
+/// the prover section is hashed and dropped.
+///     * input: a reader of the CRS
+///     * input: the ciphersuite of the parameters
+///     * output: the verifier parameters, and the digest of the CRS
+///     * error: invalid ciphersuite/n, malformed encoding with the failing element,
+///       or `InconsistentCrsElement` with the first element that fails the relations
+/// The relations are checked for the decoded elements only:
+/// the caller compares the digest with the published one to check the rest of the CRS.
+pub fn read_verifier_param<D: Digest, R: Read>(
+    reader: &mut R,
+    ciphersuite: Ciphersuite,
+) -> Result<(VerifierParams, Vec<u8>), PointproofsError> {
+    if !check_ciphersuite(ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite(ciphersuite));
+    }
+    let mut reader = HashingReader {
+        reader,
+        hasher: D::new(),
+    };
+    let n = read_n(&mut reader)?;
+
+    // the verifier needs the first n powers in PointproofsG2,
+    // and the first power in the other group for the relations;
+    // the digest of the prover parameters covers the powers in PointproofsG1,
+    // and the one of the verifier parameters the first n powers in PointproofsG2
+    let orientation = group_orientation(ciphersuite);
+    let ((g1_len, g1_id_len), (g2_len, g2_id_len)) = match orientation {
+        GroupOrientation::Standard => ((1, 2 * n - 1), (n, n)),
+        GroupOrientation::Switched => ((n, n), (1, 2 * n - 1)),
+    };
+    let mut prover_hasher = Sha256::new();
+    let mut verifier_hasher = Sha256::new();
+    let (g1_hasher, g2_hasher) = match orientation {
+        GroupOrientation::Standard => (&mut prover_hasher, &mut verifier_hasher),
+        GroupOrientation::Switched => (&mut verifier_hasher, &mut prover_hasher),
+    };
+    let g1: Vec<G1Affine> =
+        read_prefix(&mut reader, CrsGroup::G1, n, g1_len, g1_id_len, g1_hasher)?;
+    let g2: Vec<G2Affine> =
+        read_prefix(&mut reader, CrsGroup::G2, n, g2_len, g2_id_len, g2_hasher)?;
+    let gt = Fq12::deserialize(&mut reader, true).map_err(|e| malformed(CrsGroup::Gt, n + 1, e))?;
+    hash_points(&mut verifier_hasher, &[gt]);
+    let digest = reader.hasher.result().to_vec();
+
+    let g1_one = G1Affine::one();
+    let g2_one = G2Affine::one();
+    if g1[0].is_zero() || Bls12::pairing(g1[0], g2_one) != Bls12::pairing(g1_one, g2[0]) {
+        return Err(PointproofsError::InconsistentCrsElement {
+            group: CrsGroup::G1OrG2,
+            power: 1,
+        });
+    }
+    let ranges = [(1..n, 1)];
+    let mut done = 0;
+    match orientation {
+        GroupOrientation::Standard => check_chain(
+            CrsGroup::G2,
+            &g2,
+            n,
+            &ranges,
+            &digest,
+            &mut done,
+            n,
+            &mut |_: CrsProgress| (),
+            |lhs, rhs, _| Bls12::pairing(g1_one, lhs) == Bls12::pairing(g1[0], rhs),
+        )?,
+        GroupOrientation::Switched => check_chain(
+            CrsGroup::G1,
+            &g1,
+            n,
+            &ranges,
+            &digest,
+            &mut done,
+            n,
+            &mut |_: CrsProgress| (),
+            |lhs, rhs, _| Bls12::pairing(lhs, g2_one) == Bls12::pairing(rhs, g2[0]),
+        )?,
+    }
+    let gt_expected = match orientation {
+        GroupOrientation::Standard => Bls12::pairing(g1[0], g2[n - 1]),
+        GroupOrientation::Switched => Bls12::pairing(g1[n - 1], g2[0]),
+    };
+    if gt != gt_expected {
+        return Err(PointproofsError::InconsistentCrsElement {
+            group: CrsGroup::Gt,
+            power: n + 1,
+        });
+    }
+
+    let generators = match orientation {
+        GroupOrientation::Standard => PointproofsG2Vec::Standard(g2),
+        GroupOrientation::Switched => PointproofsG2Vec::Switched(g1),
+    };
+    let prover_digest = digest_result(prover_hasher);
+    let params_id = compute_params_id(
+        ciphersuite,
+        n,
+        &prover_digest,
+        &digest_result(verifier_hasher),
+    );
+    Ok((
+        verifier_params(
+            ciphersuite,
+            n,
+            generators,
+            gt,
+            Some(params_id),
+            prover_digest,
+        ),
+        digest,
+    ))
+}
+
+/// Reads the first `len` of the 2n - 1 powers of alpha in a group,
+/// and hashes over the others without decoding them;
+/// the encodings of the first `id_len` powers go into `id_hasher`.
+fn read_prefix<R: Read, C: SerDes>(
+    reader: &mut R,
+    group: CrsGroup,
+    n: usize,
+    len: usize,
+    id_len: usize,
+    id_hasher: &mut Sha256,
+) -> Result<Vec<C>, PointproofsError> {
+    let element_len = match group {
+        CrsGroup::G1 => GroupOrientation::Standard.pointproofs_g1_len(),
+        _ => GroupOrientation::Standard.pointproofs_g2_len(),
+    };
+    let mut buf = vec![0u8; element_len];
+    let mut points = Vec::with_capacity(len);
+    for position in 0..2 * n - 1 {
+        reader
+            .read_exact(&mut buf)
+            .map_err(|e| malformed(group, power(n, position), e))?;
+        if position < len {
+            points.push(
+                C::deserialize(&mut buf.as_slice(), true)
+                    .map_err(|e| malformed(group, power(n, position), e))?,
+            );
+        }
+        if position < id_len {
+            id_hasher.input(&buf);
+        }
+    }
+    Ok(points)
+}
+
+/// A reader that hashes the bytes it reads.
+struct HashingReader<'a, R: Read, D: Digest> {
+    reader: &'a mut R,
+    hasher: D,
+}
+
+impl<'a, R: Read, D: Digest> Read for HashingReader<'a, R, D> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.reader.read(buf)?;
+        self.hasher.input(&buf[..len]);
+        Ok(len)
+    }
+}
+
+/// Reads and checks the dimension of the CRS.
+fn read_n<R: Read>(reader: &mut R) -> Result<usize, PointproofsError> {
+    let mut buf = [0u8; 4];
+    reader
+        .read_exact(&mut buf)
+        .map_err(|e| PointproofsError::MalformedEncoding(format!("read_param: n: {}", e)))?;
+    let n = u32::from_le_bytes(buf) as usize;
+    if n > MAX_N || n == 0 {
+        return Err(PointproofsError::InvalidN { n, max: MAX_N });
+    }
+    Ok(n)
+}
+
+/// The power of alpha of the element at `position` in its group:
+/// the CRS holds the powers 1, ..., n, n+2, ..., 2n.
+fn power(n: usize, position: usize) -> usize {
+    if position < n {
+        position + 1
+    } else {
+        position + 2
+    }
+}
+
+/// The error for an element that cannot be decoded.
+fn malformed(group: CrsGroup, power: usize, e: std::io::Error) -> PointproofsError {
+    PointproofsError::MalformedEncoding(format!("read_param: {:?}^(alpha^{}): {}", group, power, e))
+}
+
+/// Reads the 2n - 1 powers of alpha in a group.
+fn read_powers<R: Read, C: SerDes, F: FnMut(CrsProgress)>(
+    reader: &mut R,
+    group: CrsGroup,
+    n: usize,
+    done: &mut usize,
+    total: usize,
+    progress: &mut F,
+) -> Result<Vec<C>, PointproofsError> {
+    let mut points = Vec::with_capacity(2 * n - 1);
+    for position in 0..2 * n - 1 {
+        points.push(
+            C::deserialize(reader, true).map_err(|e| malformed(group, power(n, position), e))?,
+        );
+        *done += 1;
+        if *done % CRS_CHUNK == 0 {
+            progress(CrsProgress::Decoding { done: *done, total });
+        }
+    }
+    Ok(points)
+}
+
+/// Checks the pairing relations of the CRS; see the module documentation.
+fn check_powers<F: FnMut(CrsProgress)>(
+    g1: &[G1Affine],
+    g2: &[G2Affine],
+    gt: &Fq12,
+    n: usize,
+    seed: &[u8],
+    progress: &mut F,
+) -> Result<(), PointproofsError> {
+    let total = 4 * n - 1;
+    let g1_one = G1Affine::one();
+    let g2_one = G2Affine::one();
+
+    // g1^alpha and g2^alpha; this is the relation of the first element of both groups,
+    // which does not tell which of them is inconsistent
+    if g1[0].is_zero() || Bls12::pairing(g1[0], g2_one) != Bls12::pairing(g1_one, g2[0]) {
+        return Err(PointproofsError::InconsistentCrsElement {
+            group: CrsGroup::G1OrG2,
+            power: 1,
+        });
+    }
+    let mut done = 2;
+    progress(CrsProgress::Checking { done, total });
+    let ranges = chain_ranges(n);
+    // the relations of step 1 depend on g1^alpha and g2^alpha only, and the ones of
+    // step 2 on g1^{alpha^2} and g2^{alpha^2}: the first ranges of both groups are checked
+    // before the others, so that an inconsistent element is not blamed on another one
+    let (first, rest) = ranges.split_at(std::cmp::min(1, ranges.len()));
+    for ranges in [first, rest].iter() {
+        // e(g1^{alpha^i}, g2) = e(g1^{alpha^{i-s}}, g2^{alpha^s})
+        check_chain(
+            CrsGroup::G1,
+            g1,
+            n,
+            ranges,
+            seed,
+            &mut done,
+            total,
+            progress,
+            |lhs, rhs, step| Bls12::pairing(lhs, g2_one) == Bls12::pairing(rhs, g2[step - 1]),
+        )?;
+        // e(g1, g2^{alpha^i}) = e(g1^{alpha^s}, g2^{alpha^{i-s}})
+        check_chain(
+            CrsGroup::G2,
+            g2,
+            n,
+            ranges,
+            seed,
+            &mut done,
+            total,
+            progress,
+            |lhs, rhs, step| Bls12::pairing(g1_one, lhs) == Bls12::pairing(g1[step - 1], rhs),
+        )?;
+    }
+
+    if *gt != Bls12::pairing(g1[0], g2[n - 1]) {
+        return Err(PointproofsError::InconsistentCrsElement {
+            group: CrsGroup::Gt,
+            power: n + 1,
+        });
+    }
+    progress(CrsProgress::Checking { done: total, total });
+    Ok(())
+}
+
+/// Checks that the elements of `points` at the positions of `ranges` are the previous one
+/// to the power alpha^s, where s is the step of the range;
+/// `pair(lhs, rhs, s)` checks lhs = rhs^{alpha^s} with pairings.
+/// The relations are checked in chunks of `CRS_CHUNK`, and the failing
+/// chunks element by element, to find the first inconsistent element.
+#[allow(clippy::too_many_arguments)]
+fn check_chain<C: CurveAffine, F: FnMut(CrsProgress), P: Fn(C, C, usize) -> bool>(
+    group: CrsGroup,
+    points: &[C],
+    n: usize,
+    ranges: &[(Range<usize>, usize)],
+    seed: &[u8],
+    done: &mut usize,
+    total: usize,
+    progress: &mut F,
+    pair: P,
+) -> Result<(), PointproofsError> {
+    for (range, step) in ranges {
+        let step = *step;
+        let mut start = range.start;
+        while start < range.end {
+            let end = std::cmp::min(start + CRS_CHUNK, range.end);
+            let coeffs: Vec<[u64; 4]> = (start..end)
+                .map(|position| chain_coefficient(seed, group, position))
+                .collect();
+            if !chain_holds(points, start..end, &coeffs, step, &pair) {
+                // find the first element that fails
+                let one = [[1u64, 0, 0, 0]];
+                let position = (start..end)
+                    .find(|p| !chain_holds(points, *p..*p + 1, &one, step, &pair))
+                    .unwrap_or(start);
+                return Err(PointproofsError::InconsistentCrsElement {
+                    group,
+                    power: power(n, position),
+                });
+            }
+            *done += end - start;
+            progress(CrsProgress::Checking { done: *done, total });
+            start = end;
+        }
+    }
+    Ok(())
+}
+
+/// The ranges of positions with the same step in a group of the CRS:
+/// the step is 2 across the missing n+1.
+fn chain_ranges(n: usize) -> Vec<(Range<usize>, usize)> {
+    if n == 1 {
+        vec![]
+    } else {
+        vec![(1..n, 1), (n..n + 1, 2), (n + 1..2 * n - 1, 1)]
+    }
+}
+
+/// Checks the relations of the positions in `range` combined with `coeffs`.
+fn chain_holds<C: CurveAffine, P: Fn(C, C, usize) -> bool>(
+    points: &[C],
+    range: Range<usize>,
+    coeffs: &[[u64; 4]],
+    step: usize,
+    pair: &P,
+) -> bool {
+    let coeffs: Vec<&[u64; 4]> = coeffs.iter().collect();
+    let lhs = sum_of_prod(&points[range.start..range.end], &coeffs);
+    let rhs = sum_of_prod(&points[range.start - 1..range.end - 1], &coeffs);
+    pair(lhs.into_affine(), rhs.into_affine(), step)
+}
+
+/// A 128 bits coefficient of the random linear combinations,
+/// derived from the hash of the CRS, the group and the position.
+fn chain_coefficient(seed: &[u8], group: CrsGroup, position: usize) -> [u64; 4] {
+    let mut hasher = Sha512::new();
+    hasher.input(seed);
+    hasher.input([group as u8]);
+    hasher.input((position as u64).to_le_bytes());
+    let digest = hasher.result();
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().take(2).enumerate() {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[i * 8..i * 8 + 8]);
+        *limb = u64::from_le_bytes(bytes);
+    }
+    limbs
+}
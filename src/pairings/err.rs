@@ -1,15 +1,198 @@
 //! this file is part of the pointproofs.
-//! It defines a list of error messages.
-
-pub(crate) const ERR_SEED_TOO_SHORT: &str = "The seed length is too short";
-pub(crate) const ERR_CIPHERSUITE: &str = "Invalid ciphersuite ID";
-pub(crate) const ERR_COMPRESS: &str = "Only support compress=true mode";
-pub(crate) const ERR_INVALID_VALUE: &str = "Invalid number of values";
-pub(crate) const ERR_INVALID_INDEX: &str = "Invalid index";
-pub(crate) const ERR_DUPLICATED_INDEX: &str = "Duplicated index";
-pub(crate) const ERR_INDEX_PROOF_NOT_MATCH: &str = "Length of index and proof sets do not match";
-pub(crate) const ERR_X_COM_SIZE: &str =
-    "Invalid sizes for commit, proof, or values for cross commit";
-pub(crate) const ERR_MAX_N: &str = "N is too large";
-pub(crate) const ERR_PARAM: &str = "The input parameter is not correct";
-pub(crate) const ERR_INDEX_VALUE_NOT_MATCH: &str = "Length of index and value sets do not match";
+//! It defines the error type returned by the public APIs.
+
+use pairings::param::Ciphersuite;
+use std::fmt;
+
+/// Errors returned by the pointproofs APIs.
+/// Variants carry the offending index/lengths so that callers
+/// can tell malformed requests apart without matching on strings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PointproofsError {
+    /// The seed is shorter than 32 bytes; carries the seed length.
+    SeedTooShort(usize),
+    /// The ciphersuite id is not supported.
+    InvalidCiphersuite(Ciphersuite),
+    /// Two inputs carry different ciphersuite ids.
+    CiphersuiteMismatch {
+        expected: Ciphersuite,
+        found: Ciphersuite,
+    },
+    /// The number of values does not match the parameter n.
+    InvalidValueCount { expected: usize, found: usize },
+    /// An index is outside of [0, n).
+    InvalidIndex { index: usize, n: usize },
+    /// The number of indices is outside of the accepted range for parameter n.
+    InvalidIndexCount { count: usize, n: usize },
+    /// An index appears more than once.
+    DuplicatedIndex(usize),
+    /// The index set and the value set have different lengths.
+    IndexValueMismatch { indices: usize, values: usize },
+    /// The index set and the proof set have different lengths.
+    IndexProofMismatch { indices: usize, proofs: usize },
+    /// The number of commitments does not match the number of
+    /// proofs, index sets or value sets for cross commit operations.
+    CrossCommitSizeMismatch { commits: usize, found: usize },
+    /// N is zero or larger than the supported maximum.
+    InvalidN { n: usize, max: usize },
+    /// The parameters are not well formed.
+    InvalidParam,
+    /// The window of a custom precomputation is outside of [1, max] bits.
+    InvalidWindow { bits: usize, max: usize },
+    /// The pre-computed tables cover only part of the generators.
+    PartialPrecomputation,
+    /// The memory-mapped parameters were written with a different memory layout,
+    /// i.e., by another version or build of this library.
+    IncompatibleLayout,
+    /// The parameters fail the consistency check.
+    InconsistentParams,
+    /// An encoding could not be parsed or produced.
+    MalformedEncoding(String),
+    /// A commitment or a proof is not in the prime order subgroup.
+    PointNotInSubgroup,
+    /// A commitment or a proof is the identity, and the policy rejects it.
+    IdentityPoint,
+    /// The element group^{alpha^power} of a CRS is not consistent
+    /// with the previous ones; see `param::read_param_with_progress`.
+    InconsistentCrsElement { group: CrsGroup, power: usize },
+    /// The parameters, or the parameters a commitment is bound to,
+    /// have a different `params_id` than expected.
+    ParamsIdMismatch,
+    /// The checksum of serialized parameters does not match their content.
+    ChecksumMismatch,
+}
+
+/// The group of an element of a CRS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrsGroup {
+    /// g1^{alpha^i}
+    G1,
+    /// g2^{alpha^i}
+    G2,
+    /// g1^{alpha^i} or g2^{alpha^i}: the relation of g1^alpha and g2^alpha fails,
+    /// and does not tell which of them is inconsistent
+    G1OrG2,
+    /// e(g1, g2)^{alpha^{n+1}}
+    Gt,
+}
+
+impl fmt::Display for PointproofsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PointproofsError::SeedTooShort(len) => {
+                write!(f, "The seed length is too short: {} bytes", len)
+            }
+            PointproofsError::InvalidCiphersuite(csid) => {
+                write!(f, "Invalid ciphersuite ID: {}", csid)
+            }
+            PointproofsError::CiphersuiteMismatch { expected, found } => write!(
+                f,
+                "Ciphersuite ID does not match: expected {}, found {}",
+                expected, found
+            ),
+            PointproofsError::InvalidValueCount { expected, found } => write!(
+                f,
+                "Invalid number of values: expected {}, found {}",
+                expected, found
+            ),
+            PointproofsError::InvalidIndex { index, n } => {
+                write!(f, "Invalid index: {} (n = {})", index, n)
+            }
+            PointproofsError::InvalidIndexCount { count, n } => {
+                write!(f, "Invalid number of indices: {} (n = {})", count, n)
+            }
+            PointproofsError::DuplicatedIndex(index) => write!(f, "Duplicated index: {}", index),
+            PointproofsError::IndexValueMismatch { indices, values } => write!(
+                f,
+                "Length of index and value sets do not match: {} indices, {} values",
+                indices, values
+            ),
+            PointproofsError::IndexProofMismatch { indices, proofs } => write!(
+                f,
+                "Length of index and proof sets do not match: {} indices, {} proofs",
+                indices, proofs
+            ),
+            PointproofsError::CrossCommitSizeMismatch { commits, found } => write!(
+                f,
+                "Invalid sizes for commit, proof, or values for cross commit: \
+                 {} commits, found {}",
+                commits, found
+            ),
+            PointproofsError::InvalidN { n, max } => {
+                write!(f, "N is invalid: {} (must be within [1, {}])", n, max)
+            }
+            PointproofsError::InvalidParam => write!(f, "The input parameter is not correct"),
+            PointproofsError::InvalidWindow { bits, max } => write!(
+                f,
+                "Invalid precomputation window: {} bits (must be within [1, {}])",
+                bits, max
+            ),
+            PointproofsError::PartialPrecomputation => write!(
+                f,
+                "The pre-computed tables cover only part of the generators"
+            ),
+            PointproofsError::IncompatibleLayout => write!(
+                f,
+                "The mapped parameters were written with a different memory layout"
+            ),
+            PointproofsError::InconsistentParams => write!(f, "Input params are not consistent"),
+            PointproofsError::MalformedEncoding(e) => write!(f, "Malformed encoding: {}", e),
+            PointproofsError::PointNotInSubgroup => {
+                write!(f, "The point is not in the prime order subgroup")
+            }
+            PointproofsError::IdentityPoint => write!(f, "The point is the identity"),
+            PointproofsError::InconsistentCrsElement { group, power } => write!(
+                f,
+                "The CRS element {:?}^(alpha^{}) is not consistent",
+                group, power
+            ),
+            PointproofsError::ParamsIdMismatch => {
+                write!(f, "The parameters come from a different setup")
+            }
+            PointproofsError::ChecksumMismatch => {
+                write!(f, "The checksum of the parameters does not match")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PointproofsError {}
+
+impl From<PointproofsError> for std::io::Error {
+    fn from(e: PointproofsError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Reasons for a proof to be rejected by the `try_*verify` APIs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The inputs are malformed; the proof was rejected before any pairing was computed.
+    Malformed(PointproofsError),
+    /// The inputs are well formed but the pairing check does not hold.
+    PairingCheckFailed,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyError::Malformed(e) => write!(f, "Malformed verification input: {}", e),
+            VerifyError::PairingCheckFailed => write!(f, "Pairing check failed"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VerifyError::Malformed(e) => Some(e),
+            VerifyError::PairingCheckFailed => None,
+        }
+    }
+}
+
+impl From<PointproofsError> for VerifyError {
+    fn from(e: PointproofsError) -> Self {
+        VerifyError::Malformed(e)
+    }
+}
@@ -0,0 +1,83 @@
+//! this file is part of the pointproofs.
+//! It defines radix-2 FFTs, over the scalar field and in the exponent of a group;
+//! they are used to compute the proofs for all indices of a vector at once.
+
+use ff::{Field, PrimeField};
+use pairing::CurveProjective;
+
+/// Returns a primitive 2^log_size-th root of unity of the field,
+/// or None if the 2-adicity of the field is smaller than log_size.
+pub(crate) fn root_of_unity<F: PrimeField>(log_size: u32) -> Option<F> {
+    if log_size > F::S {
+        return None;
+    }
+    // F::root_of_unity() is a primitive 2^S-th root of unity
+    let mut omega = F::root_of_unity();
+    for _ in log_size..F::S {
+        omega.square();
+    }
+    Some(omega)
+}
+
+/// In-place FFT of field elements:
+///     a[k] = \sum_t a[t] * omega^{t*k}
+/// where omega is a primitive a.len()-th root of unity, and a.len() is a power of 2.
+pub(crate) fn fft_fr<F: PrimeField>(a: &mut [F], omega: &F) {
+    radix2_fft(a, omega, |u, v, w| {
+        let mut t = *v;
+        t.mul_assign(w);
+        *v = *u;
+        v.sub_assign(&t);
+        u.add_assign(&t);
+    })
+}
+
+/// In-place FFT of group elements, in the exponent:
+///     a[k] = \prod_t a[t]^{omega^{t*k}}
+/// where omega is a primitive a.len()-th root of unity, and a.len() is a power of 2.
+pub(crate) fn fft_group<C: CurveProjective>(a: &mut [C], omega: &C::Scalar) {
+    radix2_fft(a, omega, |u, v, w| {
+        let mut t = *v;
+        t.mul_assign(*w);
+        *v = *u;
+        v.sub_assign(&t);
+        u.add_assign(&t);
+    })
+}
+
+/// Iterative Cooley-Tukey FFT; `butterfly(u, v, w)` sets (u, v) to (u + v*w, u - v*w).
+fn radix2_fft<F: PrimeField, T: Copy>(
+    a: &mut [T],
+    omega: &F,
+    butterfly: impl Fn(&mut T, &mut T, &F),
+) {
+    let n = a.len();
+    assert!(n.is_power_of_two());
+    if n == 1 {
+        return;
+    }
+
+    // bit-reversal permutation
+    let shift = n.leading_zeros() + 1;
+    for k in 0..n {
+        let rk = k.reverse_bits() >> shift;
+        if k < rk {
+            a.swap(k, rk);
+        }
+    }
+
+    let mut m = 1;
+    while m < n {
+        // w_m is a primitive 2m-th root of unity
+        let w_m = omega.pow(&[(n / (2 * m)) as u64]);
+        for k in (0..n).step_by(2 * m) {
+            let mut w = F::one();
+            for j in 0..m {
+                let (left, right) = a.split_at_mut(k + j + m);
+                butterfly(&mut left[k + j], &mut right[0], &w);
+                w.mul_assign(&w_m);
+            }
+        }
+        m *= 2;
+    }
+}
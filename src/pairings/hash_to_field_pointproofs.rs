@@ -3,13 +3,14 @@
 //! The algorithms are described here:
 //! https://github.com/algorand/pointproofs/blob/master/SPEC.md#hashes
 use bigint::U512;
-use ff::PrimeField;
-use pairing::bls12_381::*;
+use blake2::Blake2b;
+use ff::{Field, PrimeField, PrimeFieldRepr};
 use pairing::serdes::SerDes;
-use pairings::err::*;
+use pairing::Engine;
+use pairings::ciphersuite::*;
 use pairings::param::*;
-use pairings::Commitment;
-use sha2::{Digest, Sha512};
+use pairings::{GenericCommitment, PointproofsError};
+use sha2::{Digest, Sha256, Sha512};
 use std::ops::Rem;
 
 // A wrapper of `hash_to_tj_repr` that outputs `Fr`s instead of `FrRepr`s.
@@ -17,17 +18,20 @@ use std::ops::Rem;
 /// * input: a list of k * x indices, for which we need to generate t_j
 /// * input: Value: a list of k * x messages that is commited to
 /// * output: a list of k field elements
-pub(crate) fn hash_to_tj_fr<Blob: AsRef<[u8]>>(
-    commits: &[Commitment],
+pub(crate) fn hash_to_tj_fr<E: Engine, Blob: AsRef<[u8]>>(
+    commits: &[GenericCommitment<E>],
     set: &[Vec<usize>],
     value_sub_vector: &[Vec<Blob>],
     n: usize,
-) -> Result<Vec<Fr>, String> {
+) -> Result<Vec<E::Fr>, PointproofsError>
+where
+    GenericCommitment<E>: SerDes,
+{
     Ok(hash_to_tj_repr(commits, set, value_sub_vector, n)?
         .iter()
         // the hash_to_ti_repr should already produce valid Fr elements
         // so it is safe to unwrap here
-        .map(|s| Fr::from_repr(*s).unwrap())
+        .map(|s| E::Fr::from_repr(*s).unwrap())
         .collect())
 }
 
@@ -37,28 +41,47 @@ pub(crate) fn hash_to_tj_fr<Blob: AsRef<[u8]>>(
 /// * input: a list of k * x indices, for which we need to generate t_j
 /// * input: Value: a list of k * x messages that is commited to
 /// * output: a list of k field elements
-pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
-    commits: &[Commitment],
+pub(crate) fn hash_to_tj_repr<E: Engine, Blob: AsRef<[u8]>>(
+    commits: &[GenericCommitment<E>],
     set: &[Vec<usize>],
     value_sub_vector: &[Vec<Blob>],
     n: usize,
-) -> Result<Vec<FrRepr>, String> {
+) -> Result<Vec<<E::Fr as PrimeField>::Repr>, PointproofsError>
+where
+    GenericCommitment<E>: SerDes,
+{
     // check the length are correct
-    if commits.len() != set.len() || commits.len() != value_sub_vector.len() {
-        return Err(ERR_X_COM_SIZE.to_owned());
-    };
+    for len in &[set.len(), value_sub_vector.len()] {
+        if commits.len() != *len {
+            return Err(PointproofsError::CrossCommitSizeMismatch {
+                commits: commits.len(),
+                found: *len,
+            });
+        }
+    }
 
-    // check the ciphersuite is supported
+    // check the ciphersuite is supported, and is the same for all commits
+    if commits.is_empty() {
+        return Ok(vec![]);
+    }
+    let ciphersuite = commits[0].ciphersuite;
+    let encoding = match ciphersuite_params(ciphersuite) {
+        Some(p) => p.transcript,
+        None => return Err(PointproofsError::InvalidCiphersuite(ciphersuite)),
+    };
     for e in commits {
-        if !check_ciphersuite(e.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+        if e.ciphersuite != ciphersuite {
+            return Err(PointproofsError::CiphersuiteMismatch {
+                expected: ciphersuite,
+                found: e.ciphersuite,
+            });
         }
     }
 
     // handle the case where there is only one input
     // in this case, simply return FrRepr::one()
     if commits.len() == 1 {
-        return Ok(vec![FrRepr([1, 0, 0, 0])]);
+        return Ok(vec![E::Fr::one().into_repr()]);
     }
 
     // tmp = {C | S | m[S]} for i \in [0 .. commit.len-1]
@@ -67,41 +90,45 @@ pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
         // serialize commitment
         match commits[i].serialize(&mut tmp, true) {
             Ok(_p) => _p,
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(PointproofsError::MalformedEncoding(e.to_string())),
         };
         // add the set to tmp
-        for j in 0..set[i].len() {
-            let t = set[i][j].to_be_bytes();
-            tmp.append(&mut t.to_vec());
-        }
+        append_indices(&mut tmp, &set[i], encoding);
 
         // if the set leng does not mathc values, return an error
         if set[i].len() != value_sub_vector[i].len() {
-            return Err(ERR_INDEX_PROOF_NOT_MATCH.to_owned());
+            return Err(PointproofsError::IndexValueMismatch {
+                indices: set[i].len(),
+                values: value_sub_vector[i].len(),
+            });
         }
 
         // add values to set; returns an error if index is out of range
         for j in 0..set[i].len() {
             if set[i][j] >= n {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::InvalidIndex {
+                    index: set[i][j],
+                    n,
+                });
             }
-            let t = value_sub_vector[i][j].as_ref();
-            tmp.append(&mut t.to_vec());
+            append_value(&mut tmp, value_sub_vector[i][j].as_ref(), encoding);
         }
     }
 
-    let mut hasher = Sha512::new();
-    hasher.input(tmp);
-    let digest = hasher.result();
+    let digest = hash_to_64_bytes(ciphersuite, HashDomain::CrossCommit, &tmp);
 
     // formulate the output
     Ok((0..commits.len())
         .map(|i| {
             // each field element t_i is generated as
             // t_i = hash_to_field (i | C | S | m[S])
-            hash_to_field_repr_pointproofs([&i.to_be_bytes()[..], digest.as_ref()].concat())
+            hash_to_field_repr_pointproofs::<E::Fr, _>(
+                ciphersuite,
+                HashDomain::CrossCommit,
+                coefficient_input(i, &digest, encoding),
+            )
         })
-        .collect::<Vec<FrRepr>>())
+        .collect())
 }
 
 // A wrapper of `hash_to_ti` that outputs `Fr`s instead of `FrRepr`s.
@@ -109,17 +136,20 @@ pub(crate) fn hash_to_tj_repr<Blob: AsRef<[u8]>>(
 // * input: a list of indices, for which we need to generate t_i
 // * input: Value: the messages that is commited to
 // * output: a list of field elements
-pub(crate) fn hash_to_ti_fr<Blob: AsRef<[u8]>>(
-    commit: &Commitment,
+pub(crate) fn hash_to_ti_fr<E: Engine, Blob: AsRef<[u8]>>(
+    commit: &GenericCommitment<E>,
     set: &[usize],
     value_sub_vector: &[Blob],
     n: usize,
-) -> Result<Vec<Fr>, String> {
+) -> Result<Vec<E::Fr>, PointproofsError>
+where
+    GenericCommitment<E>: SerDes,
+{
     Ok(hash_to_ti_repr(commit, set, value_sub_vector, n)?
         .iter()
         // the hash_to_ti_repr should already produce valid Fr elements
         // so it is safe to unwrap here
-        .map(|s| Fr::from_repr(*s).unwrap())
+        .map(|s| E::Fr::from_repr(*s).unwrap())
         .collect())
 }
 
@@ -129,30 +159,37 @@ pub(crate) fn hash_to_ti_fr<Blob: AsRef<[u8]>>(
 /// * input: a list of indices, for which we need to generate t_i
 /// * input: Value: the messages that is commited to
 /// * output: a list of field elements
-pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
-    commit: &Commitment,
+pub(crate) fn hash_to_ti_repr<E: Engine, Blob: AsRef<[u8]>>(
+    commit: &GenericCommitment<E>,
     set: &[usize],
     value_sub_vector: &[Blob],
     n: usize,
-) -> Result<Vec<FrRepr>, String> {
-    if !check_ciphersuite(commit.ciphersuite) {
-        return Err(ERR_CIPHERSUITE.to_owned());
-    }
+) -> Result<Vec<<E::Fr as PrimeField>::Repr>, PointproofsError>
+where
+    GenericCommitment<E>: SerDes,
+{
+    let encoding = match ciphersuite_params(commit.ciphersuite) {
+        Some(p) => p.transcript,
+        None => return Err(PointproofsError::InvalidCiphersuite(commit.ciphersuite)),
+    };
     // if the set leng does not mathc values, return an error
     if set.len() != value_sub_vector.len() {
-        return Err(ERR_INVALID_INDEX.to_owned());
+        return Err(PointproofsError::IndexValueMismatch {
+            indices: set.len(),
+            values: value_sub_vector.len(),
+        });
     }
 
     // handle the case where there is only one input
     // in this case, simply return FrRepr::one()
     if set.len() == 1 {
-        return Ok(vec![FrRepr([1, 0, 0, 0])]);
+        return Ok(vec![E::Fr::one().into_repr()]);
     }
 
     // add values to set; returns an error if index is out of range
     for e in set {
         if *e >= n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex { index: *e, n });
         }
     }
 
@@ -161,61 +198,229 @@ pub(crate) fn hash_to_ti_repr<Blob: AsRef<[u8]>>(
     // serialize commitment
     match commit.serialize(&mut tmp, true) {
         Ok(_p) => _p,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(PointproofsError::MalformedEncoding(e.to_string())),
     };
     // add the set to tmp
-    for index in set {
-        let t = index.to_be_bytes();
-        tmp.append(&mut t.to_vec());
-    }
-    // add values to set; returns an error if index is out of range
+    append_indices(&mut tmp, set, encoding);
+    // add values to set
     for e in value_sub_vector {
-        let t = e.as_ref();
-        tmp.append(&mut t.to_vec());
+        append_value(&mut tmp, e.as_ref(), encoding);
     }
 
-    let mut hasher = Sha512::new();
-    hasher.input(tmp);
-    let digest = hasher.result();
+    let digest = hash_to_64_bytes(commit.ciphersuite, HashDomain::SameCommit, &tmp);
 
     // formulate the output
     Ok(set
         .iter()
         .map(|index| {
-            hash_to_field_repr_pointproofs([&index.to_be_bytes()[..], digest.as_ref()].concat())
+            hash_to_field_repr_pointproofs::<E::Fr, _>(
+                commit.ciphersuite,
+                HashDomain::SameCommit,
+                coefficient_input(*index, &digest, encoding),
+            )
         })
         .collect())
 }
 
+/// Appends the indices of a transcript `C | S | m[S]`:
+/// `| index: usize |` each, or `| #indices: u64 | index: u64 |` each if length-prefixed.
+fn append_indices(tmp: &mut Vec<u8>, set: &[usize], encoding: TranscriptEncoding) {
+    match encoding {
+        TranscriptEncoding::Raw => {
+            for index in set {
+                tmp.extend_from_slice(&index.to_be_bytes());
+            }
+        }
+        TranscriptEncoding::LengthPrefixed => {
+            tmp.extend_from_slice(&(set.len() as u64).to_be_bytes());
+            for index in set {
+                tmp.extend_from_slice(&(*index as u64).to_be_bytes());
+            }
+        }
+    }
+}
+
+/// Appends a value of a transcript `C | S | m[S]`:
+/// `| value |`, or `| length: u64 | value |` if length-prefixed.
+fn append_value(tmp: &mut Vec<u8>, value: &[u8], encoding: TranscriptEncoding) {
+    if encoding == TranscriptEncoding::LengthPrefixed {
+        tmp.extend_from_slice(&(value.len() as u64).to_be_bytes());
+    }
+    tmp.extend_from_slice(value);
+}
+
+/// The input `i | digest` of the i-th aggregation coefficient;
+/// `i` is a `usize`, or a `u64` if length-prefixed.
+fn coefficient_input(i: usize, digest: &[u8], encoding: TranscriptEncoding) -> Vec<u8> {
+    match encoding {
+        TranscriptEncoding::Raw => [&i.to_be_bytes()[..], digest].concat(),
+        TranscriptEncoding::LengthPrefixed => [&(i as u64).to_be_bytes()[..], digest].concat(),
+    }
+}
+
 /// A wrapper of `hash_to_field` that outputs `Fr`s instead of `FrRepr`s.
-/// hash_to_field_pointproofs use SHA 512 to hash a blob into a non-zero field element
-pub(crate) fn hash_to_field_pointproofs<Blob: AsRef<[u8]>>(input: Blob) -> Fr {
+/// hash_to_field_pointproofs use the hash function of the ciphersuite
+/// to hash a blob into a non-zero field element
+pub(crate) fn hash_to_field_pointproofs<F: PrimeField, Blob: AsRef<[u8]>>(
+    ciphersuite: Ciphersuite,
+    input: Blob,
+) -> F {
     // the hash_to_field_repr_pointproofs should already produce a valid Fr element
     // so it is safe to unwrap here
-    Fr::from_repr(hash_to_field_repr_pointproofs(input.as_ref())).unwrap()
+    F::from_repr(hash_to_field_repr_pointproofs::<F, _>(
+        ciphersuite,
+        HashDomain::Value,
+        input.as_ref(),
+    ))
+    .unwrap()
+}
+
+/// Hashes the seed of `paramgen_from_seed` into a non-zero field element.
+pub(crate) fn hash_seed_to_field_pointproofs<F: PrimeField, Blob: AsRef<[u8]>>(
+    ciphersuite: Ciphersuite,
+    seed: Blob,
+) -> F {
+    // as above, the output is a valid Fr element
+    F::from_repr(hash_to_field_repr_pointproofs::<F, _>(
+        ciphersuite,
+        HashDomain::ParamSeed,
+        seed.as_ref(),
+    ))
+    .unwrap()
+}
+
+/// Hashes a list of blobs into non-zero field elements, one per blob.
+/// See `hash_to_field_pointproofs`.
+pub(crate) fn hash_to_field_vec_pointproofs<F: PrimeField, Blob: AsRef<[u8]>>(
+    ciphersuite: Ciphersuite,
+    values: &[Blob],
+) -> Vec<F> {
+    values
+        .iter()
+        .map(|s| hash_to_field_pointproofs(ciphersuite, s.as_ref()))
+        .collect()
+}
+
+/// Encodes each field element as the big-endian bytes of its representation.
+/// When the committed values are field elements, those encodings stand in
+/// for the values in the inputs of `hash_to_ti_repr` and `hash_to_tj_repr`.
+pub(crate) fn scalars_to_blobs<F: PrimeField>(scalars: &[F]) -> Vec<Vec<u8>> {
+    scalars
+        .iter()
+        .map(|s| {
+            let mut buf: Vec<u8> = vec![];
+            // writing into a vector does not fail
+            s.into_repr().write_be(&mut buf).unwrap();
+            buf
+        })
+        .collect()
 }
 
 /// Hashes a blob into a non-zero field element.
-/// hash_to_field_pointproofs use SHA 512 to hash a blob into a non-zero field element.
-pub(crate) fn hash_to_field_repr_pointproofs<Blob: AsRef<[u8]>>(input: Blob) -> FrRepr {
-    let mut hasher = Sha512::new();
-    hasher.input(input);
-    let hash_output = hasher.result();
-    let mut t = os2ip_mod_p(&hash_output);
+/// hash_to_field_pointproofs use the hash function of the ciphersuite
+/// to hash a blob into a non-zero field element.
+pub(crate) fn hash_to_field_repr_pointproofs<F: PrimeField, Blob: AsRef<[u8]>>(
+    ciphersuite: Ciphersuite,
+    domain: HashDomain,
+    input: Blob,
+) -> F::Repr {
+    let hash_output = hash_to_64_bytes(ciphersuite, domain, input.as_ref());
+    let mut t = os2ip_mod_p::<F>(&hash_output);
 
     // if we get 0, return 1
     // this should not happen in practise
-    if t == FrRepr([0, 0, 0, 0]) {
-        t = FrRepr([1, 0, 0, 0]);
+    if t.is_zero() {
+        t = F::one().into_repr();
     }
     t
 }
 
+/// Hashes `dst | input` into 64 bytes, using the hash function
+/// and the domain separation tag selected by the ciphersuite.
+/// For `expand_message_xmd`, the tag is `dst | domain tag`, and is appended to the input.
+/// The ciphersuite is assumed to be registered; this is checked by the callers.
+pub(crate) fn hash_to_64_bytes(
+    ciphersuite: Ciphersuite,
+    domain: HashDomain,
+    input: &[u8],
+) -> Vec<u8> {
+    let params = match ciphersuite_params(ciphersuite) {
+        Some(p) => p,
+        None => panic!("{}", PointproofsError::InvalidCiphersuite(ciphersuite)),
+    };
+    match params.hash {
+        HashFunction::Sha512 => {
+            let mut hasher = Sha512::new();
+            hasher.input(params.dst);
+            hasher.input(input);
+            hasher.result().to_vec()
+        }
+        HashFunction::Sha256 => {
+            // two invocations of SHA-256 with a counter byte,
+            // so that the output is large enough to be reduced mod r without bias
+            let mut output = Vec::with_capacity(64);
+            for counter in 0u8..2 {
+                let mut hasher = Sha256::new();
+                hasher.input(params.dst);
+                hasher.input([counter]);
+                hasher.input(input);
+                output.extend_from_slice(&hasher.result());
+            }
+            output
+        }
+        HashFunction::Blake2b => {
+            let mut hasher = Blake2b::new();
+            hasher.input(params.dst);
+            hasher.input(input);
+            hasher.result().to_vec()
+        }
+        HashFunction::ExpandMessageXmdSha256 => {
+            expand_message_xmd(input, &[params.dst, domain.tag()].concat(), 64)
+        }
+    }
+}
+
+/// expand_message_xmd of RFC 9380, section 5.3.1, with SHA-256:
+/// expands `msg` into `len_in_bytes` uniform bytes under the tag `dst`.
+/// `dst` is at most 255 bytes and `len_in_bytes` at most 8160 bytes;
+/// the tags and lengths of the ciphersuites are constants within these bounds.
+pub(crate) fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    assert!(dst.len() <= 255 && len_in_bytes <= 255 * 32);
+    // ell = ceil(len_in_bytes / b_in_bytes)
+    let ell = (len_in_bytes + 31) / 32;
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+
+    // b_0 = H(Z_pad | msg | l_i_b_str | 0 | DST_prime)
+    let mut hasher = Sha256::new();
+    hasher.input([0u8; 64]);
+    hasher.input(msg);
+    hasher.input((len_in_bytes as u16).to_be_bytes());
+    hasher.input([0u8]);
+    hasher.input(&dst_prime);
+    let b_0 = hasher.result();
+
+    // b_i = H((b_0 xor b_{i-1}) | i | DST_prime), with b_1 = H(b_0 | 1 | DST_prime)
+    let mut uniform_bytes: Vec<u8> = Vec::with_capacity(ell * 32);
+    let mut b_i = [0u8; 32];
+    for i in 1..=ell {
+        let mut hasher = Sha256::new();
+        let chained: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        hasher.input(&chained);
+        hasher.input([i as u8]);
+        hasher.input(&dst_prime);
+        b_i.copy_from_slice(&hasher.result());
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
 /// this is Pointproofs's Octect String to Integer Primitive (os2ip) function
 /// https://tools.ietf.org/html/rfc8017#section-4
 /// the input is a 64 bytes array, and the output is between 0 and p-1
 /// i.e., it performs mod operation by default.
-pub(crate) fn os2ip_mod_p(oct_str: &[u8]) -> FrRepr {
+/// p is the characteristic of F, which is assumed to fit in 256 bits.
+pub(crate) fn os2ip_mod_p<F: PrimeField>(oct_str: &[u8]) -> F::Repr {
     // "For the purposes of this document, and consistent with ASN.1 syntax,
     // an octet string is an ordered sequence of octets (eight-bit bytes).
     // The sequence is indexed from first (conventionally, leftmost) to last
@@ -237,13 +442,13 @@ pub(crate) fn os2ip_mod_p(oct_str: &[u8]) -> FrRepr {
 
     let r_sec = U512::from(oct_str);
 
-    // hard coded modulus p
-    let p = U512::from([
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0x73, 0xED, 0xA7, 0x53, 0x29, 0x9D, 0x7D, 0x48, 0x33, 0x39, 0xD8, 0x08, 0x09, 0xA1,
-        0xD8, 0x05, 0x53, 0xBD, 0xA4, 0x02, 0xFF, 0xFE, 0x5B, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0x00,
-        0x00, 0x00, 0x01,
-    ]);
+    // the modulus p, as a big-endian 64 bytes array
+    let mut p_bytes = [0u8; 64];
+    let limbs = F::char().as_ref().len();
+    // writing into a slice of the exact size cannot fail
+    F::char().write_be(&mut p_bytes[64 - 8 * limbs..]).unwrap();
+    let p = U512::from(&p_bytes[..]);
+
     // t = r % p
     let t_sec = r_sec.rem(p);
 
@@ -252,18 +457,8 @@ pub(crate) fn os2ip_mod_p(oct_str: &[u8]) -> FrRepr {
     let bytes: &mut [u8] = tslide.as_mut();
     t_sec.to_big_endian(bytes);
 
-    FrRepr([
-        u64::from_be_bytes([
-            bytes[56], bytes[57], bytes[58], bytes[59], bytes[60], bytes[61], bytes[62], bytes[63],
-        ]),
-        u64::from_be_bytes([
-            bytes[48], bytes[49], bytes[50], bytes[51], bytes[52], bytes[53], bytes[54], bytes[55],
-        ]),
-        u64::from_be_bytes([
-            bytes[40], bytes[41], bytes[42], bytes[43], bytes[44], bytes[45], bytes[46], bytes[47],
-        ]),
-        u64::from_be_bytes([
-            bytes[32], bytes[33], bytes[34], bytes[35], bytes[36], bytes[37], bytes[38], bytes[39],
-        ]),
-    ])
+    let mut t = F::Repr::default();
+    // t < p, so it fits in the limbs of F::Repr
+    t.read_be(&bytes[64 - 8 * limbs..]).unwrap();
+    t
 }
@@ -0,0 +1,447 @@
+//! this file is part of the pointproofs.
+//! It defines an uncompressed, fixed-stride format for prover parameters
+//! that can be memory-mapped and used by commit/prove without decoding.
+//!
+//! The format is
+//!     `| header | canary | generators | pre_compute |`
+//! where every section starts at a multiple of `SECTION_ALIGN` bytes,
+//! and the group elements are stored in their in-memory layout,
+//! i.e., `size_of::<PointproofsG1Affine>()` bytes each, with zeros
+//! in place of the padding bytes.
+//! The header is `HEADER_LEN` bytes, little endian:
+//!     `| magic | version | ciphersuite id | precomputation | window bits |
+//!       point size: u32 | point align: u32 | 0: u32 | n: u64 |
+//!       precomp range start: u64 | precomp range end: u64 | pp_len: u64 | 0: u64 |
+//!       params_id: [u8; 32] | verifier digest: [u8; 32] |`
+//! where the `params_id` and the verifier digest are zeros if the parameters are not linked.
+//! The canary is the generator of PointproofsG1; it detects files
+//! written by a build with a different memory layout.
+//!
+//! The memory layout depends on the build (the pairing engine, the compiler
+//! and the target), so the files shall be produced where they are used,
+//! e.g., with `ProverParams::convert_to_mapped` from the compressed format.
+
+use pairing::serdes::SerDes;
+use pairing::{CurveAffine, Engine};
+use pairings::ciphersuite::group_orientation;
+use pairings::param::*;
+use pairings::params_id::{decode_params_id, encode_params_id};
+use pairings::pointproofs_groups::*;
+use pairings::precomp::*;
+use pairings::*;
+use std::io::{Read, Write};
+use std::mem::{align_of, size_of};
+use std::ops::Range;
+
+const MAGIC: [u8; 8] = *b"PPMAPPED";
+const VERSION: u8 = 1;
+const HEADER_LEN: usize = 128;
+// larger than the alignment of the group elements;
+// mappings themselves are page aligned
+const SECTION_ALIGN: usize = 64;
+// number of group elements written at once
+const WRITE_CHUNK: usize = 1024;
+
+impl<E: Engine> GenericProverParams<E> {
+    /// Writes the parameters in the memory-mapped format:
+    ///
+    /// `| header | canary | generators | pre_compute |` => bytes
+    ///
+    /// The output can be loaded with `map_file` or `read_mapped`
+    /// by the same build of this library.
+    /// Returns an error if ciphersuite id is invalid or the parameters are not well formed.
+    pub fn write_mapped<W: Write>(&self, writer: &mut W) -> std::io::Result<()>
+    where
+        E::G1Affine: MappedAffine,
+        E::G2Affine: MappedAffine,
+    {
+        if !check_ciphersuite(self.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(self.ciphersuite).into());
+        }
+        if self.n == 0
+            || self.n * 2 != self.generators.len()
+            || self.precomp_range.end > self.n * 2
+            || self.precomp.len()
+                != self.precomp_range.len() * points_per_generator::<E::Fr>(self.precomputation)
+        {
+            return Err(PointproofsError::InvalidParam.into());
+        }
+
+        dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            write_mapped_helper::<E, G, W>(self, writer)
+        })
+    }
+
+    /// Converts prover parameters from the compressed format of `SerDes`
+    /// into the memory-mapped format.
+    /// Returns an error if the input cannot be deserialized.
+    pub fn convert_to_mapped<R: Read, W: Write>(
+        reader: &mut R,
+        writer: &mut W,
+    ) -> std::io::Result<()>
+    where
+        Self: SerDes,
+        E::G1Affine: MappedAffine,
+        E::G2Affine: MappedAffine,
+    {
+        Self::deserialize(reader, true)?.write_mapped(writer)
+    }
+
+    /// Reads parameters in the memory-mapped format into memory.
+    /// The group elements are copied, not decoded, and the `params_id`
+    /// is not recomputed.
+    /// Returns an error if the header is malformed, or was written
+    /// with a different memory layout.
+    ///
+    /// # Safety
+    /// The input must have been written by `write_mapped`,
+    /// by the same build of this library.
+    /// Only the header and the canary are checked: the bytes of the coordinates
+    /// and of the flag of the point at infinity are trusted as they are, i.e.,
+    /// they are not checked to be a valid `bool`, reduced field elements,
+    /// or points of the group.
+    pub unsafe fn read_mapped<R: Read>(reader: &mut R) -> std::io::Result<Self>
+    where
+        E::G1Affine: MappedAffine,
+        E::G2Affine: MappedAffine,
+    {
+        let mut bytes: Vec<u8> = vec![];
+        reader.read_to_end(&mut bytes)?;
+        let layout = MappedLayout::decode(&bytes)?;
+
+        let orientation = group_orientation(layout.ciphersuite);
+        let (generators, precomp) = dispatch_groups!(orientation, G => {
+            check_mapped::<E, <G as PointproofsGroups<E>>::G1Affine>(&layout, &bytes)?;
+            (
+                G::wrap_g1_vec(copy_points(&bytes, layout.generators_offset(), layout.n * 2)),
+                G::wrap_g1_vec(copy_points(&bytes, layout.precomp_offset(), layout.pp_len)),
+            )
+        });
+        Ok(layout.into_params(generators, precomp))
+    }
+
+    /// Maps a file in the memory-mapped format.
+    /// The group elements are used in place: they are neither decoded nor copied,
+    /// and the file stays mapped as long as the parameters, or a clone of them, live.
+    /// As for `read_mapped`, the `params_id` is not recomputed.
+    /// Returns an error if the header is malformed, or was written
+    /// with a different memory layout.
+    ///
+    /// # Safety
+    /// The file must have been written by `write_mapped`,
+    /// by the same build of this library, and must not be modified while mapped.
+    /// As for `read_mapped`, the bytes of the coordinates and of the flag
+    /// of the point at infinity are trusted as they are.
+    #[cfg(feature = "mmap")]
+    pub unsafe fn map_file(file: &std::fs::File) -> std::io::Result<Self>
+    where
+        E::G1Affine: MappedAffine,
+        E::G2Affine: MappedAffine,
+    {
+        let map = std::sync::Arc::new(memmap2::Mmap::map(file)?);
+        let layout = MappedLayout::decode(&map)?;
+
+        let orientation = group_orientation(layout.ciphersuite);
+        let (generators, precomp) = dispatch_groups!(orientation, G => {
+            check_mapped::<E, <G as PointproofsGroups<E>>::G1Affine>(&layout, &map)?;
+            let align = align_of::<<G as PointproofsGroups<E>>::G1Affine>();
+            if map.as_ptr() as usize % align != 0 {
+                return Err(PointproofsError::IncompatibleLayout.into());
+            }
+            (
+                G::wrap_g1_points(Points::Mapped {
+                    map: map.clone(),
+                    offset: layout.generators_offset(),
+                    len: layout.n * 2,
+                }),
+                G::wrap_g1_points(Points::Mapped {
+                    map: map.clone(),
+                    offset: layout.precomp_offset(),
+                    len: layout.pp_len,
+                }),
+            )
+        });
+        Ok(layout.into_params(generators, precomp))
+    }
+}
+
+/// The affine group elements that can be written in the memory-mapped format.
+///
+/// # Safety
+/// The type must be a plain struct of field elements and of a `bool` flag
+/// for the point at infinity, as the affine points of pairing-plus:
+/// any bytes for the field elements and 0 or 1 for the flag form a value
+/// of the type, and its equality reads every byte of the field elements.
+pub unsafe trait MappedAffine: CurveAffine {}
+
+unsafe impl MappedAffine for pairing::bls12_381::G1Affine {}
+unsafe impl MappedAffine for pairing::bls12_381::G2Affine {}
+
+/// The header of a file in the memory-mapped format.
+struct MappedLayout {
+    ciphersuite: Ciphersuite,
+    n: usize,
+    precomputation: Precomputation,
+    precomp_range: Range<usize>,
+    pp_len: usize,
+    point_size: usize,
+    point_align: usize,
+    params_id: Option<ParamsId>,
+    verifier_digest: [u8; 32],
+}
+
+impl MappedLayout {
+    /// Offset of the generators.
+    fn generators_offset(&self) -> usize {
+        align_section(HEADER_LEN + self.point_size)
+    }
+
+    /// Offset of the pre-computed data.
+    fn precomp_offset(&self) -> usize {
+        align_section(self.generators_offset() + self.n * 2 * self.point_size)
+    }
+
+    /// Length of the file.
+    fn file_len(&self) -> usize {
+        self.precomp_offset() + self.pp_len * self.point_size
+    }
+
+    /// Formats the header.
+    fn encode(&self) -> [u8; HEADER_LEN] {
+        let (kind, bits) = precomputation_code(self.precomputation);
+        let mut header = [0u8; HEADER_LEN];
+        header[0..8].copy_from_slice(&MAGIC);
+        header[8] = VERSION;
+        header[9] = self.ciphersuite;
+        header[10] = kind;
+        header[11] = bits;
+        header[12..16].copy_from_slice(&(self.point_size as u32).to_le_bytes());
+        header[16..20].copy_from_slice(&(self.point_align as u32).to_le_bytes());
+        header[24..32].copy_from_slice(&(self.n as u64).to_le_bytes());
+        header[32..40].copy_from_slice(&(self.precomp_range.start as u64).to_le_bytes());
+        header[40..48].copy_from_slice(&(self.precomp_range.end as u64).to_le_bytes());
+        header[48..56].copy_from_slice(&(self.pp_len as u64).to_le_bytes());
+        header[64..96].copy_from_slice(&encode_params_id(self.params_id));
+        header[96..128].copy_from_slice(&self.verifier_digest);
+        header
+    }
+
+    /// Parses the header at the beginning of `bytes`.
+    /// The group elements are checked by `check_mapped`.
+    fn decode(bytes: &[u8]) -> Result<Self, PointproofsError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(PointproofsError::InvalidParam);
+        }
+        if bytes[0..8] != MAGIC || bytes[8] != VERSION {
+            return Err(PointproofsError::IncompatibleLayout);
+        }
+        if !check_ciphersuite(bytes[9]) {
+            return Err(PointproofsError::InvalidCiphersuite(bytes[9]));
+        }
+        let precomputation = match precomputation_from_code(bytes[10], bytes[11]) {
+            Some(p) => p,
+            None => return Err(PointproofsError::InvalidParam),
+        };
+        check_precomputation(precomputation)?;
+
+        let n = read_u64(&bytes[24..32]);
+        if n > MAX_N || n == 0 {
+            return Err(PointproofsError::InvalidN { n, max: MAX_N });
+        }
+        let precomp_range = read_u64(&bytes[32..40])..read_u64(&bytes[40..48]);
+        if precomp_range.start > precomp_range.end
+            || precomp_range.end > n * 2
+            || (precomputation == Precomputation::None && precomp_range != (0..0))
+        {
+            return Err(PointproofsError::InvalidParam);
+        }
+
+        let mut params_id = [0u8; 32];
+        params_id.copy_from_slice(&bytes[64..96]);
+        let mut verifier_digest = [0u8; 32];
+        verifier_digest.copy_from_slice(&bytes[96..128]);
+        Ok(MappedLayout {
+            ciphersuite: bytes[9],
+            n,
+            precomputation,
+            precomp_range,
+            pp_len: read_u64(&bytes[48..56]),
+            point_size: read_u32(&bytes[12..16]),
+            point_align: read_u32(&bytes[16..20]),
+            params_id: decode_params_id(params_id),
+            verifier_digest,
+        })
+    }
+
+    /// Builds the parameters from the generators and the pre-computed data of the file.
+    fn into_params<E: Engine>(
+        self,
+        generators: PointproofsG1Vec<E>,
+        precomp: PointproofsG1Vec<E>,
+    ) -> GenericProverParams<E> {
+        GenericProverParams {
+            ciphersuite: self.ciphersuite,
+            n: self.n,
+            generators,
+            precomputation: self.precomputation,
+            precomp_range: self.precomp_range,
+            precomp,
+            precomp_threshold: DEFAULT_PRECOMP_THRESHOLD,
+            params_id: self.params_id,
+            verifier_digest: self.verifier_digest,
+        }
+    }
+}
+
+/// Writes the header and the sections of the parameters.
+fn write_mapped_helper<E: Engine, G: PointproofsGroups<E>, W: Write>(
+    prover_params: &GenericProverParams<E>,
+    writer: &mut W,
+) -> std::io::Result<()>
+where
+    G::G1Affine: MappedAffine,
+{
+    let layout = MappedLayout {
+        ciphersuite: prover_params.ciphersuite,
+        n: prover_params.n,
+        precomputation: prover_params.precomputation,
+        precomp_range: prover_params.precomp_range.clone(),
+        pp_len: prover_params.precomp.len(),
+        point_size: size_of::<G::G1Affine>(),
+        point_align: align_of::<G::G1Affine>(),
+        params_id: prover_params.params_id,
+        verifier_digest: prover_params.verifier_digest,
+    };
+    let fields = field_bytes::<G::G1Affine>();
+    writer.write_all(&layout.encode())?;
+    let mut position = HEADER_LEN;
+    write_points(
+        writer,
+        &[G::G1Affine::one()],
+        &fields,
+        &mut position,
+        HEADER_LEN,
+    )?;
+    write_points(
+        writer,
+        G::g1_slice(&prover_params.generators),
+        &fields,
+        &mut position,
+        layout.generators_offset(),
+    )?;
+    write_points(
+        writer,
+        G::g1_slice(&prover_params.precomp),
+        &fields,
+        &mut position,
+        layout.precomp_offset(),
+    )
+}
+
+/// Checks that the group elements of the file are laid out as in this build:
+/// the size and the alignment of the elements, the length of the file,
+/// the length of the pre-computed data, and the canary.
+fn check_mapped<E: Engine, C: MappedAffine>(
+    layout: &MappedLayout,
+    bytes: &[u8],
+) -> Result<(), PointproofsError> {
+    if layout.point_size != size_of::<C>() || layout.point_align != align_of::<C>() {
+        return Err(PointproofsError::IncompatibleLayout);
+    }
+    // pp_len is checked first, so that file_len does not overflow
+    if layout.pp_len
+        != layout.precomp_range.len() * points_per_generator::<E::Fr>(layout.precomputation)
+        || bytes.len() != layout.file_len()
+    {
+        return Err(PointproofsError::InvalidParam);
+    }
+    // the canary is copied out rather than read in place,
+    // so that this check does not depend on the alignment of `bytes`
+    if unsafe { copy_points::<C>(bytes, HEADER_LEN, 1) }[0] != C::one() {
+        return Err(PointproofsError::IncompatibleLayout);
+    }
+    Ok(())
+}
+
+/// Finds the bytes of the in-memory representation of `C` that belong to
+/// its fields, as opposed to padding: byte `i` does if setting it alone
+/// changes the value, i.e., the point at infinity or the coordinates.
+/// The other bytes may be uninitialized in a value, and shall not be read.
+fn field_bytes<C: MappedAffine>() -> Vec<bool> {
+    let probe = |i: Option<usize>| {
+        let mut bytes = vec![0u8; size_of::<C>()];
+        if let Some(i) = i {
+            bytes[i] = 1;
+        }
+        // a value of C, see MappedAffine
+        unsafe { copy_points::<C>(&bytes, 0, 1) }.remove(0)
+    };
+    let zero = probe(None);
+    (0..size_of::<C>())
+        .map(|i| {
+            let point = probe(Some(i));
+            point.is_zero() || point != zero
+        })
+        .collect()
+}
+
+/// Pads the output with zeros up to `offset`, then writes the
+/// in-memory representation of `points`, where only the bytes of
+/// the fields (see `field_bytes`) are read, and the others are zeros.
+fn write_points<W: Write, C: MappedAffine>(
+    writer: &mut W,
+    points: &[C],
+    fields: &[bool],
+    position: &mut usize,
+    offset: usize,
+) -> std::io::Result<()> {
+    writer.write_all(&vec![0u8; offset - *position])?;
+    let mut bytes: Vec<u8> = Vec::with_capacity(WRITE_CHUNK * size_of::<C>());
+    for chunk in points.chunks(WRITE_CHUNK) {
+        bytes.clear();
+        for point in chunk {
+            let raw = point as *const C as *const u8;
+            bytes.extend(fields.iter().enumerate().map(|(i, field)| {
+                if *field {
+                    unsafe { *raw.add(i) }
+                } else {
+                    0
+                }
+            }));
+        }
+        writer.write_all(&bytes)?;
+    }
+    *position = offset + points.len() * size_of::<C>();
+    Ok(())
+}
+
+/// Copies `len` group elements, in their in-memory representation, from `bytes[offset..]`.
+/// The caller checks that the range is within `bytes`, and that the
+/// elements were written by the same build.
+unsafe fn copy_points<C: MappedAffine>(bytes: &[u8], offset: usize, len: usize) -> Vec<C> {
+    let mut points: Vec<C> = Vec::with_capacity(len);
+    std::ptr::copy_nonoverlapping(
+        bytes[offset..].as_ptr(),
+        points.as_mut_ptr() as *mut u8,
+        len * size_of::<C>(),
+    );
+    points.set_len(len);
+    points
+}
+
+/// Rounds `offset` up to a multiple of `SECTION_ALIGN`.
+fn align_section(offset: usize) -> usize {
+    (offset + SECTION_ALIGN - 1) & !(SECTION_ALIGN - 1)
+}
+
+fn read_u32(bytes: &[u8]) -> usize {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(bytes);
+    u32::from_le_bytes(buf) as usize
+}
+
+fn read_u64(bytes: &[u8]) -> usize {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    u64::from_le_bytes(buf) as usize
+}
@@ -1,64 +1,181 @@
 //! this file is part of the pointproofs.
 //! It defines some misc functions.
 
-use pairing::CurveAffine;
-use pairings::err::ERR_PARAM;
+use ff::PrimeFieldRepr;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pairings::precomp::*;
 use pairings::*;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use std::collections::HashSet;
 use std::hash::Hash;
+use std::ops::Range;
 
-/// checks if a slice/vector constains duplicated elements
-pub(crate) fn has_unique_elements<T>(iter: T) -> bool
+/// returns the first element that appears twice in a slice/vector, if any
+pub(crate) fn first_duplicate<T>(iter: T) -> Option<T::Item>
 where
     T: IntoIterator,
-    T::Item: Eq + Hash,
+    T::Item: Eq + Hash + Copy,
 {
     let mut uniq = HashSet::new();
-    iter.into_iter().all(move |x| uniq.insert(x))
+    iter.into_iter().find(move |x| !uniq.insert(*x))
 }
 
-/// This helper computes the sum of product:
-///     \sum_{i=start}^{end-1}
-///         param.generator[i]^scarlar_u64[i]
-/// It tries to use pre-computed data when possible.
-/// It assumes end - start = n; and the lengths matches.
-/// It doesnot perform any sanity checks of those conditions.
-pub(crate) fn pp_sum_of_prod_helper(
-    prover_params: &ProverParams,
+/// returns an error if the indices contain duplicates
+pub(crate) fn check_unique_indices(indices: &[usize]) -> Result<(), PointproofsError> {
+    match first_duplicate(indices) {
+        Some(index) => Err(PointproofsError::DuplicatedIndex(*index)),
+        None => Ok(()),
+    }
+}
+
+/// returns an error if the indices of a sparse vector
+/// are out of range or contain duplicates
+pub(crate) fn check_sparse_indices<T>(
+    entries: &[(usize, T)],
+    n: usize,
+) -> Result<(), PointproofsError> {
+    for (index, _) in entries {
+        if *index >= n {
+            return Err(PointproofsError::InvalidIndex { index: *index, n });
+        }
+    }
+    match first_duplicate(entries.iter().map(|e| e.0)) {
+        Some(index) => Err(PointproofsError::DuplicatedIndex(index)),
+        None => Ok(()),
+    }
+}
+
+/// Converts a scalar into the four 64-bit limbs taken by `sum_of_products`.
+/// The scalar field of the engine must fit in 256 bits,
+/// as it does for BLS12-381 and BN254.
+pub(crate) fn repr_to_u64<R: PrimeFieldRepr>(repr: &R) -> [u64; 4] {
+    let mut res = [0u64; 4];
+    res.copy_from_slice(repr.as_ref());
+    res
+}
+
+/// Minimum number of terms per chunk, when a multi-exponentiation
+/// is split across the thread pool: below that,
+/// a single thread is faster.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_CHUNK: usize = 64;
+
+/// Number of terms per chunk for a parallel multi-exponentiation
+/// over `len` terms: one chunk per thread.
+#[cfg(feature = "parallel")]
+fn parallel_chunk_size(len: usize) -> usize {
+    let threads = rayon::current_num_threads();
+    std::cmp::max((len + threads - 1) / threads, PARALLEL_MIN_CHUNK)
+}
+
+/// Computes f(0..len), where f(range) is the sum of the terms in `range`.
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn chunked_sum<P, F>(len: usize, f: F) -> P
+where
+    P: CurveProjective,
+    F: Fn(Range<usize>) -> P + Sync,
+{
+    f(0..len)
+}
+
+/// Computes f(0..len), where f(range) is the sum of the terms in `range`.
+/// 0..len is split into chunks whose sums are computed
+/// on the rayon thread pool and then added up;
+/// the result is the same group element as on a single thread.
+#[cfg(feature = "parallel")]
+pub(crate) fn chunked_sum<P, F>(len: usize, f: F) -> P
+where
+    P: CurveProjective,
+    F: Fn(Range<usize>) -> P + Sync,
+{
+    let chunk = parallel_chunk_size(len);
+    (0..(len + chunk - 1) / chunk)
+        .into_par_iter()
+        .map(|k| f(k * chunk..std::cmp::min((k + 1) * chunk, len)))
+        .reduce(P::zero, |mut acc, e| {
+            acc.add_assign(&e);
+            acc
+        })
+}
+
+/// Computes \sum bases[i]^scalars_u64[i] with `sum_of_products`.
+pub(crate) fn sum_of_prod<C: CurveAffine>(bases: &[C], scalars_u64: &[&[u64; 4]]) -> C::Projective {
+    chunked_sum(bases.len(), |r| {
+        C::sum_of_products(&bases[r.clone()], &scalars_u64[r])
+    })
+}
+
+/// Computes \sum bases[i]^scalars_u64[i] with `sum_of_products_precomp_256`,
+/// where precomp[i*256..(i+1)*256] is the pre-computed data for bases[i].
+pub(crate) fn sum_of_prod_precomp_256<C: CurveAffine>(
+    bases: &[C],
+    scalars_u64: &[&[u64; 4]],
+    precomp: &[C],
+) -> C::Projective {
+    chunked_sum(bases.len(), |r| {
+        C::sum_of_products_precomp_256(
+            &bases[r.clone()],
+            &scalars_u64[r.clone()],
+            &precomp[r.start * 256..r.end * 256],
+        )
+    })
+}
+
+/// Wraps \sum_{i=start}^{end-1} param.generator[i]^scarlar_u64[i]
+/// as a PointproofsG1 element; used for commitments and proofs.
+/// It uses the pre-computed data of the parameters, see `PrecompTable`.
+pub(crate) fn wrap_pp_sum_of_prod<E: Engine, G: PointproofsGroups<E>>(
+    prover_params: &GenericProverParams<E>,
     scalars_u64: &[&[u64; 4]],
     start: usize,
     end: usize,
-) -> PointproofsG1 {
-    // the second condition `n <= 1024` comes from benchmarking
-    // pre-computation is faster only when the #basis is <1024
-    if prover_params.precomp.len() == 512 * prover_params.n && prover_params.n <= 1024 {
-        PointproofsG1Affine::sum_of_products_precomp_256(
-            &prover_params.generators[start..end],
-            &scalars_u64,
-            &prover_params.precomp[start * 256..end * 256],
-        )
-    } else {
-        PointproofsG1Affine::sum_of_products(&prover_params.generators[start..end], &scalars_u64)
-    }
+) -> PointproofsG1<E> {
+    G::wrap_g1(pp_table::<E, G>(prover_params).range_sum_of_prod(start, end, scalars_u64))
 }
 
-/// Computes prover_params.generator[index] ^ scalars
-/// Tries to use pre-computated data when possible.
-pub(crate) fn pp_single_exp_helper(
-    prover_params: &ProverParams,
-    scalar: Fr,
+/// Computes element * prover_params.generator[index]^scalar;
+/// used to update commitments and proofs.
+pub(crate) fn add_single_exp<E: Engine, G: PointproofsGroups<E>>(
+    element: &PointproofsG1<E>,
+    prover_params: &GenericProverParams<E>,
+    scalar: E::Fr,
     index: usize,
-) -> PointproofsG1 {
-    if prover_params.precomp.len() == 3 * prover_params.generators.len() {
-        prover_params.generators[index]
-            .mul_precomp_3(scalar, &prover_params.precomp[index * 3..(index + 1) * 3])
-    } else if prover_params.precomp.len() == 256 * prover_params.generators.len() {
-        prover_params.generators[index].mul_precomp_256(
-            scalar,
-            &prover_params.precomp[index * 256..(index + 1) * 256],
-        )
-    } else {
-        assert_eq!(prover_params.precomp.len(), 0, "{}", ERR_PARAM);
-        prover_params.generators[index].mul(scalar)
+) -> PointproofsG1<E> {
+    let mut res = G::g1(element);
+    res.add_assign(&pp_table::<E, G>(prover_params).single_exp(index, scalar));
+    G::wrap_g1(res)
+}
+
+/// Wraps \prod param.generator[indices[i]]^scarlar_u64[i]
+/// as a PointproofsG1 element; used for sparse commitments and proofs,
+/// where only the populated entries of the vector are exponentiated.
+/// It uses the pre-computed data of the parameters, see `PrecompTable`.
+pub(crate) fn wrap_pp_subset_sum_of_prod<E: Engine, G: PointproofsGroups<E>>(
+    prover_params: &GenericProverParams<E>,
+    indices: &[usize],
+    scalars_u64: &[&[u64; 4]],
+) -> PointproofsG1<E> {
+    // an empty vector is committed to the identity
+    if indices.is_empty() {
+        return G::wrap_g1(G::G1::zero());
     }
+    G::wrap_g1(pp_table::<E, G>(prover_params).subset_sum_of_prod(indices, scalars_u64))
+}
+
+/// Computes element * \prod param.generator[indices[i]]^scarlar_u64[i];
+/// used to apply a set of changes to a commitment or a proof at once.
+pub(crate) fn add_pp_subset_sum_of_prod<E: Engine, G: PointproofsGroups<E>>(
+    element: &PointproofsG1<E>,
+    prover_params: &GenericProverParams<E>,
+    indices: &[usize],
+    scalars_u64: &[&[u64; 4]],
+) -> PointproofsG1<E> {
+    let mut res = G::g1(element);
+    res.add_assign(&G::g1(&wrap_pp_subset_sum_of_prod::<E, G>(
+        prover_params,
+        indices,
+        scalars_u64,
+    )));
+    G::wrap_g1(res)
 }
@@ -1,54 +1,125 @@
 //! this file is part of the pointproofs,
-//! a pairing based vector commitment scheme, implemented with BLS12-381 curve.
+//! a pairing based vector commitment scheme, generic over the pairing engine;
+//! BLS12-381 is the default instantiation.
+//! Commitments, proofs and parameters are in either group orientation;
+//! the orientation is selected by the ciphersuite id.
 
-use self::param::Ciphersuite;
+use self::param::{Ciphersuite, Precomputation};
 use self::pointproofs_groups::*;
-use pairing::bls12_381::*;
+use pairing::bls12_381::Bls12;
+use pairing::Engine;
+use std::ops::Range;
 
-/// Structure for porver parameters.
+/// Structure for porver parameters over the pairing engine `E`.
 #[derive(Clone, Debug)]
-pub struct ProverParams {
+pub struct GenericProverParams<E: Engine> {
     pub(crate) ciphersuite: Ciphersuite,
     pub(crate) n: usize,
-    generators: Vec<PointproofsG1Affine>,
-    pp_len: usize,
-    precomp: Vec<PointproofsG1Affine>,
+    generators: PointproofsG1Vec<E>,
+    precomputation: Precomputation,
+    // the generators for which `precomp` holds tables
+    precomp_range: Range<usize>,
+    precomp: PointproofsG1Vec<E>,
+    // the largest number of generators for which
+    // multi-exponentiations use the pre-computed data
+    precomp_threshold: usize,
+    // the fingerprint of the parameters, see `params_id`;
+    // None until they are linked to their verifier parameters
+    params_id: Option<ParamsId>,
+    // the digest of the verifier parameters of the setup, zeros until linked
+    verifier_digest: [u8; 32],
 }
 
-/// Structure for verifier parameters.
+/// Prover parameters over BLS12-381, the default instantiation.
+pub type ProverParams = GenericProverParams<Bls12>;
+
+/// Structure for verifier parameters over the pairing engine `E`.
 #[derive(Clone, Debug)]
-pub struct VerifierParams {
+pub struct GenericVerifierParams<E: Engine> {
     pub(crate) ciphersuite: Ciphersuite,
     pub(crate) n: usize,
-    generators: Vec<PointproofsG2Affine>,
-    pp_len: usize,
-    precomp: Vec<PointproofsG2Affine>,
-    gt_elt: Fq12,
+    generators: PointproofsG2Vec<E>,
+    precomputation: Precomputation,
+    precomp_range: Range<usize>,
+    precomp: PointproofsG2Vec<E>,
+    precomp_threshold: usize,
+    gt_elt: E::Fqk,
+    params_id: Option<ParamsId>,
+    // the digest of the prover parameters of the setup, zeros until linked
+    prover_digest: [u8; 32],
 }
 
-/// Structure to hold a commitment.
-#[derive(Clone, Debug, PartialEq)]
-pub struct Commitment {
+/// Verifier parameters over BLS12-381, the default instantiation.
+pub type VerifierParams = GenericVerifierParams<Bls12>;
+
+/// Structure to hold a commitment over the pairing engine `E`.
+#[derive(Clone, Debug)]
+pub struct GenericCommitment<E: Engine> {
     pub(crate) ciphersuite: Ciphersuite,
-    pub(crate) commit: PointproofsG1,
+    pub(crate) commit: PointproofsG1<E>,
+    // the fingerprint of the parameters the commitment is bound to, if any
+    pub(crate) params_id: Option<ParamsId>,
 }
 
-/// Structure to hold a proof.
-#[derive(Clone, Debug, PartialEq)]
-pub struct Proof {
+/// A commitment over BLS12-381, the default instantiation.
+pub type Commitment = GenericCommitment<Bls12>;
+
+/// Structure to hold a proof over the pairing engine `E`.
+#[derive(Clone, Debug)]
+pub struct GenericProof<E: Engine> {
     pub(crate) ciphersuite: Ciphersuite,
-    pub(crate) proof: PointproofsG1,
+    pub(crate) proof: PointproofsG1<E>,
+}
+
+/// A proof over BLS12-381, the default instantiation.
+pub type Proof = GenericProof<Bls12>;
+
+/// Structure that keeps a vector of values, its commitment
+/// and a cache of proofs in sync under updates.
+/// Cached proofs are refreshed lazily, when they are requested.
+#[derive(Clone, Debug)]
+pub struct GenericProverState<'a, E: Engine> {
+    prover_params: &'a GenericProverParams<E>,
+    // the values, as hashed into t_i for aggregation
+    values: Vec<Vec<u8>>,
+    // the committed field elements
+    scalars: Vec<E::Fr>,
+    commit: GenericCommitment<E>,
+    // the cached proofs, each with the number of changes it reflects
+    pub(crate) proofs: Vec<Option<(GenericProof<E>, usize)>>,
+    // the log of (changed index, new scalar - old scalar)
+    changes: Vec<(usize, E::Fr)>,
 }
 
+/// A prover state over BLS12-381, the default instantiation.
+pub type ProverState<'a> = GenericProverState<'a, Bls12>;
+
+pub use self::err::{CrsGroup, PointproofsError, VerifyError};
+pub use self::params_id::{link_params_id, ParamsId};
+pub use self::validate::IdentityPolicy;
+
+#[macro_use]
+pub mod pointproofs_groups;
+
+pub mod ciphersuite;
 pub(crate) mod commit;
 pub mod param;
 pub(crate) mod prove;
+pub(crate) mod state;
+pub(crate) mod validate;
 
 pub(crate) mod hash_to_field_pointproofs;
-pub mod pointproofs_groups;
 
 //mod c_api;
 pub(crate) mod c_api;
+mod container;
+mod crs;
 mod err;
+mod fft;
+mod precomp;
+mod mapped;
 mod misc;
+mod params_id;
 mod serdes;
+#[cfg(feature = "serde")]
+mod serde_support;
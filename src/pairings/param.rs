@@ -3,23 +3,51 @@
 
 use ff::Field;
 use pairing::serdes::SerDes;
-use pairing::{bls12_381::*, CurveAffine, CurveProjective};
-use pairings::err::*;
-use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
+use pairing::{bls12_381::*, CurveAffine, CurveProjective, Engine};
+use pairings::ciphersuite::*;
+use pairings::crs::CrsPowers;
+pub use pairings::crs::{read_param_with_progress, read_verifier_param, CrsProgress};
+use pairings::hash_to_field_pointproofs::hash_seed_to_field_pointproofs;
+use pairings::misc::sum_of_prod;
+use pairings::params_id::set_params_id;
 use pairings::*;
+use rand_core::RngCore;
+use std::ops::Range;
 
-const VALID_CIPHERSUITE: [u8; 1] = [0u8];
+/// The maximum dimension supported by the parameters.
+pub const MAX_N: usize = 65536;
 
 /// Ciphersuite ID is a wrapper of u8.
 pub type Ciphersuite = u8;
 
-/// Checks if csid is supported. Currently only support csid = 0.
+/// The default for the largest number of generators for which
+/// multi-exponentiations use the pre-computed data.
+/// It comes from benchmarking: pre-computation is faster only when the #basis is <1024.
+pub const DEFAULT_PRECOMP_THRESHOLD: usize = 1024;
+
+/// The pre-computed data held by a set of parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precomputation {
+    /// No pre-computed data.
+    None,
+    /// 3 points per generator; speeds up single exponentiations, i.e., updates.
+    Window3,
+    /// 256 points per generator; speeds up single exponentiations
+    /// and multi-exponentiations over at most `precomp_threshold` generators.
+    Window256,
+    /// A fixed-base table with windows of the given number of bits (1 to 16):
+    /// ceil(255 / bits) * (2^bits - 1) points per generator for BLS12-381.
+    /// Exponentiations then take no doubling, and one addition per window.
+    Custom(usize),
+}
+
+/// Checks if csid is supported: it must be registered in `ciphersuite`.
 pub fn check_ciphersuite(csid: Ciphersuite) -> bool {
-    VALID_CIPHERSUITE.contains(&csid)
+    ciphersuite_params(csid).is_some()
 }
 
-/// Generate a set of parameters from a seed and a ciphersuite ID.
-/// Returns an error is the seed is not long enough; or ciphersuite is not valid; or n == 0
+/// Generate a set of BLS12-381 parameters from a seed and a ciphersuite ID.
+/// Returns an error is the seed is not long enough; or ciphersuite is not valid; or n is too large.
 /// This function shall only be used for testing purpose.
 /// In deployment you should use `pointproofs-param` crate to ensure the
 /// security of the public parameters.
@@ -27,23 +55,40 @@ pub fn paramgen_from_seed<Blob: AsRef<[u8]>>(
     seed: Blob,
     ciphersuite: Ciphersuite,
     n: usize,
-) -> Result<(ProverParams, VerifierParams), String> {
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    paramgen_from_seed_generic::<Bls12, Blob>(seed, ciphersuite, n)
+}
+
+/// Generate a set of parameters over the pairing engine E
+/// from a seed and a ciphersuite ID.
+/// Returns an error is the seed is not long enough; or ciphersuite is not valid; or n is too large.
+/// This function shall only be used for testing purpose.
+pub fn paramgen_from_seed_generic<E: Engine, Blob: AsRef<[u8]>>(
+    seed: Blob,
+    ciphersuite: Ciphersuite,
+    n: usize,
+) -> Result<(GenericProverParams<E>, GenericVerifierParams<E>), PointproofsError>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+    E::Fqk: SerDes,
+{
     // check the length of the seed
     if seed.as_ref().len() < 32 {
-        return Err(ERR_SEED_TOO_SHORT.to_owned());
+        return Err(PointproofsError::SeedTooShort(seed.as_ref().len()));
     }
 
     // checks the validity of the inputs
     if !check_ciphersuite(ciphersuite) {
-        return Err(ERR_CIPHERSUITE.to_owned());
+        return Err(PointproofsError::InvalidCiphersuite(ciphersuite));
     }
-    if n > 65536 {
-        return Err(ERR_MAX_N.to_owned());
+    if n > MAX_N {
+        return Err(PointproofsError::InvalidN { n, max: MAX_N });
     }
 
     // invoke the internal parameter generation function
     Ok(paramgen_from_alpha(
-        &hash_to_field_pointproofs(&seed),
+        &hash_seed_to_field_pointproofs::<E::Fr, _>(ciphersuite, &seed),
         ciphersuite,
         n,
     ))
@@ -52,11 +97,16 @@ pub fn paramgen_from_seed<Blob: AsRef<[u8]>>(
 /// Internal logic for parameter generation.
 /// Will always succeed.
 /// Will not be called outside this module.
-fn paramgen_from_alpha(
-    alpha: &Fr,
+fn paramgen_from_alpha<E: Engine>(
+    alpha: &E::Fr,
     ciphersuite: Ciphersuite,
     n: usize,
-) -> (ProverParams, VerifierParams) {
+) -> (GenericProverParams<E>, GenericVerifierParams<E>)
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+    E::Fqk: SerDes,
+{
     #[cfg(not(debug_assertions))]
     println!(
         "\n\n\nWarning!!! \nWarning!!! \nWarning!!! \nWarning!!! \n\
@@ -65,163 +115,430 @@ fn paramgen_from_alpha(
         the security of the public parameters.\n\
         End of warning.\n\n"
     );
+    let (mut pp, mut vp) = dispatch_groups!(group_orientation(ciphersuite), G => {
+        paramgen_from_alpha_helper::<E, G>(alpha, ciphersuite, n)
+    });
+    set_params_id(&mut pp, &mut vp);
+    (pp, vp)
+}
+
+/// Generates the parameters within the groups of a given orientation.
+fn paramgen_from_alpha_helper<E: Engine, G: PointproofsGroups<E>>(
+    alpha: &E::Fr,
+    ciphersuite: Ciphersuite,
+    n: usize,
+) -> (GenericProverParams<E>, GenericVerifierParams<E>) {
     let mut g1_vec = Vec::with_capacity(2 * n);
     // prover vector at index i-1 contains g1^{alpha^i} for i ranging from 1 to 2n
     // except that at index i, prover vector contains nothing useful
     // (we'll use G1::one as a placeholder in order to maintain the indexing)
     let mut g2_vec = Vec::with_capacity(n);
     // verifier vector at index i-1 contains g2^{alpha^i} for i ranging from 1 to n
-    let mut alpha_power = Fr::one();
+    let mut alpha_power = E::Fr::one();
     for _ in 0..n {
         alpha_power.mul_assign(&alpha); // compute alpha^i
-        g1_vec.push(PointproofsG1Affine::one().mul(alpha_power).into_affine());
-        g2_vec.push(PointproofsG2Affine::one().mul(alpha_power).into_affine());
+        g1_vec.push(G::G1Affine::one().mul(alpha_power).into_affine());
+        g2_vec.push(G::G2Affine::one().mul(alpha_power).into_affine());
     }
 
     // skip g1^{alpha^{n+1}}
     alpha_power.mul_assign(&alpha);
-    g1_vec.push(PointproofsG1::zero().into_affine()); // this 0 is important -- without it, prove will not work correctly
+    g1_vec.push(G::G1::zero().into_affine()); // this 0 is important -- without it, prove will not work correctly
 
     // Now do the rest of the prover
     for _ in n..2 * n - 1 {
         alpha_power.mul_assign(&alpha); // compute alpha^i
-        g1_vec.push(PointproofsG1Affine::one().mul(alpha_power).into_affine());
+        g1_vec.push(G::G1Affine::one().mul(alpha_power).into_affine());
     }
 
     // verifier also gets gt^{alpha^{n+1}} in the target group
-    let gt = pointproofs_pairing(g1_vec[0], g2_vec[n - 1]);
+    let gt = G::pairing(g1_vec[0], g2_vec[n - 1]);
 
     (
-        ProverParams {
+        GenericProverParams {
             ciphersuite,
             n,
-            generators: g1_vec,
-            pp_len: 0,
-            precomp: Vec::with_capacity(0),
+            generators: G::wrap_g1_vec(g1_vec),
+            precomputation: Precomputation::None,
+            precomp_range: 0..0,
+            precomp: G::wrap_g1_vec(Vec::with_capacity(0)),
+            precomp_threshold: DEFAULT_PRECOMP_THRESHOLD,
+            // set by paramgen_from_alpha
+            params_id: None,
+            verifier_digest: [0u8; 32],
         },
-        VerifierParams {
+        GenericVerifierParams {
             ciphersuite,
             n,
-            generators: g2_vec,
-            pp_len: 0,
-            precomp: Vec::with_capacity(0),
+            generators: G::wrap_g2_vec(g2_vec),
+            precomputation: Precomputation::None,
+            precomp_range: 0..0,
+            precomp: G::wrap_g2_vec(Vec::with_capacity(0)),
+            precomp_threshold: DEFAULT_PRECOMP_THRESHOLD,
             gt_elt: gt,
+            params_id: None,
+            prover_digest: [0u8; 32],
         },
     )
 }
 
-impl VerifierParams {
+impl<E: Engine> GenericVerifierParams<E> {
     /// pre-process the public parameters with precomputation value set to 3
     pub fn precomp_3(&mut self) {
-        self.precomp = vec![PointproofsG2Affine::zero(); 3 * self.n];
-        for i in 0..self.n {
-            self.generators[i].precomp_3(&mut self.precomp[i * 3..(i + 1) * 3]);
-        }
-        self.pp_len = self.n * 3;
+        self.precompute_tables(Precomputation::Window3, 0..self.n);
     }
 
     /// pre-process the public parameters with precomputation value set to 256
     pub fn precomp_256(&mut self) {
-        self.precomp = vec![PointproofsG2Affine::zero(); 256 * self.n];
-        for i in 0..self.n {
-            self.generators[i].precomp_256(&mut self.precomp[i * 256..(i + 1) * 256]);
-        }
-        self.pp_len = self.n * 256;
+        self.precompute_tables(Precomputation::Window256, 0..self.n);
+    }
+
+    /// pre-process the public parameters with fixed-base tables
+    /// of `window_bits`-bit windows, i.e., `Precomputation::Custom(window_bits)`.
+    /// Larger windows are faster, and take more memory.
+    /// Returns an error if the window is not within [1, 16] bits.
+    pub fn precompute(&mut self, window_bits: usize) -> Result<(), PointproofsError> {
+        self.set_precomputation(Precomputation::Custom(window_bits))
+    }
+
+    /// pre-process the public parameters with the given precomputation;
+    /// `Precomputation::None` drops the pre-computed data.
+    /// Returns an error if the window of a custom precomputation is invalid.
+    pub fn set_precomputation(
+        &mut self,
+        precomputation: Precomputation,
+    ) -> Result<(), PointproofsError> {
+        precomp::check_precomputation(precomputation)?;
+        self.precompute_tables(precomputation, 0..self.n);
+        Ok(())
+    }
+
+    /// pre-process the public parameters with the given precomputation,
+    /// using at most `max_bytes` bytes of memory for the tables.
+    /// If the budget does not allow tables for all generators,
+    /// tables are computed for as many generators as it allows;
+    /// the other generators are used without pre-computed data.
+    /// The tables are for the first generators:
+    /// all verifier generators are used equally often.
+    /// Returns the number of generators with tables,
+    /// or an error if the window of a custom precomputation is invalid.
+    /// Parameters with tables for part of the generators cannot be serialized.
+    pub fn precompute_with_budget(
+        &mut self,
+        precomputation: Precomputation,
+        max_bytes: usize,
+    ) -> Result<usize, PointproofsError> {
+        precomp::check_precomputation(precomputation)?;
+        let point_size = dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            std::mem::size_of::<<G as PointproofsGroups<E>>::G2Affine>()
+        });
+        let table_size = point_size * precomp::points_per_generator::<E::Fr>(precomputation);
+        let count = match table_size {
+            0 => self.n,
+            _ => std::cmp::min(max_bytes / table_size, self.n),
+        };
+        self.precompute_tables(precomputation, 0..count);
+        Ok(self.precomp_range.len())
+    }
+
+    /// Returns the pre-computed data held by the parameters.
+    pub fn precomputation(&self) -> Precomputation {
+        self.precomputation
+    }
+
+    /// Returns the number of generators with pre-computed tables.
+    pub fn precomputed_generators(&self) -> usize {
+        self.precomp_range.len()
+    }
+
+    /// Sets the largest number of generators for which multi-exponentiations
+    /// use the pre-computed data; the default is `DEFAULT_PRECOMP_THRESHOLD`.
+    /// This does not change the results, only the running time.
+    pub fn set_precomp_threshold(&mut self, threshold: usize) {
+        self.precomp_threshold = threshold;
+    }
+
+    /// Replaces the pre-computed data with the tables of `precomputation`
+    /// for the generators in `covered`.
+    fn precompute_tables(&mut self, precomputation: Precomputation, covered: Range<usize>) {
+        let covered = match precomputation {
+            Precomputation::None => 0..0,
+            _ => covered,
+        };
+        self.precomp = dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            G::wrap_g2_vec(precomp::precompute_tables(
+                G::g2_slice(&self.generators),
+                precomputation,
+                covered.clone(),
+            ))
+        });
+        self.precomputation = precomputation;
+        self.precomp_range = covered;
     }
 }
 
-impl ProverParams {
+impl<E: Engine> GenericProverParams<E> {
     /// pre-process the public parameters with precomputation value set to 3
     pub fn precomp_3(&mut self) {
-        let twice_n = self.generators.len();
-        self.precomp = vec![PointproofsG1Affine::zero(); 3 * twice_n];
-        for i in 0..twice_n {
-            self.generators[i].precomp_3(&mut self.precomp[i * 3..(i + 1) * 3]);
-        }
-        self.pp_len = self.n * 6;
+        self.precompute_tables(Precomputation::Window3, 0..2 * self.n);
     }
 
     /// pre-process the public parameters with precomputation value set to 256
     pub fn precomp_256(&mut self) {
-        let twice_n = self.generators.len();
-        self.precomp = vec![PointproofsG1Affine::zero(); 256 * twice_n];
-        for i in 0..twice_n {
-            self.generators[i].precomp_256(&mut self.precomp[i * 256..(i + 1) * 256]);
-        }
-        self.pp_len = self.n * 512;
+        self.precompute_tables(Precomputation::Window256, 0..2 * self.n);
+    }
+
+    /// pre-process the public parameters with fixed-base tables
+    /// of `window_bits`-bit windows, i.e., `Precomputation::Custom(window_bits)`.
+    /// Larger windows are faster, and take more memory.
+    /// Returns an error if the window is not within [1, 16] bits.
+    pub fn precompute(&mut self, window_bits: usize) -> Result<(), PointproofsError> {
+        self.set_precomputation(Precomputation::Custom(window_bits))
+    }
+
+    /// pre-process the public parameters with the given precomputation;
+    /// `Precomputation::None` drops the pre-computed data.
+    /// Returns an error if the window of a custom precomputation is invalid.
+    pub fn set_precomputation(
+        &mut self,
+        precomputation: Precomputation,
+    ) -> Result<(), PointproofsError> {
+        precomp::check_precomputation(precomputation)?;
+        self.precompute_tables(precomputation, 0..2 * self.n);
+        Ok(())
+    }
+
+    /// pre-process the public parameters with the given precomputation,
+    /// using at most `max_bytes` bytes of memory for the tables.
+    /// If the budget does not allow tables for all generators,
+    /// tables are computed for as many generators as it allows;
+    /// the other generators are used without pre-computed data.
+    /// The tables are for the generators that are used by the most proofs,
+    /// i.e., the generators around generators[n].
+    /// Returns the number of generators with tables,
+    /// or an error if the window of a custom precomputation is invalid.
+    /// Parameters with tables for part of the generators cannot be serialized.
+    pub fn precompute_with_budget(
+        &mut self,
+        precomputation: Precomputation,
+        max_bytes: usize,
+    ) -> Result<usize, PointproofsError> {
+        precomp::check_precomputation(precomputation)?;
+        let point_size = dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            std::mem::size_of::<<G as PointproofsGroups<E>>::G1Affine>()
+        });
+        let table_size = point_size * precomp::points_per_generator::<E::Fr>(precomputation);
+        let count = match table_size {
+            0 => 2 * self.n,
+            _ => std::cmp::min(max_bytes / table_size, 2 * self.n),
+        };
+        self.precompute_tables(precomputation, precomp::pp_budget_range(self.n, count));
+        Ok(self.precomp_range.len())
+    }
+
+    /// Returns the pre-computed data held by the parameters.
+    pub fn precomputation(&self) -> Precomputation {
+        self.precomputation
+    }
+
+    /// Returns the number of generators with pre-computed tables.
+    pub fn precomputed_generators(&self) -> usize {
+        self.precomp_range.len()
+    }
+
+    /// Sets the largest number of generators for which multi-exponentiations
+    /// use the pre-computed data; the default is `DEFAULT_PRECOMP_THRESHOLD`.
+    /// This does not change the results, only the running time.
+    pub fn set_precomp_threshold(&mut self, threshold: usize) {
+        self.precomp_threshold = threshold;
+    }
+
+    /// Replaces the pre-computed data with the tables of `precomputation`
+    /// for the generators in `covered`.
+    fn precompute_tables(&mut self, precomputation: Precomputation, covered: Range<usize>) {
+        let covered = match precomputation {
+            Precomputation::None => 0..0,
+            _ => covered,
+        };
+        self.precomp = dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            G::wrap_g1_vec(precomp::precompute_tables(
+                G::g1_slice(&self.generators),
+                precomputation,
+                covered.clone(),
+            ))
+        });
+        self.precomputation = precomputation;
+        self.precomp_range = covered;
     }
 
     /// check if the parameters are correct -- should not be used other than testing
     #[cfg(test)]
-    pub(crate) fn check_parameters(&self, vp: &VerifierParams) -> bool {
+    pub(crate) fn check_parameters(&self, vp: &GenericVerifierParams<E>) -> bool {
         if self.n != vp.n || self.ciphersuite != vp.ciphersuite {
             return false;
         }
+        dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            check_parameters_helper::<E, G>(self, vp)
+        })
+    }
+}
 
-        // prover_params.generators[i] should contain the generator of the G1 group raised to the power alpha^{i+1},
-        // except prover_params.generators[n] will contain nothing useful.
-        // verifier_params.generators[j] should contain the generator of the G2 group raised to the power alpha^{j+1}.
-        // gt should contain the generator of the target group raised to the power alpha^{n+1}.
-
-        let mut dh_values = Vec::with_capacity(3 * self.n);
-        // If all is correct, then
-        // dh_values[i] will contains the generator of the target group raised to the power alpha^{i+1}
-        // We will test all possible pairing of the two arrays with each other and with the generators
-        // of the two groups, and see if they all match as appropriate.
-
-        for i in 0..self.n {
-            dh_values.push(pointproofs_pairing(
-                self.generators[i],
-                PointproofsG2Affine::one(),
-            ));
-        }
-        dh_values.push(vp.gt_elt);
-        for i in self.n + 1..2 * self.n {
-            dh_values.push(pointproofs_pairing(
-                self.generators[i],
-                PointproofsG2Affine::one(),
-            ));
-        }
-        for i in 0..self.n {
-            dh_values.push(pointproofs_pairing(
-                self.generators[2 * self.n - 1],
-                vp.generators[i],
-            ));
-        }
+/// check if the parameters are correct within the groups of a given orientation
+#[cfg(test)]
+fn check_parameters_helper<E: Engine, G: PointproofsGroups<E>>(
+    pp: &GenericProverParams<E>,
+    vp: &GenericVerifierParams<E>,
+) -> bool {
+    let pp_generators = G::g1_slice(&pp.generators);
+    let vp_generators = G::g2_slice(&vp.generators);
+
+    // prover_params.generators[i] should contain the generator of the G1 group raised to the power alpha^{i+1},
+    // except prover_params.generators[n] will contain nothing useful.
+    // verifier_params.generators[j] should contain the generator of the G2 group raised to the power alpha^{j+1}.
+    // gt should contain the generator of the target group raised to the power alpha^{n+1}.
+
+    let mut dh_values = Vec::with_capacity(3 * pp.n);
+    // If all is correct, then
+    // dh_values[i] will contains the generator of the target group raised to the power alpha^{i+1}
+    // We will test all possible pairing of the two arrays with each other and with the generators
+    // of the two groups, and see if they all match as appropriate.
+
+    for i in 0..pp.n {
+        dh_values.push(G::pairing(pp_generators[i], G::G2Affine::one()));
+    }
+    dh_values.push(vp.gt_elt);
+    for i in pp.n + 1..2 * pp.n {
+        dh_values.push(G::pairing(pp_generators[i], G::G2Affine::one()));
+    }
+    for i in 0..pp.n {
+        dh_values.push(G::pairing(pp_generators[2 * pp.n - 1], vp_generators[i]));
+    }
 
-        for (i, e) in dh_values.iter().enumerate().take(self.n) {
-            if e != &pointproofs_pairing(PointproofsG1Affine::one(), vp.generators[i]) {
-                return false;
-            };
-        }
+    for (i, e) in dh_values.iter().enumerate().take(pp.n) {
+        if e != &G::pairing(G::G1Affine::one(), vp_generators[i]) {
+            return false;
+        };
+    }
 
-        for i in 0..2 * self.n {
-            if i != self.n {
-                for j in 0..self.n {
-                    if dh_values[i + j + 1]
-                        != pointproofs_pairing(self.generators[i], vp.generators[j])
-                    {
-                        return false;
-                    };
-                }
+    for i in 0..2 * pp.n {
+        if i != pp.n {
+            for j in 0..pp.n {
+                if dh_values[i + j + 1] != G::pairing(pp_generators[i], vp_generators[j]) {
+                    return false;
+                };
             }
         }
-        true
     }
+    true
+}
+
+/// Checks that the prover and verifier parameters are consistent,
+/// with random linear combinations of the relations checked by `check_parameters`.
+///     * input: the prover and verifier parameters
+///     * input: a source of randomness for the coefficients
+///     * output: whether the parameters hold the powers of the same alpha
+/// Inconsistent parameters pass the check with probability at most 2^{-127};
+/// the cost is a constant number of pairings and O(n) exponentiations.
+pub fn check_consistency<E: Engine, R: RngCore + ?Sized>(
+    pp: &GenericProverParams<E>,
+    vp: &GenericVerifierParams<E>,
+    rng: &mut R,
+) -> bool {
+    if pp.n != vp.n || pp.ciphersuite != vp.ciphersuite || !check_ciphersuite(pp.ciphersuite) {
+        return false;
+    }
+    dispatch_groups!(group_orientation(pp.ciphersuite), G => {
+        check_consistency_helper::<E, G, R>(pp, vp, rng)
+    })
+}
+
+/// check if the parameters are consistent within the groups of a given orientation
+fn check_consistency_helper<E: Engine, G: PointproofsGroups<E>, R: RngCore + ?Sized>(
+    pp: &GenericProverParams<E>,
+    vp: &GenericVerifierParams<E>,
+    rng: &mut R,
+) -> bool {
+    let n = pp.n;
+    let pp_generators = G::g1_slice(&pp.generators);
+    let vp_generators = G::g2_slice(&vp.generators);
+    if n == 0 || pp_generators.len() != 2 * n || vp_generators.len() != n {
+        return false;
+    }
+    // pp_generators[n] is the identity, in place of the missing g1^{alpha^{n+1}}
+    if !pp_generators[n].is_zero() {
+        return false;
+    }
+    let g1_one = <G as PointproofsGroups<E>>::G1Affine::one();
+    let g2_one = <G as PointproofsGroups<E>>::G2Affine::one();
+
+    // pp_generators[0] and vp_generators[0] are g1^alpha and g2^alpha
+    if pp_generators[0].is_zero()
+        || G::pairing(pp_generators[0], g2_one) != G::pairing(g1_one, vp_generators[0])
+    {
+        return false;
+    }
+
+    // gt_elt = e(g1^alpha, g2^{alpha^n})
+    if vp.gt_elt != G::pairing(pp_generators[0], vp_generators[n - 1]) {
+        return false;
+    }
+    if n == 1 {
+        return true;
+    }
+
+    // pp_generators[i] = pp_generators[i-1]^alpha for i in [1, n) and [n+2, 2n),
+    // and pp_generators[n+1] = pp_generators[n-1]^{alpha^2} across the missing alpha^{n+1}:
+    // e(\sum r_i pp_generators[i], g2) =
+    //     e(\sum r_i pp_generators[i-1], g2^alpha) * e(pp_generators[n-1]^{r_n}, g2^{alpha^2})
+    let positions: Vec<usize> = (1..n).chain(n + 2..2 * n).collect();
+    let coeffs: Vec<[u64; 4]> = (0..positions.len() + 1)
+        .map(|_| random_coefficient(rng))
+        .collect();
+    let coeffs_ref: Vec<&[u64; 4]> = coeffs.iter().collect();
+    let (chain_coeffs, hole_coeff) = coeffs_ref.split_at(positions.len());
+
+    let lhs_bases: Vec<_> = positions
+        .iter()
+        .map(|&i| pp_generators[i])
+        .chain(std::iter::once(pp_generators[n + 1]))
+        .collect();
+    let rhs_bases: Vec<_> = positions.iter().map(|&i| pp_generators[i - 1]).collect();
+    let lhs = sum_of_prod(&lhs_bases, &coeffs_ref).into_affine();
+    let rhs = sum_of_prod(&rhs_bases, chain_coeffs).into_affine();
+    let rhs_hole = sum_of_prod(&pp_generators[n - 1..n], hole_coeff).into_affine();
+    if G::pairing(lhs, g2_one)
+        != G::pairing_product(rhs, vp_generators[0], rhs_hole, vp_generators[1])
+    {
+        return false;
+    }
+
+    // vp_generators[j] = vp_generators[j-1]^alpha for j in [1, n):
+    // e(g1, \sum s_j vp_generators[j]) = e(g1^alpha, \sum s_j vp_generators[j-1])
+    let coeffs: Vec<[u64; 4]> = (1..n).map(|_| random_coefficient(rng)).collect();
+    let coeffs_ref: Vec<&[u64; 4]> = coeffs.iter().collect();
+    let lhs = sum_of_prod(&vp_generators[1..n], &coeffs_ref).into_affine();
+    let rhs = sum_of_prod(&vp_generators[0..n - 1], &coeffs_ref).into_affine();
+    G::pairing(g1_one, lhs) == G::pairing(pp_generators[0], rhs)
 }
 
-impl std::cmp::PartialEq for ProverParams {
+/// A uniformly random 128 bits coefficient.
+fn random_coefficient<R: RngCore + ?Sized>(rng: &mut R) -> [u64; 4] {
+    [rng.next_u64(), rng.next_u64(), 0, 0]
+}
+
+impl<E: Engine> std::cmp::PartialEq for GenericProverParams<E> {
     /// Convenient function to compare secret key objects
     fn eq(&self, other: &Self) -> bool {
         self.ciphersuite == other.ciphersuite
             && self.n == other.n
             && self.generators == other.generators
-            && self.pp_len == other.pp_len
+            && self.precomputation == other.precomputation
+            && self.precomp_range == other.precomp_range
             && self.precomp == other.precomp
     }
 }
 
-impl std::cmp::PartialEq for VerifierParams {
+impl<E: Engine> std::cmp::PartialEq for GenericVerifierParams<E> {
     /// Convenient function to compare secret key objects
     fn eq(&self, other: &Self) -> bool {
         self.ciphersuite == other.ciphersuite
@@ -294,66 +611,39 @@ impl std::cmp::PartialEq for VerifierParams {
 //     /// e(g2,g1)^{alpha^{N+1}}
 //     pub gt_alpha_nplus1: Fq12,
 // }
+//
+// The CRS does not carry a ciphersuite id;
+// the caller selects the ciphersuite the parameters will be used with.
+// The CRS is over BLS12-381.
 pub fn read_param<R: std::io::Read>(
     reader: &mut R,
-) -> Result<(ProverParams, VerifierParams), String> {
+    ciphersuite: Ciphersuite,
+) -> Result<(ProverParams, VerifierParams), PointproofsError> {
+    if !check_ciphersuite(ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite(ciphersuite));
+    }
+
     let param = match pointproofs_paramgen::PointproofsParams::deserialize(reader, true) {
-        Err(e) => return Err(format!("read_param: {}", e.to_string())),
+        Err(e) => {
+            return Err(PointproofsError::MalformedEncoding(format!(
+                "read_param: {}",
+                e
+            )))
+        }
         Ok(p) => p,
     };
 
     if !pointproofs_paramgen::consistent(&param) {
-        return Err("Input params are not consistent".to_owned());
+        return Err(PointproofsError::InconsistentParams);
     };
 
-    // groups are NOT switched
-    #[cfg(not(feature = "group_switched"))]
-    let pp = ProverParams {
-        ciphersuite: 0,
+    Ok(CrsPowers {
         n: param.n,
-        generators: [
-            param.g1_alpha_1_to_n,
-            vec![PointproofsG1::zero().into_affine()],
-            param.g1_alpha_nplus2_to_2n,
-        ]
-        .concat(),
-        pp_len: 0,
-        precomp: vec![],
-    };
-
-    #[cfg(not(feature = "group_switched"))]
-    let vp = VerifierParams {
-        ciphersuite: 0,
-        n: param.n,
-        generators: param.g2_alpha_1_to_n,
-        pp_len: 0,
-        precomp: vec![],
-        gt_elt: param.gt_alpha_nplus1,
-    };
-
-    // groups switched
-    #[cfg(feature = "group_switched")]
-    let pp = ProverParams {
-        ciphersuite: 0,
-        n: param.n,
-        generators: [
-            param.g2_alpha_1_to_n,
-            vec![PointproofsG1::zero().into_affine()],
-            param.g2_alpha_nplus2_to_2n,
-        ]
-        .concat(),
-        pp_len: 0,
-        precomp: vec![],
-    };
-    #[cfg(feature = "group_switched")]
-    let vp = VerifierParams {
-        ciphersuite: 0,
-        n: param.n,
-        generators: param.g1_alpha_1_to_n,
-        pp_len: 0,
-        precomp: vec![],
-        gt_elt: param.gt_alpha_nplus1,
-    };
-
-    Ok((pp, vp))
+        g1_alpha_1_to_n: param.g1_alpha_1_to_n,
+        g1_alpha_nplus2_to_2n: param.g1_alpha_nplus2_to_2n,
+        g2_alpha_1_to_n: param.g2_alpha_1_to_n,
+        g2_alpha_nplus2_to_2n: param.g2_alpha_nplus2_to_2n,
+        gt_alpha_nplus1: param.gt_alpha_nplus1,
+    }
+    .into_params(ciphersuite))
 }
@@ -0,0 +1,308 @@
+//! this file is part of the pointproofs.
+//! It defines the fingerprint of a set of parameters, `params_id`,
+//! which ties prover parameters, verifier parameters and commitments
+//! to the setup they come from.
+//!
+//! The fingerprint is
+//!     `SHA-256(PARAMS_ID_DST | ciphersuite id | n: u64 | prover digest | verifier digest)`
+//! where the digests are the hashes of the two halves of the parameters:
+//!     `prover digest = SHA-256(prover generators)`,
+//!     `verifier digest = SHA-256(verifier generators | gt_element)`,
+//! with the group elements in their compressed encoding, in increasing powers of alpha,
+//! and without the unused g^{alpha^{n+1}} of the prover generators.
+//!
+//! Each half of the parameters recomputes its own digest, and carries the digest
+//! of the other half: a `params_id` that does not match the generators is detected
+//! when the parameters are read, while the digest of the other half is trusted.
+//! Parameters read in the headerless format do not carry the digest of the other half:
+//! they have no `params_id` until they are linked with `link_params_id`.
+//! In serialized parameters, the `params_id` and the digest of such parameters are zeros.
+
+use pairing::serdes::SerDes;
+use pairing::Engine;
+use pairings::param::*;
+use pairings::pointproofs_groups::*;
+use pairings::serdes::{read_prover_params, read_verifier_params};
+use pairings::validate::io_error;
+use pairings::*;
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+
+/// The fingerprint of a set of parameters.
+pub type ParamsId = [u8; 32];
+
+/// Domain separation tag of `params_id`.
+const PARAMS_ID_DST: &[u8] = b"POINTPROOFS_PARAMS_ID_V1_";
+
+/// The `params_id` and the digest of parameters that are not linked, once serialized.
+const UNLINKED: ParamsId = [0u8; 32];
+
+/// Encodes the `params_id` of parameters, or `UNLINKED`.
+pub(crate) fn encode_params_id(params_id: Option<ParamsId>) -> ParamsId {
+    params_id.unwrap_or(UNLINKED)
+}
+
+/// Decodes the `params_id` of serialized parameters, if they are linked.
+pub(crate) fn decode_params_id(params_id: ParamsId) -> Option<ParamsId> {
+    if params_id == UNLINKED {
+        None
+    } else {
+        Some(params_id)
+    }
+}
+
+/// Finishes the hash of a digest or of a `params_id`.
+pub(crate) fn digest_result(hasher: Sha256) -> ParamsId {
+    let mut id = [0u8; 32];
+    id.copy_from_slice(&hasher.result());
+    id
+}
+
+/// Computes the `params_id` from the digests of the prover and the verifier parameters.
+pub(crate) fn compute_params_id(
+    ciphersuite: Ciphersuite,
+    n: usize,
+    prover_digest: &ParamsId,
+    verifier_digest: &ParamsId,
+) -> ParamsId {
+    let mut hasher = Sha256::new();
+    hasher.input(PARAMS_ID_DST);
+    hasher.input([ciphersuite]);
+    hasher.input((n as u64).to_le_bytes());
+    hasher.input(prover_digest);
+    hasher.input(verifier_digest);
+    digest_result(hasher)
+}
+
+/// Computes the digest of the prover generators, i.e., of all but `generators[n]`.
+pub(crate) fn prover_digest<E: Engine>(n: usize, generators: &PointproofsG1Vec<E>) -> ParamsId
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+{
+    let mut hasher = Sha256::new();
+    match generators {
+        PointproofsG1Vec::Standard(g) => {
+            hash_points(&mut hasher, &g[..n]);
+            hash_points(&mut hasher, &g[n + 1..]);
+        }
+        PointproofsG1Vec::Switched(g) => {
+            hash_points(&mut hasher, &g[..n]);
+            hash_points(&mut hasher, &g[n + 1..]);
+        }
+    }
+    digest_result(hasher)
+}
+
+/// Computes the digest of the verifier generators and of the target group element.
+pub(crate) fn verifier_digest<E: Engine>(
+    generators: &PointproofsG2Vec<E>,
+    gt_elt: &E::Fqk,
+) -> ParamsId
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+    E::Fqk: SerDes,
+{
+    let mut hasher = Sha256::new();
+    match generators {
+        PointproofsG2Vec::Standard(g) => hash_points(&mut hasher, g),
+        PointproofsG2Vec::Switched(g) => hash_points(&mut hasher, g),
+    }
+    hash_points(&mut hasher, &[*gt_elt]);
+    digest_result(hasher)
+}
+
+/// Sets the `params_id` of prover and verifier parameters of a same setup,
+/// and the digest each of them carries of the other.
+pub(crate) fn set_params_id<E: Engine>(
+    pp: &mut GenericProverParams<E>,
+    vp: &mut GenericVerifierParams<E>,
+) where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+    E::Fqk: SerDes,
+{
+    let prover_digest = prover_digest(pp.n, &pp.generators);
+    let verifier_digest = verifier_digest(&vp.generators, &vp.gt_elt);
+    let params_id = compute_params_id(pp.ciphersuite, pp.n, &prover_digest, &verifier_digest);
+    pp.params_id = Some(params_id);
+    pp.verifier_digest = verifier_digest;
+    vp.params_id = Some(params_id);
+    vp.prover_digest = prover_digest;
+}
+
+/// Sets the `params_id` of prover and verifier parameters read in the headerless
+/// format, which does not carry the digest of the other half of the parameters:
+/// until then, they have no `params_id`.
+/// The parameters shall come from a same setup, which `check_consistency` checks.
+/// Returns an error if the ciphersuites or the dimensions differ.
+pub fn link_params_id<E: Engine>(
+    pp: &mut GenericProverParams<E>,
+    vp: &mut GenericVerifierParams<E>,
+) -> Result<(), PointproofsError>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+    E::Fqk: SerDes,
+{
+    if pp.ciphersuite != vp.ciphersuite {
+        return Err(PointproofsError::CiphersuiteMismatch {
+            expected: pp.ciphersuite,
+            found: vp.ciphersuite,
+        });
+    }
+    if pp.n != vp.n {
+        return Err(PointproofsError::InconsistentParams);
+    }
+    set_params_id(pp, vp);
+    Ok(())
+}
+
+/// Hashes the compressed encodings of `points`.
+pub(crate) fn hash_points<A: SerDes>(hasher: &mut Sha256, points: &[A]) {
+    let mut buf: Vec<u8> = vec![];
+    for point in points.iter() {
+        buf.clear();
+        // serializing into a vector does not fail
+        point
+            .serialize(&mut buf, true)
+            .expect("serialization into a vector");
+        hasher.input(&buf);
+    }
+}
+
+/// Checks that a commitment is not bound to other parameters than the ones of `params_id`;
+/// parameters that are not linked, i.e., without `params_id`, do not match a bound commitment.
+pub(crate) fn check_params_id<E: Engine>(
+    com: &GenericCommitment<E>,
+    params_id: Option<ParamsId>,
+) -> Result<(), PointproofsError> {
+    match com.params_id {
+        Some(id) if Some(id) != params_id => Err(PointproofsError::ParamsIdMismatch),
+        _ => Ok(()),
+    }
+}
+
+impl<E: Engine> GenericProverParams<E> {
+    /// The fingerprint of the parameters: a hash of the generators of both
+    /// the prover and the verifier parameters, and of the target group element.
+    /// Prover and verifier parameters from a same setup have the same `params_id`.
+    /// Returns None for parameters read in the headerless format, until they are
+    /// linked with `link_params_id`.
+    pub fn params_id(&self) -> Option<ParamsId> {
+        self.params_id
+    }
+
+    /// Deserializes parameters as `deserialize`, and checks their `params_id`;
+    /// the `params_id` of a container is checked before the generators are decoded.
+    /// Returns `PointproofsError::ParamsIdMismatch` if the parameters come from another setup,
+    /// or have no `params_id`, e.g., were written in the headerless format.
+    pub fn deserialize_matching<R: Read>(
+        reader: &mut R,
+        compressed: bool,
+        params_id: &ParamsId,
+    ) -> Result<Self, PointproofsError>
+    where
+        E::G1Affine: SerDes,
+        E::G2Affine: SerDes,
+    {
+        read_prover_params(reader, compressed, Some(params_id)).map_err(io_error)
+    }
+}
+
+impl<E: Engine> GenericVerifierParams<E> {
+    /// The fingerprint of the parameters: a hash of the generators of both
+    /// the prover and the verifier parameters, and of the target group element.
+    /// Prover and verifier parameters from a same setup have the same `params_id`.
+    /// Returns None for parameters read in the headerless format, until they are
+    /// linked with `link_params_id`.
+    pub fn params_id(&self) -> Option<ParamsId> {
+        self.params_id
+    }
+
+    /// Deserializes parameters as `deserialize`, and checks their `params_id`;
+    /// the `params_id` of a container is checked before the generators are decoded.
+    /// Returns `PointproofsError::ParamsIdMismatch` if the parameters come from another setup,
+    /// or have no `params_id`, e.g., were written in the headerless format.
+    pub fn deserialize_matching<R: Read>(
+        reader: &mut R,
+        compressed: bool,
+        params_id: &ParamsId,
+    ) -> Result<Self, PointproofsError>
+    where
+        E::G1Affine: SerDes,
+        E::G2Affine: SerDes,
+        E::Fqk: SerDes,
+    {
+        read_verifier_params(reader, compressed, Some(params_id)).map_err(io_error)
+    }
+}
+
+impl<E: Engine> GenericCommitment<E> {
+    /// The fingerprint of the parameters the commitment is bound to, if any.
+    pub fn params_id(&self) -> Option<ParamsId> {
+        self.params_id
+    }
+
+    /// Binds the commitment to the parameters with fingerprint `params_id`.
+    /// Verifying or updating a bound commitment with other parameters fails
+    /// with `PointproofsError::ParamsIdMismatch`, rather than rejecting the proofs.
+    pub fn bind_params_id(&mut self, params_id: ParamsId) {
+        self.params_id = Some(params_id);
+    }
+
+    /// Convert a commitment and its binding into a blob:
+    ///
+    /// `| commitment | 0 |` or `| commitment | 1 | params_id |` => bytes
+    ///
+    /// where `commitment` is the output of `serialize`.
+    /// Returns an error if ciphersuite id is invalid or serialization fails.
+    pub fn serialize_with_params_id<W: Write>(
+        &self,
+        writer: &mut W,
+        compressed: bool,
+    ) -> std::io::Result<()>
+    where
+        Self: SerDes,
+    {
+        self.serialize(writer, compressed)?;
+        match self.params_id {
+            Some(id) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&id)
+            }
+            None => writer.write_all(&[0]),
+        }
+    }
+
+    /// Convert a blob of `serialize_with_params_id` into a commitment.
+    /// Returns an error if deserialization fails.
+    pub fn deserialize_with_params_id<R: Read>(
+        reader: &mut R,
+        compressed: bool,
+    ) -> std::io::Result<Self>
+    where
+        Self: SerDes,
+    {
+        let mut com = Self::deserialize(reader, compressed)?;
+        let mut flag = [0u8; 1];
+        reader.read_exact(&mut flag)?;
+        match flag[0] {
+            0 => (),
+            1 => {
+                let mut id = [0u8; 32];
+                reader.read_exact(&mut id)?;
+                com.params_id = Some(id);
+            }
+            _ => {
+                return Err(PointproofsError::MalformedEncoding(format!(
+                    "invalid params_id flag: {}",
+                    flag[0]
+                ))
+                .into())
+            }
+        }
+        Ok(com)
+    }
+}
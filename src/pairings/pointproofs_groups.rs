@@ -1,108 +1,439 @@
-use pairing::bls12_381::*;
-use pairing::Engine;
-
-// =========================
-// the groups are NOT switched
-/// A wrapper of BLS::G1. Groups are not switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub type PointproofsG1 = G1;
-/// A wrapper of BLS::G2. Groups are not switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub type PointproofsG2 = G2;
-/// A wrapper of BLS::G1Affine. Groups are not switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub type PointproofsG1Affine = G1Affine;
-/// A wrapper of BLS::G2Affine. Groups are not switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub type PointproofsG2Affine = G2Affine;
-
-#[cfg(not(feature = "group_switched"))]
-pub const POINTPROOFSG1_LEN: usize = 48;
-#[cfg(not(feature = "group_switched"))]
-pub const POINTPROOFSG2_LEN: usize = 96;
-
-/// A wrapper of BLS::pairing_product. Groups are switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub(crate) fn pointproofs_pairing(p1: PointproofsG1Affine, q1: PointproofsG2Affine) -> Fq12 {
-    Bls12::pairing(p1, q1)
-}
-/// A wrapper of BLS::pairing_product. Groups are switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub(crate) fn pointproofs_pairing_product(
-    p1: PointproofsG1Affine,
-    q1: PointproofsG2Affine,
-    p2: PointproofsG1Affine,
-    q2: PointproofsG2Affine,
-) -> Fq12 {
-    Bls12::pairing_product(p1, q1, p2, q2)
-}
-/// A wrapper of BLS::pairing_multi_product. Groups are switched and proof/commits are in BLS::G1
-#[cfg(not(feature = "group_switched"))]
-pub(crate) fn pointproofs_pairing_multi_product(
-    g1_vec: &[PointproofsG1Affine],
-    g2_vec: &[PointproofsG2Affine],
-) -> Fq12 {
-    Bls12::pairing_multi_product(g1_vec, g2_vec)
-}
-
-/// Size for serialized commitment.
-#[cfg(not(feature = "group_switched"))]
+//! this file is part of the pointproofs.
+//! It maps PointproofsG1/PointproofsG2 of the paper onto the groups
+//! of the pairing engine.
+//!
+//! The mapping is a runtime property, selected by the group orientation
+//! of the ciphersuite (see `ciphersuite` module):
+//!     * Standard: PointproofsG1 is E::G1 and PointproofsG2 is E::G2
+//!     * Switched: PointproofsG1 is E::G2 and PointproofsG2 is E::G1
+//! With switched groups almost everything is slower, except that
+//! the (batch) verification will be faster.
+//!
+//! The algorithms are written once, generic over `PointproofsGroups`,
+//! and `dispatch_groups!` selects the instantiation from the orientation.
+
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pairings::ciphersuite::GroupOrientation;
+use std::ops::Deref;
+#[cfg(feature = "mmap")]
+use std::sync::Arc;
+
+/// Size for serialized commitment over BLS12-381; groups are not switched.
 pub const COMMIT_LEN: usize = 49;
 
-/// Size for serialized proof.
-#[cfg(not(feature = "group_switched"))]
+/// Size for serialized proof over BLS12-381; groups are not switched.
 pub const PROOF_LEN: usize = 49;
 
-// =========================
-// the groups are switched
-
-/// A wrapper of BLS::G2. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub type PointproofsG1 = G2;
-/// A wrapper of BLS::G1. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub type PointproofsG2 = G1;
-/// A wrapper of BLS::G2Affine. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub type PointproofsG1Affine = G2Affine;
-/// A wrapper of BLS::G1Affine. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub type PointproofsG2Affine = G1Affine;
-
-#[cfg(feature = "group_switched")]
-pub const POINTPROOFSG1_LEN: usize = 96;
-#[cfg(feature = "group_switched")]
-pub const POINTPROOFSG2_LEN: usize = 48;
-
-/// A wrapper of BLS::pairing. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub(crate) fn pointproofs_pairing(p1: PointproofsG1Affine, q1: PointproofsG2Affine) -> Fq12 {
-    Bls12::pairing(q1, p1)
-}
-/// A wrapper of BLS::pairing_product. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub(crate) fn pointproofs_pairing_product(
-    p1: PointproofsG1Affine,
-    q1: PointproofsG2Affine,
-    p2: PointproofsG1Affine,
-    q2: PointproofsG2Affine,
-) -> Fq12 {
-    Bls12::pairing_product(q1, p1, q2, p2)
-}
-/// A wrapper of BLS::pairing_multi_product. Groups are switched and proof/commits are in BLS::G2
-#[cfg(feature = "group_switched")]
-pub(crate) fn pointproofs_pairing_multi_product(
-    g1_vec: &[PointproofsG1Affine],
-    g2_vec: &[PointproofsG2Affine],
-) -> Fq12 {
-    Bls12::pairing_multi_product(g2_vec, g1_vec)
-}
-
-
-/// Size for serialized commitment.
-#[cfg(feature = "group_switched")]
-pub const COMMIT_LEN: usize = 97;
-
-/// Size for serialized proof.
-#[cfg(feature = "group_switched")]
-pub const PROOF_LEN: usize = 97;
+/// Size for serialized commitment over BLS12-381; groups are switched.
+pub const SWITCHED_COMMIT_LEN: usize = 97;
+
+/// Size for serialized proof over BLS12-381; groups are switched.
+pub const SWITCHED_PROOF_LEN: usize = 97;
+
+/// Size for uncompressed serialized commitment over BLS12-381; groups are not switched.
+pub const UNCOMPRESSED_COMMIT_LEN: usize = 97;
+
+/// Size for uncompressed serialized proof over BLS12-381; groups are not switched.
+pub const UNCOMPRESSED_PROOF_LEN: usize = 97;
+
+/// Size for uncompressed serialized commitment over BLS12-381; groups are switched.
+pub const SWITCHED_UNCOMPRESSED_COMMIT_LEN: usize = 193;
+
+/// Size for uncompressed serialized proof over BLS12-381; groups are switched.
+pub const SWITCHED_UNCOMPRESSED_PROOF_LEN: usize = 193;
+
+impl GroupOrientation {
+    /// Size for a serialized PointproofsG1 element over BLS12-381.
+    pub fn pointproofs_g1_len(self) -> usize {
+        match self {
+            GroupOrientation::Standard => 48,
+            GroupOrientation::Switched => 96,
+        }
+    }
+
+    /// Size for a serialized PointproofsG2 element over BLS12-381.
+    pub fn pointproofs_g2_len(self) -> usize {
+        match self {
+            GroupOrientation::Standard => 96,
+            GroupOrientation::Switched => 48,
+        }
+    }
+
+    /// Size for an uncompressed serialized PointproofsG1 element over BLS12-381.
+    pub fn pointproofs_g1_uncompressed_len(self) -> usize {
+        match self {
+            GroupOrientation::Standard => 96,
+            GroupOrientation::Switched => 192,
+        }
+    }
+
+    /// Size for an uncompressed serialized PointproofsG2 element over BLS12-381.
+    pub fn pointproofs_g2_uncompressed_len(self) -> usize {
+        match self {
+            GroupOrientation::Standard => 192,
+            GroupOrientation::Switched => 96,
+        }
+    }
+
+    /// Size for serialized commitment over BLS12-381.
+    pub fn commit_len(self) -> usize {
+        match self {
+            GroupOrientation::Standard => COMMIT_LEN,
+            GroupOrientation::Switched => SWITCHED_COMMIT_LEN,
+        }
+    }
+
+    /// Size for serialized proof over BLS12-381.
+    pub fn proof_len(self) -> usize {
+        match self {
+            GroupOrientation::Standard => PROOF_LEN,
+            GroupOrientation::Switched => SWITCHED_PROOF_LEN,
+        }
+    }
+
+    /// Size for uncompressed serialized commitment over BLS12-381.
+    pub fn uncompressed_commit_len(self) -> usize {
+        match self {
+            GroupOrientation::Standard => UNCOMPRESSED_COMMIT_LEN,
+            GroupOrientation::Switched => SWITCHED_UNCOMPRESSED_COMMIT_LEN,
+        }
+    }
+
+    /// Size for uncompressed serialized proof over BLS12-381.
+    pub fn uncompressed_proof_len(self) -> usize {
+        match self {
+            GroupOrientation::Standard => UNCOMPRESSED_PROOF_LEN,
+            GroupOrientation::Switched => SWITCHED_UNCOMPRESSED_PROOF_LEN,
+        }
+    }
+}
+
+/// A PointproofsG1 element, i.e., a commitment or a proof.
+#[derive(Debug)]
+pub(crate) enum PointproofsG1<E: Engine> {
+    /// groups are not switched; the element is in E::G1
+    Standard(E::G1),
+    /// groups are switched; the element is in E::G2
+    Switched(E::G2),
+}
+
+/// The storage of a list of affine group elements: either owned,
+/// or a view into a memory-mapped file of prover parameters (see `mapped` module).
+#[derive(Clone)]
+pub(crate) enum Points<A> {
+    /// the elements are owned
+    Owned(Vec<A>),
+    /// `len` elements, in their in-memory layout, at `offset` of `map`
+    #[cfg(feature = "mmap")]
+    Mapped {
+        map: Arc<memmap2::Mmap>,
+        offset: usize,
+        len: usize,
+    },
+}
+
+impl<A> Deref for Points<A> {
+    type Target = [A];
+
+    fn deref(&self) -> &[A] {
+        match self {
+            Points::Owned(v) => v,
+            // the offset and the alignment of the view are checked
+            // when the file is mapped, see `ProverParams::map_file`
+            #[cfg(feature = "mmap")]
+            Points::Mapped { map, offset, len } => unsafe {
+                std::slice::from_raw_parts(map.as_ptr().add(*offset) as *const A, *len)
+            },
+        }
+    }
+}
+
+impl<A: std::fmt::Debug> std::fmt::Debug for Points<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A: PartialEq> PartialEq for Points<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+/// A list of PointproofsG1Affine elements, i.e., prover generators.
+#[derive(Clone, Debug)]
+pub(crate) enum PointproofsG1Vec<E: Engine> {
+    /// groups are not switched; the elements are in E::G1
+    Standard(Points<E::G1Affine>),
+    /// groups are switched; the elements are in E::G2
+    Switched(Points<E::G2Affine>),
+}
+
+/// A list of PointproofsG2Affine elements, i.e., verifier generators.
+#[derive(Clone, Debug)]
+pub(crate) enum PointproofsG2Vec<E: Engine> {
+    /// groups are not switched; the elements are in E::G2
+    Standard(Vec<E::G2Affine>),
+    /// groups are switched; the elements are in E::G1
+    Switched(Vec<E::G1Affine>),
+}
+
+// Engines are marker types that do not implement `Copy` or `PartialEq`,
+// so those traits are implemented by hand rather than derived.
+impl<E: Engine> Clone for PointproofsG1<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E: Engine> Copy for PointproofsG1<E> {}
+
+macro_rules! impl_orientation_eq {
+    ($name:ident) => {
+        impl<E: Engine> PartialEq for $name<E> {
+            fn eq(&self, other: &Self) -> bool {
+                match (self, other) {
+                    ($name::Standard(a), $name::Standard(b)) => a == b,
+                    ($name::Switched(a), $name::Switched(b)) => a == b,
+                    _ => false,
+                }
+            }
+        }
+    };
+}
+
+impl_orientation_eq!(PointproofsG1);
+impl_orientation_eq!(PointproofsG1Vec);
+impl_orientation_eq!(PointproofsG2Vec);
+
+impl<E: Engine> PointproofsG1Vec<E> {
+    /// An empty list of the given orientation.
+    pub(crate) fn empty(orientation: GroupOrientation) -> Self {
+        match orientation {
+            GroupOrientation::Standard => PointproofsG1Vec::Standard(Points::Owned(vec![])),
+            GroupOrientation::Switched => PointproofsG1Vec::Switched(Points::Owned(vec![])),
+        }
+    }
+
+    /// Number of elements in the list.
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            PointproofsG1Vec::Standard(v) => v.len(),
+            PointproofsG1Vec::Switched(v) => v.len(),
+        }
+    }
+}
+
+impl<E: Engine> PointproofsG2Vec<E> {
+    /// An empty list of the given orientation.
+    pub(crate) fn empty(orientation: GroupOrientation) -> Self {
+        match orientation {
+            GroupOrientation::Standard => PointproofsG2Vec::Standard(vec![]),
+            GroupOrientation::Switched => PointproofsG2Vec::Switched(vec![]),
+        }
+    }
+
+    /// Number of elements in the list.
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            PointproofsG2Vec::Standard(v) => v.len(),
+            PointproofsG2Vec::Switched(v) => v.len(),
+        }
+    }
+}
+
+/// The concrete groups of a group orientation, over the pairing engine E.
+///
+/// The accessors panic if the input is of the other orientation.
+/// This never happens for well formed inputs: the variant of every
+/// element is set from the ciphersuite id it is created with, and
+/// the APIs check that the ciphersuite ids of all inputs match
+/// before dispatching.
+pub(crate) trait PointproofsGroups<E: Engine> {
+    /// PointproofsG1, in which commitments and proofs live
+    type G1: CurveProjective<Affine = Self::G1Affine, Scalar = E::Fr>;
+    /// PointproofsG1Affine
+    type G1Affine: CurveAffine<Projective = Self::G1, Scalar = E::Fr>;
+    /// PointproofsG2, in which the verifier parameters live
+    type G2: CurveProjective<Affine = Self::G2Affine, Scalar = E::Fr>;
+    /// PointproofsG2Affine
+    type G2Affine: CurveAffine<Projective = Self::G2, Scalar = E::Fr>;
+
+    /// A wrapper of E::pairing.
+    fn pairing(p1: Self::G1Affine, q1: Self::G2Affine) -> E::Fqk;
+
+    /// A wrapper of E::pairing_product.
+    fn pairing_product(
+        p1: Self::G1Affine,
+        q1: Self::G2Affine,
+        p2: Self::G1Affine,
+        q2: Self::G2Affine,
+    ) -> E::Fqk;
+
+    /// A wrapper of E::pairing_multi_product.
+    fn pairing_multi_product(g1_vec: &[Self::G1Affine], g2_vec: &[Self::G2Affine]) -> E::Fqk;
+
+    /// Unwraps a PointproofsG1 element.
+    fn g1(p: &PointproofsG1<E>) -> Self::G1;
+
+    /// Wraps a PointproofsG1 element.
+    fn wrap_g1(p: Self::G1) -> PointproofsG1<E>;
+
+    /// Unwraps a list of PointproofsG1Affine elements.
+    fn g1_slice(v: &PointproofsG1Vec<E>) -> &[Self::G1Affine];
+
+    /// Wraps a list of PointproofsG1Affine elements.
+    fn wrap_g1_vec(v: Vec<Self::G1Affine>) -> PointproofsG1Vec<E> {
+        Self::wrap_g1_points(Points::Owned(v))
+    }
+
+    /// Wraps a list of PointproofsG1Affine elements, owned or mapped.
+    fn wrap_g1_points(v: Points<Self::G1Affine>) -> PointproofsG1Vec<E>;
+
+    /// Unwraps a list of PointproofsG2Affine elements.
+    fn g2_slice(v: &PointproofsG2Vec<E>) -> &[Self::G2Affine];
+
+    /// Wraps a list of PointproofsG2Affine elements.
+    fn wrap_g2_vec(v: Vec<Self::G2Affine>) -> PointproofsG2Vec<E>;
+}
+
+/// Groups are not switched and proof/commits are in E::G1.
+pub(crate) struct StandardGroups;
+
+/// Groups are switched and proof/commits are in E::G2.
+pub(crate) struct SwitchedGroups;
+
+pub(crate) const ORIENTATION_MISMATCH: &str = "group orientation does not match the ciphersuite";
+
+impl<E: Engine> PointproofsGroups<E> for StandardGroups {
+    type G1 = E::G1;
+    type G1Affine = E::G1Affine;
+    type G2 = E::G2;
+    type G2Affine = E::G2Affine;
+
+    fn pairing(p1: E::G1Affine, q1: E::G2Affine) -> E::Fqk {
+        E::pairing(p1, q1)
+    }
+
+    fn pairing_product(
+        p1: E::G1Affine,
+        q1: E::G2Affine,
+        p2: E::G1Affine,
+        q2: E::G2Affine,
+    ) -> E::Fqk {
+        E::pairing_product(p1, q1, p2, q2)
+    }
+
+    fn pairing_multi_product(g1_vec: &[E::G1Affine], g2_vec: &[E::G2Affine]) -> E::Fqk {
+        E::pairing_multi_product(g1_vec, g2_vec)
+    }
+
+    fn g1(p: &PointproofsG1<E>) -> E::G1 {
+        match p {
+            PointproofsG1::Standard(p) => *p,
+            PointproofsG1::Switched(_) => panic!("{}", ORIENTATION_MISMATCH),
+        }
+    }
+
+    fn wrap_g1(p: E::G1) -> PointproofsG1<E> {
+        PointproofsG1::Standard(p)
+    }
+
+    fn g1_slice(v: &PointproofsG1Vec<E>) -> &[E::G1Affine] {
+        match v {
+            PointproofsG1Vec::Standard(v) => &v[..],
+            PointproofsG1Vec::Switched(_) => panic!("{}", ORIENTATION_MISMATCH),
+        }
+    }
+
+    fn wrap_g1_points(v: Points<E::G1Affine>) -> PointproofsG1Vec<E> {
+        PointproofsG1Vec::Standard(v)
+    }
+
+    fn g2_slice(v: &PointproofsG2Vec<E>) -> &[E::G2Affine] {
+        match v {
+            PointproofsG2Vec::Standard(v) => v,
+            PointproofsG2Vec::Switched(_) => panic!("{}", ORIENTATION_MISMATCH),
+        }
+    }
+
+    fn wrap_g2_vec(v: Vec<E::G2Affine>) -> PointproofsG2Vec<E> {
+        PointproofsG2Vec::Standard(v)
+    }
+}
+
+impl<E: Engine> PointproofsGroups<E> for SwitchedGroups {
+    type G1 = E::G2;
+    type G1Affine = E::G2Affine;
+    type G2 = E::G1;
+    type G2Affine = E::G1Affine;
+
+    fn pairing(p1: E::G2Affine, q1: E::G1Affine) -> E::Fqk {
+        E::pairing(q1, p1)
+    }
+
+    fn pairing_product(
+        p1: E::G2Affine,
+        q1: E::G1Affine,
+        p2: E::G2Affine,
+        q2: E::G1Affine,
+    ) -> E::Fqk {
+        E::pairing_product(q1, p1, q2, p2)
+    }
+
+    fn pairing_multi_product(g1_vec: &[E::G2Affine], g2_vec: &[E::G1Affine]) -> E::Fqk {
+        E::pairing_multi_product(g2_vec, g1_vec)
+    }
+
+    fn g1(p: &PointproofsG1<E>) -> E::G2 {
+        match p {
+            PointproofsG1::Switched(p) => *p,
+            PointproofsG1::Standard(_) => panic!("{}", ORIENTATION_MISMATCH),
+        }
+    }
+
+    fn wrap_g1(p: E::G2) -> PointproofsG1<E> {
+        PointproofsG1::Switched(p)
+    }
+
+    fn g1_slice(v: &PointproofsG1Vec<E>) -> &[E::G2Affine] {
+        match v {
+            PointproofsG1Vec::Switched(v) => &v[..],
+            PointproofsG1Vec::Standard(_) => panic!("{}", ORIENTATION_MISMATCH),
+        }
+    }
+
+    fn wrap_g1_points(v: Points<E::G2Affine>) -> PointproofsG1Vec<E> {
+        PointproofsG1Vec::Switched(v)
+    }
+
+    fn g2_slice(v: &PointproofsG2Vec<E>) -> &[E::G1Affine] {
+        match v {
+            PointproofsG2Vec::Switched(v) => v,
+            PointproofsG2Vec::Standard(_) => panic!("{}", ORIENTATION_MISMATCH),
+        }
+    }
+
+    fn wrap_g2_vec(v: Vec<E::G1Affine>) -> PointproofsG2Vec<E> {
+        PointproofsG2Vec::Switched(v)
+    }
+}
+
+/// Evaluates `$body` with `$G` bound to the `PointproofsGroups`
+/// instantiation of the group orientation `$orientation`.
+/// The trait is generic over the engine, so within `$body` the associated
+/// types of `$G` are reached through generic helpers, e.g. `helper::<E, $G>(...)`.
+macro_rules! dispatch_groups {
+    ($orientation:expr, $G:ident => $body:expr) => {
+        match $orientation {
+            $crate::pairings::ciphersuite::GroupOrientation::Standard => {
+                type $G = $crate::pairings::pointproofs_groups::StandardGroups;
+                $body
+            }
+            $crate::pairings::ciphersuite::GroupOrientation::Switched => {
+                type $G = $crate::pairings::pointproofs_groups::SwitchedGroups;
+                $body
+            }
+        }
+    };
+}
@@ -0,0 +1,346 @@
+//! this file is part of the pointproofs.
+//! It defines the pre-computed tables of the parameters,
+//! and the exponentiations that use them.
+
+use ff::PrimeField;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pairings::misc::*;
+use pairings::param::Precomputation;
+use pairings::*;
+use std::ops::Range;
+
+/// The largest window, in bits, of a `Precomputation::Custom` table.
+pub(crate) const MAX_WINDOW_BITS: usize = 16;
+
+/// A view of a list of generators and of their pre-computed data.
+/// All multi-exponentiations over the parameters go through this view,
+/// so that they make the same use of the pre-computed data.
+/// The tables may cover only the generators in `covered`,
+/// see `ProverParams::precompute_with_budget`.
+pub(crate) struct PrecompTable<'a, C: CurveAffine> {
+    generators: &'a [C],
+    precomp: &'a [C],
+    precomputation: Precomputation,
+    covered: Range<usize>,
+    threshold: usize,
+}
+
+/// The view of the prover generators.
+pub(crate) fn pp_table<E: Engine, G: PointproofsGroups<E>>(
+    prover_params: &GenericProverParams<E>,
+) -> PrecompTable<G::G1Affine> {
+    PrecompTable {
+        generators: G::g1_slice(&prover_params.generators),
+        precomp: G::g1_slice(&prover_params.precomp),
+        precomputation: prover_params.precomputation,
+        covered: prover_params.precomp_range.clone(),
+        threshold: prover_params.precomp_threshold,
+    }
+}
+
+/// The view of the verifier generators.
+pub(crate) fn vp_table<E: Engine, G: PointproofsGroups<E>>(
+    verifier_params: &GenericVerifierParams<E>,
+) -> PrecompTable<G::G2Affine> {
+    PrecompTable {
+        generators: G::g2_slice(&verifier_params.generators),
+        precomp: G::g2_slice(&verifier_params.precomp),
+        precomputation: verifier_params.precomputation,
+        covered: verifier_params.precomp_range.clone(),
+        threshold: verifier_params.precomp_threshold,
+    }
+}
+
+impl<'a, C: CurveAffine> PrecompTable<'a, C> {
+    /// Computes \sum_{i=start}^{end-1} generators[i]^scalars_u64[i-start].
+    /// The 256-table and custom tables are used when they cover
+    /// at most `threshold` of the generators: for more, `sum_of_products` is faster.
+    /// The generators that are not covered go through `sum_of_products`.
+    pub(crate) fn range_sum_of_prod(
+        &self,
+        start: usize,
+        end: usize,
+        scalars_u64: &[&[u64; 4]],
+    ) -> C::Projective {
+        // [lo, hi) are the generators covered by the tables
+        let lo = std::cmp::min(std::cmp::max(start, self.covered.start), end);
+        let hi = std::cmp::max(std::cmp::min(end, self.covered.end), lo);
+        if !self.use_multi_exp_tables(hi - lo) {
+            return sum_of_prod(&self.generators[start..end], scalars_u64);
+        }
+
+        let scalars_covered = &scalars_u64[lo - start..hi - start];
+        let mut res = match self.precomputation {
+            Precomputation::Window256 => sum_of_prod_precomp_256(
+                &self.generators[lo..hi],
+                scalars_covered,
+                &self.precomp[self.slot(lo) * 256..self.slot(hi) * 256],
+            ),
+            Precomputation::Custom(bits) => {
+                window_sum_of_prod(self.precomp, bits, |k| self.slot(lo + k), scalars_covered)
+            }
+            _ => unreachable!(),
+        };
+        if start < lo {
+            res.add_assign(&sum_of_prod(
+                &self.generators[start..lo],
+                &scalars_u64[..lo - start],
+            ));
+        }
+        if hi < end {
+            res.add_assign(&sum_of_prod(
+                &self.generators[hi..end],
+                &scalars_u64[hi - start..],
+            ));
+        }
+        res
+    }
+
+    /// Computes \sum generators[indices[i]]^scalars_u64[i],
+    /// with the same use of the pre-computed data as `range_sum_of_prod`.
+    pub(crate) fn subset_sum_of_prod(
+        &self,
+        indices: &[usize],
+        scalars_u64: &[&[u64; 4]],
+    ) -> C::Projective {
+        // split the terms on whether the tables cover their generators
+        let (covered, uncovered): (Vec<usize>, Vec<usize>) =
+            (0..indices.len()).partition(|k| self.covered.contains(&indices[*k]));
+        if !self.use_multi_exp_tables(covered.len()) {
+            let basis: Vec<C> = indices.iter().map(|i| self.generators[*i]).collect();
+            return sum_of_prod(&basis, scalars_u64);
+        }
+
+        let scalars_covered: Vec<&[u64; 4]> = covered.iter().map(|k| scalars_u64[*k]).collect();
+        let mut res = match self.precomputation {
+            Precomputation::Window256 => {
+                // `sum_of_products_precomp_256` takes the tables of the basis in order
+                let mut basis: Vec<C> = Vec::with_capacity(covered.len());
+                let mut basis_precomp: Vec<C> = Vec::with_capacity(covered.len() * 256);
+                for k in covered.iter() {
+                    let slot = self.slot(indices[*k]);
+                    basis.push(self.generators[indices[*k]]);
+                    basis_precomp.extend_from_slice(&self.precomp[slot * 256..(slot + 1) * 256]);
+                }
+                sum_of_prod_precomp_256(&basis, &scalars_covered, &basis_precomp)
+            }
+            Precomputation::Custom(bits) => window_sum_of_prod(
+                self.precomp,
+                bits,
+                |k| self.slot(indices[covered[k]]),
+                &scalars_covered,
+            ),
+            _ => unreachable!(),
+        };
+        if !uncovered.is_empty() {
+            let basis: Vec<C> = uncovered
+                .iter()
+                .map(|k| self.generators[indices[*k]])
+                .collect();
+            let scalars: Vec<&[u64; 4]> = uncovered.iter().map(|k| scalars_u64[*k]).collect();
+            res.add_assign(&sum_of_prod(&basis, &scalars));
+        }
+        res
+    }
+
+    /// Computes generators[index]^scalar.
+    pub(crate) fn single_exp(&self, index: usize, scalar: C::Scalar) -> C::Projective {
+        if !self.covered.contains(&index) {
+            return self.generators[index].mul(scalar);
+        }
+        let slot = self.slot(index);
+        match self.precomputation {
+            Precomputation::None => self.generators[index].mul(scalar),
+            Precomputation::Window3 => self.generators[index]
+                .mul_precomp_3(scalar, &self.precomp[slot * 3..(slot + 1) * 3]),
+            Precomputation::Window256 => self.generators[index]
+                .mul_precomp_256(scalar, &self.precomp[slot * 256..(slot + 1) * 256]),
+            Precomputation::Custom(bits) => {
+                let scalar_u64 = repr_to_u64(&scalar.into_repr());
+                window_sum_of_prod(self.precomp, bits, |_| slot, &[&scalar_u64])
+            }
+        }
+    }
+
+    /// Whether a multi-exponentiation over `covered_count` covered generators
+    /// uses the tables.
+    fn use_multi_exp_tables(&self, covered_count: usize) -> bool {
+        covered_count > 0
+            && covered_count <= self.threshold
+            && match self.precomputation {
+                Precomputation::Window256 | Precomputation::Custom(_) => true,
+                Precomputation::None | Precomputation::Window3 => false,
+            }
+    }
+
+    /// The position of the table of a covered generator.
+    fn slot(&self, index: usize) -> usize {
+        index - self.covered.start
+    }
+}
+
+/// Number of pre-computed points per generator.
+pub(crate) fn points_per_generator<F: PrimeField>(precomputation: Precomputation) -> usize {
+    match precomputation {
+        Precomputation::None => 0,
+        Precomputation::Window3 => 3,
+        Precomputation::Window256 => 256,
+        Precomputation::Custom(bits) => window_count::<F>(bits) * ((1 << bits) - 1),
+    }
+}
+
+/// Returns the precomputation whose tables have `len` points for `generator_count` generators,
+/// if any; used when reading the tables from a blob.
+pub(crate) fn precomputation_from_len<F: PrimeField>(
+    len: usize,
+    generator_count: usize,
+) -> Option<Precomputation> {
+    if len == 0 {
+        return Some(Precomputation::None);
+    }
+    if generator_count == 0 || len % generator_count != 0 {
+        return None;
+    }
+    let per_generator = len / generator_count;
+    [Precomputation::Window3, Precomputation::Window256]
+        .iter()
+        .cloned()
+        .chain((1..=MAX_WINDOW_BITS).map(Precomputation::Custom))
+        .find(|p| points_per_generator::<F>(*p) == per_generator)
+}
+
+/// Returns an error if the precomputation is not supported.
+pub(crate) fn check_precomputation(precomputation: Precomputation) -> Result<(), PointproofsError> {
+    match precomputation {
+        Precomputation::Custom(bits) if bits == 0 || bits > MAX_WINDOW_BITS => {
+            Err(PointproofsError::InvalidWindow {
+                bits,
+                max: MAX_WINDOW_BITS,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The (kind, window bits) code of a precomputation in the headers of the file formats.
+pub(crate) fn precomputation_code(precomputation: Precomputation) -> (u8, u8) {
+    match precomputation {
+        Precomputation::None => (0, 0),
+        Precomputation::Window3 => (1, 0),
+        Precomputation::Window256 => (2, 0),
+        Precomputation::Custom(bits) => (3, bits as u8),
+    }
+}
+
+/// The precomputation of a (kind, window bits) code, if the kind is known;
+/// the window is checked with `check_precomputation`.
+pub(crate) fn precomputation_from_code(kind: u8, bits: u8) -> Option<Precomputation> {
+    match kind {
+        0 => Some(Precomputation::None),
+        1 => Some(Precomputation::Window3),
+        2 => Some(Precomputation::Window256),
+        3 => Some(Precomputation::Custom(bits as usize)),
+        _ => None,
+    }
+}
+
+/// Computes the tables of `precomputation` for the generators in `covered`.
+pub(crate) fn precompute_tables<C: CurveAffine>(
+    generators: &[C],
+    precomputation: Precomputation,
+    covered: Range<usize>,
+) -> Vec<C> {
+    let per_generator = points_per_generator::<C::Scalar>(precomputation);
+    let mut precomp = vec![C::zero(); per_generator * covered.len()];
+    for (slot, generator) in generators[covered].iter().enumerate() {
+        let table = &mut precomp[slot * per_generator..(slot + 1) * per_generator];
+        match precomputation {
+            Precomputation::None => (),
+            Precomputation::Window3 => generator.precomp_3(table),
+            Precomputation::Window256 => generator.precomp_256(table),
+            Precomputation::Custom(bits) => window_table(generator, bits, table),
+        }
+    }
+    precomp
+}
+
+/// The generators covered by partial tables of the prover parameters, for a budget
+/// of `count` generators: the tables are for the generators around n,
+/// which are used by the most proofs (generators[n +/- t] is used by n - t proofs,
+/// and generators[n - t] also by commitments), in the order
+/// n - 1, n, n + 1, n - 2, n + 2, ...
+/// generators[n] is the identity; it is covered to keep the range contiguous.
+pub(crate) fn pp_budget_range(n: usize, count: usize) -> Range<usize> {
+    let count = std::cmp::min(count, 2 * n);
+    if count == 0 {
+        return 0..0;
+    }
+    // the number of covered generators below n
+    let below = 1 + count.saturating_sub(2) / 2;
+    let end = std::cmp::min(n - below + count, 2 * n);
+    end - count..end
+}
+
+/// Number of windows of `bits` bits in a scalar.
+fn window_count<F: PrimeField>(bits: usize) -> usize {
+    (F::NUM_BITS as usize + bits - 1) / bits
+}
+
+/// Fixed-base windowed table of a generator g:
+///     table[j * (2^bits - 1) + k - 1] = g^{k * 2^{j * bits}}
+/// for every window j and every digit 1 <= k < 2^bits.
+/// An exponentiation is then a sum of one point per window, without doublings.
+fn window_table<C: CurveAffine>(generator: &C, bits: usize, table: &mut [C]) {
+    let mut table_proj: Vec<C::Projective> = Vec::with_capacity(table.len());
+    // g^{2^{j * bits}}
+    let mut window_base = generator.into_projective();
+    for _ in 0..window_count::<C::Scalar>(bits) {
+        let mut point = window_base;
+        for _ in 1..(1 << bits) {
+            table_proj.push(point);
+            point.add_assign(&window_base);
+        }
+        // point = g^{2^bits * 2^{j * bits}}
+        window_base = point;
+    }
+    C::Projective::batch_normalization(&mut table_proj);
+    for (t, p) in table.iter_mut().zip(table_proj.iter()) {
+        *t = p.into_affine();
+    }
+}
+
+/// Returns the bits [start, start + bits) of a scalar.
+fn window_digit(scalar_u64: &[u64; 4], start: usize, bits: usize) -> usize {
+    let limb = start / 64;
+    let shift = start % 64;
+    let mut digit = scalar_u64[limb] >> shift;
+    if shift + bits > 64 && limb + 1 < 4 {
+        digit |= scalar_u64[limb + 1] << (64 - shift);
+    }
+    (digit as usize) & ((1 << bits) - 1)
+}
+
+/// Computes \sum generators[index(k)]^scalars_u64[k] from their windowed tables.
+fn window_sum_of_prod<C: CurveAffine, F: Fn(usize) -> usize + Sync>(
+    precomp: &[C],
+    bits: usize,
+    index: F,
+    scalars_u64: &[&[u64; 4]],
+) -> C::Projective {
+    let windows = window_count::<C::Scalar>(bits);
+    let digits = (1 << bits) - 1;
+    let per_generator = windows * digits;
+    chunked_sum(scalars_u64.len(), |range| {
+        let mut res = C::Projective::zero();
+        for k in range {
+            let table = &precomp[index(k) * per_generator..(index(k) + 1) * per_generator];
+            for j in 0..windows {
+                let digit = window_digit(scalars_u64[k], j * bits, bits);
+                if digit != 0 {
+                    res.add_assign_mixed(&table[j * digits + digit - 1]);
+                }
+            }
+        }
+        res
+    })
+}
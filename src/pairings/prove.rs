@@ -1,14 +1,19 @@
 //! this file is part of the pointproofs.
 //! It defines functions for proofs.
 use ff::{Field, PrimeField};
-use pairing::{bls12_381::*, CurveAffine, CurveProjective};
-use pairings::err::*;
+use pairing::serdes::SerDes;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pairings::ciphersuite::group_orientation;
 use pairings::hash_to_field_pointproofs::*;
 use pairings::misc::*;
 use pairings::param::*;
+use pairings::params_id::check_params_id;
+use pairings::precomp::*;
 use pairings::*;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-impl Proof {
+impl<E: Engine> GenericProof<E> {
     /// Generate a new proof.
     ///     * input: prover parameter set
     ///     * input: values for the proof
@@ -22,40 +27,149 @@ impl Proof {
     /// In this scenario, the caller should define the right
     /// format for padding.
     pub fn new<Blob: AsRef<[u8]>>(
-        prover_params: &ProverParams,
+        prover_params: &GenericProverParams<E>,
         values: &[Blob],
         index: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+
+        // hash into a set of scalars
+        let scalars: Vec<E::Fr> = hash_to_field_vec_pointproofs(prover_params.ciphersuite, values);
+        Self::new_from_scalars(prover_params, &scalars, index)
+    }
+
+    /// Generate a new proof for a vector of field elements,
+    /// which are committed to as they are, without hashing.
+    ///     * input: prover parameter set
+    ///     * input: field elements for the proof
+    ///     * input: the index of the proof
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters
+    pub fn new_from_scalars(
+        prover_params: &GenericProverParams<E>,
+        scalars: &[E::Fr],
+        index: usize,
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
         }
 
         // check index is valid
         if index >= prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndex {
+                index,
+                n: prover_params.n,
+            });
         };
         // check param
-        if values.len() != prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+        if scalars.len() != prover_params.n {
+            return Err(PointproofsError::InvalidValueCount {
+                expected: prover_params.n,
+                found: scalars.len(),
+            });
         }
 
-        // hash into a set of scalars
-        let scalars_fr_repr: Vec<FrRepr> = values
+        let scalars_u64: Vec<[u64; 4]> = scalars
             .iter()
-            .map(|s| hash_to_field_repr_pointproofs(&s.as_ref()))
+            .map(|s| repr_to_u64(&s.into_repr()))
             .collect();
-        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
 
         // generate the proof use `sum of product` function
         // proof = \sum_{i=prover_params.n - index}^{2 * prover_params.n - index}
         //          param.generator[i]^scarlar_u64[i]
-        let proof = pp_sum_of_prod_helper(
-            &prover_params,
-            &scalars_u64,
-            prover_params.n - index,
-            2 * prover_params.n - index,
-        );
+        let proof = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            wrap_pp_sum_of_prod::<E, G>(
+                &prover_params,
+                &scalars_u64_ref,
+                prover_params.n - index,
+                2 * prover_params.n - index,
+            )
+        });
+
+        Ok(Self {
+            ciphersuite: prover_params.ciphersuite,
+            proof,
+        })
+    }
+
+    /// Generate a new proof for a sparse vector.
+    ///     * input: prover parameter set
+    ///     * input: a list of (index, value) pairs for the populated entries
+    ///     * input: the index of the proof
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// Empty entries contribute the identity, as for `Commitment::new_sparse`.
+    /// The index of the proof may be empty; the proof is then verified with
+    /// `Proof::verify_scalar` and a 0 field element.
+    pub fn new_sparse<Blob: AsRef<[u8]>>(
+        prover_params: &GenericProverParams<E>,
+        entries: &[(usize, Blob)],
+        index: usize,
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+
+        // hash into a set of scalars
+        let scalar_entries: Vec<(usize, E::Fr)> = entries
+            .iter()
+            .map(|(i, v)| (*i, hash_to_field_pointproofs(prover_params.ciphersuite, v)))
+            .collect();
+        Self::new_sparse_from_scalars(prover_params, &scalar_entries, index)
+    }
+
+    /// Generate a new proof for a sparse vector of field elements.
+    ///     * input: prover parameter set
+    ///     * input: a list of (index, field element) pairs for the populated entries
+    ///     * input: the index of the proof
+    ///     * output: a new proof
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// This is the same as `Proof::new_from_scalars` with 0s in the empty entries.
+    pub fn new_sparse_from_scalars(
+        prover_params: &GenericProverParams<E>,
+        entries: &[(usize, E::Fr)],
+        index: usize,
+    ) -> Result<Self, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+
+        // check index is valid
+        if index >= prover_params.n {
+            return Err(PointproofsError::InvalidIndex {
+                index,
+                n: prover_params.n,
+            });
+        };
+        check_sparse_indices(entries, prover_params.n)?;
+
+        // proof = \prod_{populated j != index} param.generator[n - index + j]^scalar[j]
+        // the entry at index itself is paired with the missing generator
+        let (param_indices, scalars_u64): (Vec<usize>, Vec<[u64; 4]>) = entries
+            .iter()
+            .filter(|e| e.0 != index)
+            .map(|e| (prover_params.n - index + e.0, repr_to_u64(&e.1.into_repr())))
+            .unzip();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
+
+        let proof = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            wrap_pp_subset_sum_of_prod::<E, G>(&prover_params, &param_indices, &scalars_u64_ref)
+        });
 
         Ok(Self {
             ciphersuite: prover_params.ciphersuite,
@@ -73,51 +187,164 @@ impl Proof {
     /// 0 is invalid -- no proof will be generated
     /// n is valid -- generating proof for the whole vector
     pub fn batch_new<Blob: AsRef<[u8]>>(
-        prover_params: &ProverParams,
+        prover_params: &GenericProverParams<E>,
         values: &[Blob],
         indices: &[usize],
-    ) -> Result<Vec<Self>, String> {
+    ) -> Result<Vec<Self>, PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+
+        // hash into a set of scalars
+        let scalars: Vec<E::Fr> = hash_to_field_vec_pointproofs(prover_params.ciphersuite, values);
+        Self::batch_new_from_scalars(prover_params, &scalars, indices)
+    }
+
+    /// Generate a new set of proofs for a vector of field elements.
+    ///     * input: prover parameter set
+    ///     * input: field elements for the proof
+    ///     * input: the list of indices of the proof
+    ///     * output: a list of new proofs
+    ///     * error: invalid ciphersuite/parameters
+    /// The same restrictions on the indices as for `Proof::batch_new` apply.
+    pub fn batch_new_from_scalars(
+        prover_params: &GenericProverParams<E>,
+        scalars: &[E::Fr],
+        indices: &[usize],
+    ) -> Result<Vec<Self>, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
         }
 
         // check index is valid
         if indices.len() > prover_params.n || indices.is_empty() {
-            return Err(ERR_INVALID_INDEX.to_owned());
+            return Err(PointproofsError::InvalidIndexCount {
+                count: indices.len(),
+                n: prover_params.n,
+            });
         };
         for e in indices {
             if *e >= prover_params.n {
-                return Err(ERR_INVALID_INDEX.to_owned());
+                return Err(PointproofsError::InvalidIndex {
+                    index: *e,
+                    n: prover_params.n,
+                });
             }
         }
 
-        if !misc::has_unique_elements(indices) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
-        }
+        misc::check_unique_indices(indices)?;
 
         // check param
-        if values.len() != prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+        if scalars.len() != prover_params.n {
+            return Err(PointproofsError::InvalidValueCount {
+                expected: prover_params.n,
+                found: scalars.len(),
+            });
         }
 
-        // hash into a set of scalars
-        let scalars_fr_repr: Vec<FrRepr> = values
+        let scalars_u64: Vec<[u64; 4]> = scalars
             .iter()
-            .map(|s| hash_to_field_repr_pointproofs(&s.as_ref()))
+            .map(|s| repr_to_u64(&s.into_repr()))
             .collect();
-        let scalars_u64: Vec<&[u64; 4]> = scalars_fr_repr.iter().map(|s| &s.0).collect();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
+
+        // with the `parallel` feature, the proofs are generated on the thread pool
+        #[cfg(feature = "parallel")]
+        let indices_iter = indices.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let indices_iter = indices.iter();
+        let proofs = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            indices_iter
+                .map(|e| Self {
+                    ciphersuite: prover_params.ciphersuite,
+                    proof: wrap_pp_sum_of_prod::<E, G>(
+                        &prover_params,
+                        &scalars_u64_ref,
+                        prover_params.n - *e,
+                        2 * prover_params.n - *e,
+                    ),
+                })
+                .collect()
+        });
+        Ok(proofs)
+    }
 
-        Ok(indices
-            .iter()
-            .map(|e| Self {
+    /// Generate the proofs for all indices of a vector.
+    ///     * input: prover parameter set
+    ///     * input: values for the proofs
+    ///     * output: the list of n proofs, the i-th proof for index i
+    ///     * error: invalid ciphersuite/parameters
+    /// This is the same as `Proof::batch_new` for indices 0..n,
+    /// but takes O(n log n) group operations instead of O(n^2).
+    pub fn all_proofs<Blob: AsRef<[u8]>>(
+        prover_params: &GenericProverParams<E>,
+        values: &[Blob],
+    ) -> Result<Vec<Self>, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+
+        // hash into a set of scalars
+        let scalars: Vec<E::Fr> = hash_to_field_vec_pointproofs(prover_params.ciphersuite, values);
+        Self::all_proofs_from_scalars(prover_params, &scalars)
+    }
+
+    /// Generate the proofs for all indices of a vector of field elements.
+    ///     * input: prover parameter set
+    ///     * input: field elements for the proofs
+    ///     * output: the list of n proofs, the i-th proof for index i
+    ///     * error: invalid ciphersuite/parameters
+    /// This is the same as `Proof::batch_new_from_scalars` for indices 0..n,
+    /// but takes O(n log n) group operations instead of O(n^2).
+    /// The FFTs are over 2n points (rounded up to a power of 2), so n is bounded by
+    /// the 2-adicity of the scalar field: n <= 2^31 for BLS12-381.
+    pub fn all_proofs_from_scalars(
+        prover_params: &GenericProverParams<E>,
+        scalars: &[E::Fr],
+    ) -> Result<Vec<Self>, PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+        // check param
+        if scalars.len() != prover_params.n {
+            return Err(PointproofsError::InvalidValueCount {
+                expected: prover_params.n,
+                found: scalars.len(),
+            });
+        }
+
+        // a root of unity for FFTs over 2n points
+        let log_size = (2 * prover_params.n).next_power_of_two().trailing_zeros();
+        let omega = match fft::root_of_unity::<E::Fr>(log_size) {
+            Some(p) => p,
+            None => {
+                return Err(PointproofsError::InvalidN {
+                    n: prover_params.n,
+                    max: 1 << (E::Fr::S - 1),
+                })
+            }
+        };
+
+        let proofs = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            all_proofs_helper::<E, G>(&prover_params, scalars, &omega)
+        });
+        Ok(proofs
+            .into_iter()
+            .map(|proof| Self {
                 ciphersuite: prover_params.ciphersuite,
-                proof: pp_sum_of_prod_helper(
-                    &prover_params,
-                    &scalars_u64,
-                    prover_params.n - *e,
-                    2 * prover_params.n - *e,
-                ),
+                proof,
             })
             .collect())
     }
@@ -133,104 +360,50 @@ impl Proof {
     /// 0 is invalid -- no proof will be generated
     /// n is valid -- generating proof for the whole vector
     pub fn batch_new_aggregated<Blob: AsRef<[u8]>>(
-        prover_params: &ProverParams,
-        commit: &Commitment,
+        prover_params: &GenericProverParams<E>,
+        commit: &GenericCommitment<E>,
         values: &[Blob],
         indices: &[usize],
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
-        if prover_params.ciphersuite != commit.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
-        }
-
-        // check index is valid
-        if indices.len() > prover_params.n || indices.is_empty() {
-            return Err(ERR_INVALID_INDEX.to_owned());
-        };
-        for e in indices {
-            if *e >= prover_params.n {
-                return Err(ERR_INVALID_INDEX.to_owned());
-            }
-        }
-
-        if !misc::has_unique_elements(indices) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
-        }
-
-        // check param
-        if values.len() != prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
-        }
-
-        // generate the list of sub_values
-        let mut value_sub_vector: Vec<&[u8]> = Vec::with_capacity(values.len());
-        for e in indices {
-            value_sub_vector.push(values[*e].as_ref());
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
         }
 
         // hash into a set of scalars
-        let scalars_fr: Vec<Fr> = values
-            .iter()
-            .map(|s| hash_to_field_pointproofs(&s.as_ref()))
-            .collect();
-        // get the list of scalars for each proof
-        let ti = hash_to_ti_fr(commit, indices, &value_sub_vector, prover_params.n)?;
-
-        // form the final scalars, which are ti[k]*m[n - indices[k] + j] for each index
-        let mut final_scalars: Vec<Fr> = vec![Fr::zero(); 2 * prover_params.n];
-        for k in 0..indices.len() {
-            for j in 0..prover_params.n {
-                let mut tmp = ti[k];
-                tmp.mul_assign(&scalars_fr[j]);
-                final_scalars[prover_params.n - indices[k] + j].add_assign(&tmp);
-            }
-        }
-
-        // remove the generators where the scalars are 0s, to form the final basis
-        // also convert Fr-s to FrRepr-s to [u64;4]-s
-        let mut final_basis: Vec<PointproofsG1Affine> = Vec::with_capacity(2 * prover_params.n);
-        let mut final_scalars_repr: Vec<FrRepr> = Vec::with_capacity(2 * prover_params.n);
-        for (i, e) in final_scalars.iter().enumerate() {
-            if !e.is_zero() {
-                final_scalars_repr.push(e.into_repr());
-                final_basis.push(prover_params.generators[i]);
-            }
-        }
-        let scalars_u64: Vec<&[u64; 4]> = final_scalars_repr.iter().map(|s| &s.0).collect();
-
-        // compute the final aggregated proof
-        let agg_proof = {
-            if prover_params.precomp.len() == 512 * prover_params.n
-                && final_scalars_repr.len() <= 1024
-            {
-                let mut final_basis_pp: Vec<PointproofsG1Affine> =
-                    Vec::with_capacity(512 * prover_params.n);
-                for (i, e) in final_scalars.iter().enumerate() {
-                    if !e.is_zero() {
-                        final_basis_pp = [
-                            final_basis_pp,
-                            prover_params.precomp[i * 256..(i + 1) * 256].to_vec(),
-                        ]
-                        .concat();
-                    }
-                }
-                PointproofsG1Affine::sum_of_products_precomp_256(
-                    &final_basis,
-                    &scalars_u64,
-                    &final_basis_pp,
-                )
-            } else {
-                PointproofsG1Affine::sum_of_products(&final_basis, &scalars_u64)
-            }
-        };
+        let scalars: Vec<E::Fr> = hash_to_field_vec_pointproofs(prover_params.ciphersuite, values);
+        batch_new_aggregated_core(prover_params, commit, values, &scalars, indices)
+    }
 
-        Ok(Proof {
-            ciphersuite: prover_params.ciphersuite,
-            proof: agg_proof,
-        })
+    /// Generate a new set of proofs for a vector of field elements.
+    ///     * input: prover parameter set
+    ///     * input: the commitment
+    ///     * input: field elements for the proof
+    ///     * input: the list of indices of the proof
+    ///     * output: an aggregation of the list of new proofs
+    ///     * error: invalid ciphersuite/parameters
+    /// The same restrictions on the indices as for `Proof::batch_new_aggregated` apply.
+    pub fn batch_new_aggregated_from_scalars(
+        prover_params: &GenericProverParams<E>,
+        commit: &GenericCommitment<E>,
+        scalars: &[E::Fr],
+        indices: &[usize],
+    ) -> Result<Self, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        batch_new_aggregated_core(
+            prover_params,
+            commit,
+            &scalars_to_blobs(scalars),
+            scalars,
+            indices,
+        )
     }
 
     /// Updating an existing proof.
@@ -243,23 +416,65 @@ impl Proof {
     ///     * error: invalid ciphersuite/parameters
     pub fn update<Blob: AsRef<[u8]>>(
         &mut self,
-        prover_params: &ProverParams,
+        prover_params: &GenericProverParams<E>,
         proof_index: usize,
         changed_index: usize,
         value_before: Blob,
         value_after: Blob,
-    ) -> Result<(), String> {
+    ) -> Result<(), PointproofsError> {
         // checks that cipersuite is supported
         if !check_ciphersuite(prover_params.ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+
+        self.update_scalar(
+            prover_params,
+            proof_index,
+            changed_index,
+            hash_to_field_pointproofs(prover_params.ciphersuite, &value_before),
+            hash_to_field_pointproofs(prover_params.ciphersuite, &value_after),
+        )
+    }
+
+    /// Updating an existing proof for a vector of field elements.
+    ///     * input: prover parameter set
+    ///     * input: the index for the proof
+    ///     * input: the index for the field element that is being changed
+    ///     * input: the field element before the change
+    ///     * input: the field element after the change
+    ///     * output: update self to a new proof
+    ///     * error: invalid ciphersuite/parameters
+    pub fn update_scalar(
+        &mut self,
+        prover_params: &GenericProverParams<E>,
+        proof_index: usize,
+        changed_index: usize,
+        scalar_before: E::Fr,
+        scalar_after: E::Fr,
+    ) -> Result<(), PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
         }
         if self.ciphersuite != prover_params.ciphersuite {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::CiphersuiteMismatch {
+                expected: prover_params.ciphersuite,
+                found: self.ciphersuite,
+            });
         }
 
         // check indices are valid
-        if proof_index >= prover_params.n || changed_index >= prover_params.n {
-            return Err(ERR_INVALID_INDEX.to_owned());
+        for index in &[proof_index, changed_index] {
+            if *index >= prover_params.n {
+                return Err(PointproofsError::InvalidIndex {
+                    index: *index,
+                    n: prover_params.n,
+                });
+            }
         }
 
         // update the proof
@@ -268,20 +483,214 @@ impl Proof {
         // proof_param may be pre-computed -- the code will determine this
         // by checking the length of pre_comp
         if proof_index != changed_index {
-            let mut multiplier = hash_to_field_pointproofs(&value_before);
-            multiplier.negate();
-            multiplier.add_assign(&hash_to_field_pointproofs(&value_after));
+            let mut multiplier = scalar_after;
+            multiplier.sub_assign(&scalar_before);
 
             let param_index = changed_index + prover_params.n - proof_index;
-            let res = pp_single_exp_helper(&prover_params, multiplier, param_index);
-
-            self.proof.add_assign(&res);
+            self.proof = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+                add_single_exp::<E, G>(&self.proof, &prover_params, multiplier, param_index)
+            });
         }
 
         // if proof_index == changed_index, do nothing
         Ok(())
     }
 
+    /// Updating an existing proof after a list of values changed.
+    ///     * input: prover parameter set
+    ///     * input: the index for the proof
+    ///     * input: the indices for the values that are being changed
+    ///     * input: the values before the change
+    ///     * input: the values after the change
+    ///     * output: update self to a new proof
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// This takes a single `sum_of_products` over the changed indices,
+    /// instead of one scalar multiplication per changed index.
+    pub fn batch_update<Blob: AsRef<[u8]>>(
+        &mut self,
+        prover_params: &GenericProverParams<E>,
+        proof_index: usize,
+        changed_index: &[usize],
+        value_before: &[Blob],
+        value_after: &[Blob],
+    ) -> Result<(), PointproofsError> {
+        Self::batch_update_proofs(
+            std::slice::from_mut(self),
+            prover_params,
+            &[proof_index],
+            changed_index,
+            value_before,
+            value_after,
+        )
+    }
+
+    /// Updating an existing proof for a vector of field elements,
+    /// after a list of field elements changed.
+    ///     * input: prover parameter set
+    ///     * input: the index for the proof
+    ///     * input: the indices for the field elements that are being changed
+    ///     * input: the field elements before the change
+    ///     * input: the field elements after the change
+    ///     * output: update self to a new proof
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    pub fn batch_update_scalars(
+        &mut self,
+        prover_params: &GenericProverParams<E>,
+        proof_index: usize,
+        changed_index: &[usize],
+        scalars_before: &[E::Fr],
+        scalars_after: &[E::Fr],
+    ) -> Result<(), PointproofsError> {
+        Self::batch_update_proofs_scalars(
+            std::slice::from_mut(self),
+            prover_params,
+            &[proof_index],
+            changed_index,
+            scalars_before,
+            scalars_after,
+        )
+    }
+
+    /// Updating a set of existing proofs after a list of values changed.
+    ///     * input: the proofs
+    ///     * input: prover parameter set
+    ///     * input: the indices for the proofs, one per proof
+    ///     * input: the indices for the values that are being changed
+    ///     * input: the values before the change
+    ///     * input: the values after the change
+    ///     * output: update the proofs to new proofs
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// The values are hashed once for all proofs;
+    /// the proofs are left unchanged if an error is returned.
+    pub fn batch_update_proofs<Blob: AsRef<[u8]>>(
+        proofs: &mut [Self],
+        prover_params: &GenericProverParams<E>,
+        proof_indices: &[usize],
+        changed_index: &[usize],
+        value_before: &[Blob],
+        value_after: &[Blob],
+    ) -> Result<(), PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+
+        let scalars_before: Vec<E::Fr> =
+            hash_to_field_vec_pointproofs(prover_params.ciphersuite, value_before);
+        let scalars_after: Vec<E::Fr> =
+            hash_to_field_vec_pointproofs(prover_params.ciphersuite, value_after);
+        Self::batch_update_proofs_scalars(
+            proofs,
+            prover_params,
+            proof_indices,
+            changed_index,
+            &scalars_before,
+            &scalars_after,
+        )
+    }
+
+    /// Updating a set of existing proofs for a vector of field elements,
+    /// after a list of field elements changed.
+    ///     * input: the proofs
+    ///     * input: prover parameter set
+    ///     * input: the indices for the proofs, one per proof
+    ///     * input: the indices for the field elements that are being changed
+    ///     * input: the field elements before the change
+    ///     * input: the field elements after the change
+    ///     * output: update the proofs to new proofs
+    ///     * error: invalid ciphersuite/parameters, duplicated indices
+    /// The proofs are left unchanged if an error is returned.
+    pub fn batch_update_proofs_scalars(
+        proofs: &mut [Self],
+        prover_params: &GenericProverParams<E>,
+        proof_indices: &[usize],
+        changed_index: &[usize],
+        scalars_before: &[E::Fr],
+        scalars_after: &[E::Fr],
+    ) -> Result<(), PointproofsError> {
+        // checks that cipersuite is supported
+        if !check_ciphersuite(prover_params.ciphersuite) {
+            return Err(PointproofsError::InvalidCiphersuite(
+                prover_params.ciphersuite,
+            ));
+        }
+        for e in proofs.iter() {
+            if e.ciphersuite != prover_params.ciphersuite {
+                return Err(PointproofsError::CiphersuiteMismatch {
+                    expected: prover_params.ciphersuite,
+                    found: e.ciphersuite,
+                });
+            }
+        }
+
+        // check the parameters are valid
+        if proofs.len() != proof_indices.len() {
+            return Err(PointproofsError::IndexProofMismatch {
+                indices: proof_indices.len(),
+                proofs: proofs.len(),
+            });
+        }
+        for index in proof_indices.iter().chain(changed_index.iter()) {
+            if *index >= prover_params.n {
+                return Err(PointproofsError::InvalidIndex {
+                    index: *index,
+                    n: prover_params.n,
+                });
+            }
+        }
+        for len in &[scalars_before.len(), scalars_after.len()] {
+            if changed_index.len() != *len {
+                return Err(PointproofsError::IndexValueMismatch {
+                    indices: changed_index.len(),
+                    values: *len,
+                });
+            }
+        }
+        misc::check_unique_indices(changed_index)?;
+
+        // multiplier = new_scalar - old_scalar
+        let multiplier_set: Vec<E::Fr> = scalars_before
+            .iter()
+            .zip(scalars_after.iter())
+            .map(|(before, after)| {
+                let mut multiplier = *after;
+                multiplier.sub_assign(before);
+                multiplier
+            })
+            .collect();
+
+        // proof = proof * \prod g[changed_index + n - proof_index]^multiplier
+        // where the value at proof_index itself does not affect the proof
+        for (proof, proof_index) in proofs.iter_mut().zip(proof_indices.iter()) {
+            let (param_index, scalars_u64): (Vec<usize>, Vec<[u64; 4]>) = changed_index
+                .iter()
+                .zip(multiplier_set.iter())
+                .filter(|(j, m)| **j != *proof_index && !m.is_zero())
+                .map(|(j, m)| {
+                    (
+                        j + prover_params.n - proof_index,
+                        repr_to_u64(&m.into_repr()),
+                    )
+                })
+                .unzip();
+            if param_index.is_empty() {
+                continue;
+            }
+            let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
+            proof.proof = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+                add_pp_subset_sum_of_prod::<E, G>(
+                    &proof.proof,
+                    &prover_params,
+                    &param_index,
+                    &scalars_u64_ref,
+                )
+            });
+        }
+        Ok(())
+    }
+
     /// Verify the proof.
     ///     * input: the proof
     ///     * input: verifier parameter set
@@ -291,57 +700,103 @@ impl Proof {
     ///     * output: if the proof is valid w.r.t. the rest of inputs
     pub fn verify<Blob: AsRef<[u8]>>(
         &self,
-        verifier_params: &VerifierParams,
-        com: &Commitment,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &GenericCommitment<E>,
         value: Blob,
         index: usize,
     ) -> bool {
-        if self.ciphersuite != verifier_params.ciphersuite || self.ciphersuite != com.ciphersuite {
-            #[cfg(debug_assertions)]
-            println!(
-                " ciphersuite fails {}, {}, {}",
-                self.ciphersuite, verifier_params.ciphersuite, com.ciphersuite
-            );
-            return false;
-        }
+        self.try_verify(verifier_params, com, value, index).is_ok()
+    }
 
-        if !check_ciphersuite(com.ciphersuite) {
-            return false;
-        }
+    /// Verify the proof, and explain why it is rejected.
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the value
+    ///     * input: the index
+    ///     * output: Ok(()) if the proof is valid w.r.t. the rest of inputs
+    ///     * error: VerifyError::Malformed if the inputs are invalid,
+    ///       VerifyError::PairingCheckFailed if the proof is invalid
+    pub fn try_verify<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &GenericCommitment<E>,
+        value: Blob,
+        index: usize,
+    ) -> Result<(), VerifyError> {
+        // check the validity of the ciphersuites before hashing the value
+        check_verify_ciphersuites(verifier_params, com, self)?;
 
+        let hash: E::Fr = hash_to_field_pointproofs(verifier_params.ciphersuite, &value);
+        self.try_verify_scalar(verifier_params, com, hash, index)
+    }
+
+    /// Verify the proof for a field element.
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the field element
+    ///     * input: the index
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    pub fn verify_scalar(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &GenericCommitment<E>,
+        scalar: E::Fr,
+        index: usize,
+    ) -> bool {
+        self.try_verify_scalar(verifier_params, com, scalar, index)
+            .is_ok()
+    }
+
+    /// Verify the proof for a field element, and explain why it is rejected.
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the field element
+    ///     * input: the index
+    ///     * output: Ok(()) if the proof is valid w.r.t. the rest of inputs
+    ///     * error: VerifyError::Malformed if the inputs are invalid,
+    ///       VerifyError::PairingCheckFailed if the proof is invalid
+    pub fn try_verify_scalar(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &GenericCommitment<E>,
+        scalar: E::Fr,
+        index: usize,
+    ) -> Result<(), VerifyError> {
+        // check the validity of the inputs: csid, index
+        check_verify_ciphersuites(verifier_params, com, self)?;
         if index >= verifier_params.n {
-            return false;
+            return Err(PointproofsError::InvalidIndex {
+                index,
+                n: verifier_params.n,
+            }
+            .into());
         }
 
-        // verification formula: e(com, param[n-index-1]) = gt_elt ^ hash(value) * e(proof, generator_of_g2)
+        // verification formula: e(com, param[n-index-1]) = gt_elt ^ scalar * e(proof, generator_of_g2)
         // which is to check
-        //  e(com^hash_inverse,  param[n-index-1]) * e(proof^{-hash_inverse}, generator_of_g2)
+        //  e(com^scalar_inverse,  param[n-index-1]) * e(proof^{-scalar_inverse}, generator_of_g2)
         //  ?= gt_elt
         // We modify the formula as above in order to avoid slow exponentation in the target group (which is Fq12)
-        // and perform two scalar multiplication by to 1/hash(value) in G1 instead, which is considerably faster.
+        // and perform two scalar multiplication by to 1/scalar in G1 instead, which is considerably faster.
         // We also move the pairing from the right-hand-side to the left-hand-side in order
         // to take advantage of the pairing product computation, which is faster than two pairings.
 
-        // step 1. compute hash_inverse
-        let hash = hash_to_field_pointproofs(&value);
-        // we can safely assume that hash is invertible
-        // see `hash_to_field` function
-        let hash_inverse = hash.inverse().unwrap();
-
-        // step 2, compute com^hash_inverse and proof^{-hash_inverse}
-        let mut com_mut = com.commit;
-        let mut proof_mut = self.proof;
-        proof_mut.negate();
-        com_mut.mul_assign(hash_inverse);
-        proof_mut.mul_assign(hash_inverse);
-
-        // step 3. check pairing product
-        pointproofs_pairing_product(
-            com_mut.into_affine(),
-            verifier_params.generators[verifier_params.n - index - 1],
-            proof_mut.into_affine(),
-            PointproofsG2Affine::one(),
-        ) == verifier_params.gt_elt
+        // step 1. compute scalar_inverse
+        // hashed values are never 0; a field element may be,
+        // in which case the formula is checked against 1 without scaling
+        let (scale, target) = scale_and_target::<E>(&scalar, &verifier_params.gt_elt);
+
+        // step 2 and 3 within the groups of the ciphersuite
+        if dispatch_groups!(group_orientation(verifier_params.ciphersuite), G => {
+            verify_helper::<E, G>(verifier_params, com, self, scale, target, index)
+        }) {
+            Ok(())
+        } else {
+            Err(VerifyError::PairingCheckFailed)
+        }
     }
 
     /// Aggregates a vector of proofs from a same commitment into a single one.
@@ -356,43 +811,55 @@ impl Proof {
     ///         the aggregator does not check the validity of individual commit/proofs.
     ///         The caller may need to check them if they care for it.
     pub fn same_commit_aggregate<Blob: AsRef<[u8]>>(
-        commit: &Commitment,
+        commit: &GenericCommitment<E>,
         proofs: &[Self],
         set: &[usize],
         value_sub_vector: &[Blob],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
         // check that the csids are valid/match
         let csid = commit.ciphersuite;
         if !check_ciphersuite(csid) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite(csid));
         }
         for e in proofs.iter() {
             if e.ciphersuite != csid {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::CiphersuiteMismatch {
+                    expected: csid,
+                    found: e.ciphersuite,
+                });
             }
         }
         // check that the length of proofs and sets match
-        if proofs.len() != set.len() || proofs.len() != value_sub_vector.len() {
-            return Err(ERR_INDEX_PROOF_NOT_MATCH.to_owned());
+        if proofs.len() != set.len() {
+            return Err(PointproofsError::IndexProofMismatch {
+                indices: set.len(),
+                proofs: proofs.len(),
+            });
         }
-
-        if !misc::has_unique_elements(set) {
-            return Err(ERR_DUPLICATED_INDEX.to_owned());
+        if set.len() != value_sub_vector.len() {
+            return Err(PointproofsError::IndexValueMismatch {
+                indices: set.len(),
+                values: value_sub_vector.len(),
+            });
         }
 
+        misc::check_unique_indices(set)?;
+
         // get the list of scalas
         let ti = hash_to_ti_repr(commit, set, value_sub_vector, n)?;
-        let scalars_u64: Vec<&[u64; 4]> = ti.iter().map(|s| &s.0).collect();
+        let scalars_u64: Vec<[u64; 4]> = ti.iter().map(repr_to_u64).collect();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
 
-        let mut bases: Vec<PointproofsG1> = proofs.iter().map(|s| s.proof).collect();
-        CurveProjective::batch_normalization(&mut bases);
-        let bases_affine: Vec<PointproofsG1Affine> =
-            bases.iter().map(|s| s.into_affine()).collect();
         // proof = \prod proofs[i]^ti[i]
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = dispatch_groups!(group_orientation(csid), G => {
+            proofs_sum_of_prod_helper::<E, G>(proofs, &scalars_u64_ref)
+        });
 
-        Ok(Proof {
+        Ok(GenericProof {
             ciphersuite: csid,
             proof,
         })
@@ -412,54 +879,54 @@ impl Proof {
     ///         * t\[j\] = hash_to_tj(...)
     ///         * return prod proofs\[j\]^t\[j\]
     pub fn cross_commit_aggregate_partial<Blob: AsRef<[u8]>>(
-        commits: &[Commitment],
+        commits: &[GenericCommitment<E>],
         proofs: &[Self],
         set: &[Vec<usize>],
         value_sub_vector: &[Vec<Blob>],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
         // check ciphersuite
         let ciphersuite = commits[0].ciphersuite;
         if !check_ciphersuite(ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite(ciphersuite));
         }
         for e in commits.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::CiphersuiteMismatch {
+                    expected: ciphersuite,
+                    found: e.ciphersuite,
+                });
             }
         }
         for e in proofs.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::CiphersuiteMismatch {
+                    expected: ciphersuite,
+                    found: e.ciphersuite,
+                });
             }
         }
         for e in set.iter() {
-            if !misc::has_unique_elements(e) {
-                return Err(ERR_DUPLICATED_INDEX.to_owned());
-            }
+            misc::check_unique_indices(e)?;
             for ee in e.iter() {
                 if *ee >= n {
-                    return Err(ERR_INVALID_INDEX.to_owned());
+                    return Err(PointproofsError::InvalidIndex { index: *ee, n });
                 }
             }
         }
 
         // check the length are correct
-        if commits.len() != proofs.len()
-            || commits.len() != set.len()
-            || commits.len() != value_sub_vector.len()
-            || commits.is_empty()
-        {
-            #[cfg(debug_assertions)]
-            println!(
-                "commit: {}, proofs: {}, set: {}, value_sub_vector: {}",
-                commits.len(),
-                proofs.len(),
-                set.len(),
-                value_sub_vector.len()
-            );
-            return Err(ERR_X_COM_SIZE.to_owned());
-        };
+        for len in &[proofs.len(), set.len(), value_sub_vector.len()] {
+            if commits.len() != *len || commits.is_empty() {
+                return Err(PointproofsError::CrossCommitSizeMismatch {
+                    commits: commits.len(),
+                    found: *len,
+                });
+            }
+        }
 
         // if commit.len() == 1, return the aggregated proof
         if commits.len() == 1 {
@@ -469,20 +936,21 @@ impl Proof {
         // start aggregation
         let scalars = hash_to_tj_repr(&commits, &set, &value_sub_vector, n)?;
         if scalars.len() != proofs.len() {
-            return Err(ERR_X_COM_SIZE.to_owned());
+            return Err(PointproofsError::CrossCommitSizeMismatch {
+                commits: scalars.len(),
+                found: proofs.len(),
+            });
         }
 
-        let scalars_u64: Vec<&[u64; 4]> = scalars.iter().map(|s| &s.0).collect();
-
-        let mut bases: Vec<PointproofsG1> = proofs.iter().map(|s| s.proof).collect();
-        CurveProjective::batch_normalization(&mut bases);
-        let bases_affine: Vec<PointproofsG1Affine> =
-            bases.iter().map(|s| s.into_affine()).collect();
+        let scalars_u64: Vec<[u64; 4]> = scalars.iter().map(repr_to_u64).collect();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
 
         // proof = \prod pi[i] ^ tj[i]
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = dispatch_groups!(group_orientation(ciphersuite), G => {
+            proofs_sum_of_prod_helper::<E, G>(proofs, &scalars_u64_ref)
+        });
 
-        Ok(Proof { ciphersuite, proof })
+        Ok(GenericProof { ciphersuite, proof })
     }
 
     /// Aggregate a 2-dim array of proofs, each row corresponding to a
@@ -502,56 +970,56 @@ impl Proof {
     ///         2. pi[j] = same_commit_aggregate(...)
     ///         3. return prod pi[j]^t[j]
     pub fn cross_commit_aggregate_full<Blob: AsRef<[u8]>>(
-        commits: &[Commitment],
+        commits: &[GenericCommitment<E>],
         proofs: &[Vec<Self>],
         set: &[Vec<usize>],
         value_sub_vector: &[Vec<Blob>],
         n: usize,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
         // check ciphersuite
         let ciphersuite = commits[0].ciphersuite;
         if !check_ciphersuite(ciphersuite) {
-            return Err(ERR_CIPHERSUITE.to_owned());
+            return Err(PointproofsError::InvalidCiphersuite(ciphersuite));
         }
         for e in commits.iter() {
             if e.ciphersuite != ciphersuite {
-                return Err(ERR_CIPHERSUITE.to_owned());
+                return Err(PointproofsError::CiphersuiteMismatch {
+                    expected: ciphersuite,
+                    found: e.ciphersuite,
+                });
             }
         }
         for e in proofs.iter() {
             for ee in e.iter() {
                 if ee.ciphersuite != ciphersuite {
-                    return Err(ERR_CIPHERSUITE.to_owned());
+                    return Err(PointproofsError::CiphersuiteMismatch {
+                        expected: ciphersuite,
+                        found: ee.ciphersuite,
+                    });
                 }
             }
         }
         for e in set.iter() {
-            if !misc::has_unique_elements(e) {
-                return Err(ERR_DUPLICATED_INDEX.to_owned());
-            }
+            misc::check_unique_indices(e)?;
             for ee in e.iter() {
                 if *ee >= n {
-                    return Err(ERR_INVALID_INDEX.to_owned());
+                    return Err(PointproofsError::InvalidIndex { index: *ee, n });
                 }
             }
         }
 
         // check the length are correct
-        if commits.len() != proofs.len()
-            || commits.len() != set.len()
-            || commits.len() != value_sub_vector.len()
-            || commits.is_empty()
-        {
-            #[cfg(debug_assertions)]
-            println!(
-                "commit: {}, proofs: {}, set: {}, value_sub_vector: {}",
-                commits.len(),
-                proofs.len(),
-                set.len(),
-                value_sub_vector.len()
-            );
-            return Err(ERR_X_COM_SIZE.to_owned());
-        };
+        for len in &[proofs.len(), set.len(), value_sub_vector.len()] {
+            if commits.len() != *len || commits.is_empty() {
+                return Err(PointproofsError::CrossCommitSizeMismatch {
+                    commits: commits.len(),
+                    found: *len,
+                });
+            }
+        }
 
         // if commit.len() == 1, call normal aggregation
         if commits.len() == 1 {
@@ -567,7 +1035,7 @@ impl Proof {
         // start aggregation
         // generate the random Fr-s
         let tj = hash_to_tj_fr(&commits, &set, &value_sub_vector, n)?;
-        let mut ti_s: Vec<Vec<Fr>> = Vec::with_capacity(commits.len());
+        let mut ti_s: Vec<Vec<E::Fr>> = Vec::with_capacity(commits.len());
         for j in 0..commits.len() {
             ti_s.push(hash_to_ti_fr(
                 &commits[j],
@@ -578,28 +1046,96 @@ impl Proof {
         }
         // form the final scalars by multiplying Fr-s
         // for j in 0..# com, for k in 0..#proof, tj[j] * ti[j,k]
-        let mut scalars_repr: Vec<FrRepr> = vec![];
+        let mut scalars_u64: Vec<[u64; 4]> = vec![];
         for j in 0..tj.len() {
             for e in ti_s[j].iter() {
                 let mut tmp = *e;
                 tmp.mul_assign(&tj[j]);
-                scalars_repr.push(tmp.into_repr());
+                scalars_u64.push(repr_to_u64(&tmp.into_repr()));
             }
         }
-        let scalars_u64: Vec<&[u64; 4]> = scalars_repr.iter().map(|s| &s.0).collect();
-
-        // form the final basis
-        let mut bases: Vec<PointproofsG1> = proofs.concat().iter().map(|x| x.proof).collect();
-        CurveProjective::batch_normalization(&mut bases);
-        // `into_affine()` here only performs a type conversion
-        // the CurveProjective points are already normalized via batch nomarlization
-        let bases_affine: Vec<PointproofsG1Affine> =
-            bases.iter().map(|s| s.into_affine()).collect();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
 
         // proof = \prod pi[i] ^ {tj[i] * ti[i,j]}
-        let proof = PointproofsG1Affine::sum_of_products(&bases_affine[..], &scalars_u64);
+        let proof = dispatch_groups!(group_orientation(ciphersuite), G => {
+            proofs_sum_of_prod_helper::<E, G>(&proofs.concat(), &scalars_u64_ref)
+        });
+
+        Ok(GenericProof { ciphersuite, proof })
+    }
+
+    /// Aggregates a vector of proofs for field elements
+    /// from a same commitment into a single one.
+    ///     * input: the commitment
+    ///     * input: the list of proofs
+    ///     * input: the list of the indices of the proofs
+    ///     * input: the list of the field elements of the proofs
+    ///     * input: parameter n (size of the vector)
+    ///     * output: the aggregated proof
+    ///     * error: invalid ciphersuite/length, or hash to scalars failes
+    pub fn same_commit_aggregate_scalars(
+        commit: &GenericCommitment<E>,
+        proofs: &[Self],
+        set: &[usize],
+        scalar_sub_vector: &[E::Fr],
+        n: usize,
+    ) -> Result<Self, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        Self::same_commit_aggregate(commit, proofs, set, &scalars_to_blobs(scalar_sub_vector), n)
+    }
+
+    /// Aggregate an array of proofs for field elements, each
+    /// proof is a same-commit aggregated proof.
+    ///     * input: a list of commitments
+    ///     * input: a list of (aggregated) proofs, each for one commitment
+    ///     * input: a 2-dim array of indices for the proofs, each vector of indices for an aggregated proof
+    ///     * input: a 2-dim array of field elements for the proofs, each vector for an aggregated proof
+    ///     * input: parameter n (size of the vector)
+    ///     * output: an aggregated proof
+    ///     * error: invalid ciphersuite, input vectors length does not match
+    pub fn cross_commit_aggregate_partial_scalars(
+        commits: &[GenericCommitment<E>],
+        proofs: &[Self],
+        set: &[Vec<usize>],
+        scalar_sub_vector: &[Vec<E::Fr>],
+        n: usize,
+    ) -> Result<Self, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        let value_sub_vector: Vec<Vec<Vec<u8>>> = scalar_sub_vector
+            .iter()
+            .map(|e| scalars_to_blobs(e))
+            .collect();
+        Self::cross_commit_aggregate_partial(commits, proofs, set, &value_sub_vector, n)
+    }
 
-        Ok(Proof { ciphersuite, proof })
+    /// Aggregate a 2-dim array of proofs for field elements, each row corresponding to a
+    /// commit, into a single proof.
+    ///     * input: a list of commitments
+    ///     * input: a 2-dim array of non-aggregated proofs, each vector of proofs for one commitment
+    ///     * input: a 2-dim array of indices for the proofs, each indice for a proof
+    ///     * input: a 2-dim array of field elements for the proofs, each field element for a proof
+    ///     * input: parameter n (size of the vector)
+    ///     * output: an aggregated proof
+    ///     * error: invalid ciphersuite, input vectors length does not match
+    pub fn cross_commit_aggregate_full_scalars(
+        commits: &[GenericCommitment<E>],
+        proofs: &[Vec<Self>],
+        set: &[Vec<usize>],
+        scalar_sub_vector: &[Vec<E::Fr>],
+        n: usize,
+    ) -> Result<Self, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        let value_sub_vector: Vec<Vec<Vec<u8>>> = scalar_sub_vector
+            .iter()
+            .map(|e| scalars_to_blobs(e))
+            .collect();
+        Self::cross_commit_aggregate_full(commits, proofs, set, &value_sub_vector, n)
     }
 
     /// batch verify a proof for a list of values/indices
@@ -611,304 +1147,779 @@ impl Proof {
     ///     * output: if the proof is valid w.r.t. the rest of inputs
     pub fn same_commit_batch_verify<Blob: AsRef<[u8]>>(
         &self,
-        verifier_params: &VerifierParams,
-        com: &Commitment,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &GenericCommitment<E>,
         set: &[usize],
         value_sub_vector: &[Blob],
-    ) -> bool {
-        // we want to check if
-        //   e(com, g2^{\sum_{i \in set} \alpha^{N+1-i} t_i})
-        //    ?= e(proof, g2) * e(g1, g2)^{alpha^{N+1} \sum value_i*t_i}
-        // which is to check
-        //   e(com, g2^{\sum_{i \in set} \alpha^{N+1-i} t_i * tmp})
-        //    * e(proof^{-tmp}, g2)
-        //    ?= e(g1, g2)^{alpha^N+1}
-        // where
-        //   tmp = 1/ \sum value_i*t_i
-
-        // 0. check the validity of the inputs: csid, length, etc
-        if !check_ciphersuite(com.ciphersuite) {
-            return false;
-        }
-        if com.ciphersuite != verifier_params.ciphersuite || com.ciphersuite != self.ciphersuite {
-            #[cfg(debug_assertions)]
-            println!(
-                "Ciphersuite failed? {} {} {}\n",
-                com.ciphersuite, verifier_params.ciphersuite, self.ciphersuite
-            );
-
-            return false;
-        }
-        if set.len() != value_sub_vector.len() {
-            return false;
-        }
-        if value_sub_vector.len() > verifier_params.n {
-            return false;
-        }
-        for e in set {
-            if *e >= verifier_params.n {
-                return false;
-            }
-        }
-        if !misc::has_unique_elements(set) {
-            return false;
-        }
+    ) -> bool
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        self.try_same_commit_batch_verify(verifier_params, com, set, value_sub_vector)
+            .is_ok()
+    }
 
-        // if the length == 1, call normal verification method
-        if set.len() == 1 {
-            return self.verify(&verifier_params, &com, value_sub_vector[0].as_ref(), set[0]);
-        }
-        // 1. compute tmp
-        // 1.1 get the list of scalas, return false if this failed
-        let mut ti = match hash_to_ti_fr(com, set, value_sub_vector, verifier_params.n) {
-            Err(_e) => return false,
-            Ok(p) => p,
-        };
+    /// batch verify a proof for a list of values/indices, and explain why it is rejected
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the list of indices
+    ///     * input: the list of values
+    ///     * output: Ok(()) if the proof is valid w.r.t. the rest of inputs
+    ///     * error: VerifyError::Malformed if the inputs are invalid,
+    ///       VerifyError::PairingCheckFailed if the proof is invalid
+    pub fn try_same_commit_batch_verify<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &GenericCommitment<E>,
+        set: &[usize],
+        value_sub_vector: &[Blob],
+    ) -> Result<(), VerifyError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        // check the validity of the ciphersuites before hashing the values
+        check_verify_ciphersuites(verifier_params, com, self)?;
+
+        let scalar_sub_vector: Vec<E::Fr> =
+            hash_to_field_vec_pointproofs(verifier_params.ciphersuite, value_sub_vector);
+        try_same_commit_batch_verify_core(
+            self,
+            verifier_params,
+            com,
+            set,
+            value_sub_vector,
+            &scalar_sub_vector,
+        )
+    }
 
-        // 1.2 tmp = 1/\sum value_i*t_i
-        let mut tmp = Fr::zero();
-        for k in 0..set.len() {
-            let mut mi = hash_to_field_pointproofs(value_sub_vector[k].as_ref());
-            mi.mul_assign(&ti[k]);
-            tmp.add_assign(&mi);
-        }
+    /// batch verify a proof for a list of field elements/indices
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the list of indices
+    ///     * input: the list of field elements
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    pub fn same_commit_batch_verify_scalars(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &GenericCommitment<E>,
+        set: &[usize],
+        scalar_sub_vector: &[E::Fr],
+    ) -> bool
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        self.try_same_commit_batch_verify_scalars(verifier_params, com, set, scalar_sub_vector)
+            .is_ok()
+    }
 
-        // 1.3 if tmp == 0 (should never happen in practise)
-        assert!(!tmp.is_zero());
-        let mut tmp = tmp.inverse().unwrap();
+    /// batch verify a proof for a list of field elements/indices, and explain why it is rejected
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the commitment
+    ///     * input: the list of indices
+    ///     * input: the list of field elements
+    ///     * output: Ok(()) if the proof is valid w.r.t. the rest of inputs
+    ///     * error: VerifyError::Malformed if the inputs are invalid,
+    ///       VerifyError::PairingCheckFailed if the proof is invalid
+    pub fn try_same_commit_batch_verify_scalars(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &GenericCommitment<E>,
+        set: &[usize],
+        scalar_sub_vector: &[E::Fr],
+    ) -> Result<(), VerifyError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        try_same_commit_batch_verify_core(
+            self,
+            verifier_params,
+            com,
+            set,
+            &scalars_to_blobs(scalar_sub_vector),
+            scalar_sub_vector,
+        )
+    }
 
-        // 2 check
-        //   e(com, g2^{\sum_{i \in set} \alpha^{N+1-i} t_i * tmp})
-        //    * e(proof^{-tmp}, g2)
-        //    ?= e(g1, g2)^{alpha^N+1}
+    /// Verify a proof which was aggregated from 2-dim array of proofs
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the list of commitments
+    ///     * input: a 2-dim array of indices
+    ///     * input: a 2-dim array of values
+    ///     * output: if the proof is valid w.r.t. the rest of inputs
+    pub fn cross_commit_batch_verify<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &[GenericCommitment<E>],
+        set: &[Vec<usize>],
+        value_sub_vector: &[Vec<Blob>],
+    ) -> bool
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        self.try_cross_commit_batch_verify(verifier_params, com, set, value_sub_vector)
+            .is_ok()
+    }
 
-        // 2.1 compute t_i*tmp
-        let ti_repr: Vec<FrRepr> = (0..ti.len())
-            .map(|k| {
-                ti[k].mul_assign(&tmp);
-                ti[k].into_repr()
-            })
-            .collect();
+    /// Verify a proof which was aggregated from 2-dim array of proofs,
+    /// and explain why it is rejected
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the list of commitments
+    ///     * input: a 2-dim array of indices
+    ///     * input: a 2-dim array of values
+    ///     * output: Ok(()) if the proof is valid w.r.t. the rest of inputs
+    ///     * error: VerifyError::Malformed if the inputs are invalid,
+    ///       VerifyError::PairingCheckFailed if the proof is invalid
+    pub fn try_cross_commit_batch_verify<Blob: AsRef<[u8]>>(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &[GenericCommitment<E>],
+        set: &[Vec<usize>],
+        value_sub_vector: &[Vec<Blob>],
+    ) -> Result<(), VerifyError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        // check the validity of the ciphersuites before hashing the values;
+        // values beyond com.len() are rejected by the length checks without being hashed
+        for e in com {
+            check_verify_ciphersuites(verifier_params, e, self)?;
+        }
 
-        // 2.2 g2^{\sum_{i \in set} \alpha^{N+1-i} t_i*tmp}
-        let bases: Vec<PointproofsG2Affine> = set
+        let scalar_sub_vector: Vec<Vec<E::Fr>> = value_sub_vector
             .iter()
-            .map(|index| verifier_params.generators[verifier_params.n - index - 1])
+            .take(com.len())
+            .map(|e| hash_to_field_vec_pointproofs(verifier_params.ciphersuite, e))
             .collect();
-        let scalars_u64: Vec<&[u64; 4]> = ti_repr.iter().map(|s| &s.0).collect();
-        let param_subset_sum = {
-            // the second condition `n <= 1024` comes from benchmarking
-            // pre-computation is faster only when the #basis is <1024
-            if verifier_params.precomp.len() == 256 * verifier_params.n && bases.len() <= 1024 {
-                let mut bases_precomp: Vec<PointproofsG2Affine> =
-                    Vec::with_capacity(bases.len() * 256);
-                for e in set.iter() {
-                    bases_precomp = [
-                        bases_precomp,
-                        verifier_params.precomp
-                            [(verifier_params.n - *e - 1) * 256..(verifier_params.n - *e) * 256]
-                            .to_vec(),
-                    ]
-                    .concat();
-                }
-                PointproofsG2Affine::sum_of_products_precomp_256(
-                    &bases,
-                    &scalars_u64,
-                    &bases_precomp,
-                )
-            } else {
-                PointproofsG2Affine::sum_of_products(&bases, &scalars_u64)
-            }
-        };
-
-        // 2.3 proof ^ {-tmp}
-        let mut proof_mut = self.proof;
-        tmp.negate();
-        proof_mut.mul_assign(tmp);
-
-        // 3 pairing product
-        pointproofs_pairing_product(
-            com.commit.into_affine(),
-            param_subset_sum.into_affine(),
-            proof_mut.into_affine(),
-            PointproofsG2Affine::one(),
-        ) == verifier_params.gt_elt
+        try_cross_commit_batch_verify_core(
+            self,
+            verifier_params,
+            com,
+            set,
+            value_sub_vector,
+            &scalar_sub_vector,
+        )
     }
 
-    /// Verify a proof which was aggregated from 2-dim array of proofs
+    /// Verify a proof for field elements which was aggregated from 2-dim array of proofs
     ///     * input: the proof
     ///     * input: verifier parameter set
     ///     * input: the list of commitments
     ///     * input: a 2-dim array of indices
-    ///     * input: a 2-dim array of values
+    ///     * input: a 2-dim array of field elements
     ///     * output: if the proof is valid w.r.t. the rest of inputs
-    pub fn cross_commit_batch_verify<Blob: AsRef<[u8]>>(
+    pub fn cross_commit_batch_verify_scalars(
         &self,
-        verifier_params: &VerifierParams,
-        com: &[Commitment],
+        verifier_params: &GenericVerifierParams<E>,
+        com: &[GenericCommitment<E>],
         set: &[Vec<usize>],
-        value_sub_vector: &[Vec<Blob>],
-    ) -> bool {
-        // check ciphersuite
-        if self.ciphersuite != verifier_params.ciphersuite {
-            return false;
+        scalar_sub_vector: &[Vec<E::Fr>],
+    ) -> bool
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        self.try_cross_commit_batch_verify_scalars(verifier_params, com, set, scalar_sub_vector)
+            .is_ok()
+    }
+
+    /// Verify a proof for field elements which was aggregated from 2-dim array of proofs,
+    /// and explain why it is rejected
+    ///     * input: the proof
+    ///     * input: verifier parameter set
+    ///     * input: the list of commitments
+    ///     * input: a 2-dim array of indices
+    ///     * input: a 2-dim array of field elements
+    ///     * output: Ok(()) if the proof is valid w.r.t. the rest of inputs
+    ///     * error: VerifyError::Malformed if the inputs are invalid,
+    ///       VerifyError::PairingCheckFailed if the proof is invalid
+    pub fn try_cross_commit_batch_verify_scalars(
+        &self,
+        verifier_params: &GenericVerifierParams<E>,
+        com: &[GenericCommitment<E>],
+        set: &[Vec<usize>],
+        scalar_sub_vector: &[Vec<E::Fr>],
+    ) -> Result<(), VerifyError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        let value_sub_vector: Vec<Vec<Vec<u8>>> = scalar_sub_vector
+            .iter()
+            .map(|e| scalars_to_blobs(e))
+            .collect();
+        try_cross_commit_batch_verify_core(
+            self,
+            verifier_params,
+            com,
+            set,
+            &value_sub_vector,
+            scalar_sub_vector,
+        )
+    }
+}
+
+impl<E: Engine> std::cmp::PartialEq for GenericProof<E> {
+    /// Convenient function to compare proofs
+    fn eq(&self, other: &Self) -> bool {
+        self.ciphersuite == other.ciphersuite && self.proof == other.proof
+    }
+}
+
+/// Checks that the verifier parameters, the commitment and the proof
+/// carry a same, supported ciphersuite, and that the commitment
+/// is not bound to other parameters.
+fn check_verify_ciphersuites<E: Engine>(
+    verifier_params: &GenericVerifierParams<E>,
+    com: &GenericCommitment<E>,
+    proof: &GenericProof<E>,
+) -> Result<(), PointproofsError> {
+    if !check_ciphersuite(verifier_params.ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite(
+            verifier_params.ciphersuite,
+        ));
+    }
+    for csid in &[com.ciphersuite, proof.ciphersuite] {
+        if *csid != verifier_params.ciphersuite {
+            return Err(PointproofsError::CiphersuiteMismatch {
+                expected: verifier_params.ciphersuite,
+                found: *csid,
+            });
         }
-        for e in com {
-            if self.ciphersuite != e.ciphersuite {
-                return false;
-            }
+    }
+    check_params_id(com, verifier_params.params_id)
+}
+
+/// Checks that a set of indices to be batch verified is non-empty, within range,
+/// free of duplicates, and matches the list of values.
+fn check_verify_set<E: Engine, Blob: AsRef<[u8]>>(
+    verifier_params: &GenericVerifierParams<E>,
+    set: &[usize],
+    value_sub_vector: &[Blob],
+) -> Result<(), PointproofsError> {
+    if set.len() != value_sub_vector.len() {
+        return Err(PointproofsError::IndexValueMismatch {
+            indices: set.len(),
+            values: value_sub_vector.len(),
+        });
+    }
+    if set.is_empty() || set.len() > verifier_params.n {
+        return Err(PointproofsError::InvalidIndexCount {
+            count: set.len(),
+            n: verifier_params.n,
+        });
+    }
+    for e in set {
+        if *e >= verifier_params.n {
+            return Err(PointproofsError::InvalidIndex {
+                index: *e,
+                n: verifier_params.n,
+            });
         }
+    }
+    misc::check_unique_indices(set)
+}
 
-        // check length
-        let num_commit = com.len();
-        if num_commit != set.len() || num_commit != value_sub_vector.len() || num_commit == 0 {
-            // length does not match
-            return false;
+/// Computes `Proof::batch_new_aggregated` given both the values, which are
+/// hashed into t_i, and the field elements that are committed to.
+/// `values` and `scalars` have a same length.
+fn batch_new_aggregated_core<E: Engine, Blob: AsRef<[u8]>>(
+    prover_params: &GenericProverParams<E>,
+    commit: &GenericCommitment<E>,
+    values: &[Blob],
+    scalars: &[E::Fr],
+    indices: &[usize],
+) -> Result<GenericProof<E>, PointproofsError>
+where
+    GenericCommitment<E>: SerDes,
+{
+    // checks that cipersuite is supported
+    if !check_ciphersuite(prover_params.ciphersuite) {
+        return Err(PointproofsError::InvalidCiphersuite(
+            prover_params.ciphersuite,
+        ));
+    }
+    if prover_params.ciphersuite != commit.ciphersuite {
+        return Err(PointproofsError::CiphersuiteMismatch {
+            expected: prover_params.ciphersuite,
+            found: commit.ciphersuite,
+        });
+    }
+    check_params_id(commit, prover_params.params_id)?;
+
+    // check index is valid
+    if indices.len() > prover_params.n || indices.is_empty() {
+        return Err(PointproofsError::InvalidIndexCount {
+            count: indices.len(),
+            n: prover_params.n,
+        });
+    };
+    for e in indices {
+        if *e >= prover_params.n {
+            return Err(PointproofsError::InvalidIndex {
+                index: *e,
+                n: prover_params.n,
+            });
         }
-        for j in 0..num_commit {
-            if set[j].len() != value_sub_vector[j].len()
-                || set[j].is_empty()
-                || set[j].len() > verifier_params.n
-            {
-                // length does not match
-                return false;
-            }
+    }
+
+    misc::check_unique_indices(indices)?;
+
+    // check param
+    if values.len() != prover_params.n {
+        return Err(PointproofsError::InvalidValueCount {
+            expected: prover_params.n,
+            found: values.len(),
+        });
+    }
+
+    // generate the list of sub_values
+    let mut value_sub_vector: Vec<&[u8]> = Vec::with_capacity(values.len());
+    for e in indices {
+        value_sub_vector.push(values[*e].as_ref());
+    }
+
+    // get the list of scalars for each proof
+    let ti = hash_to_ti_fr(commit, indices, &value_sub_vector, prover_params.n)?;
+
+    // form the final scalars, which are ti[k]*m[n - indices[k] + j] for each index
+    let mut final_scalars: Vec<E::Fr> = vec![E::Fr::zero(); 2 * prover_params.n];
+    for k in 0..indices.len() {
+        for j in 0..prover_params.n {
+            let mut tmp = ti[k];
+            tmp.mul_assign(&scalars[j]);
+            final_scalars[prover_params.n - indices[k] + j].add_assign(&tmp);
         }
-        for e in set.iter() {
-            if !misc::has_unique_elements(e) {
-                return false;
+    }
+
+    // compute the final aggregated proof
+    let agg_proof = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+        batch_new_aggregated_helper::<E, G>(&prover_params, &final_scalars)
+    });
+
+    Ok(GenericProof {
+        ciphersuite: prover_params.ciphersuite,
+        proof: agg_proof,
+    })
+}
+
+/// Computes `Proof::try_same_commit_batch_verify` given both the values, which are
+/// hashed into t_i, and the field elements that are committed to.
+/// `value_sub_vector` and `scalar_sub_vector` have a same length.
+fn try_same_commit_batch_verify_core<E: Engine, Blob: AsRef<[u8]>>(
+    proof: &GenericProof<E>,
+    verifier_params: &GenericVerifierParams<E>,
+    com: &GenericCommitment<E>,
+    set: &[usize],
+    value_sub_vector: &[Blob],
+    scalar_sub_vector: &[E::Fr],
+) -> Result<(), VerifyError>
+where
+    GenericCommitment<E>: SerDes,
+{
+    // we want to check if
+    //   e(com, g2^{\sum_{i \in set} \alpha^{N+1-i} t_i})
+    //    ?= e(proof, g2) * e(g1, g2)^{alpha^{N+1} \sum value_i*t_i}
+    // which is to check
+    //   e(com, g2^{\sum_{i \in set} \alpha^{N+1-i} t_i * tmp})
+    //    * e(proof^{-tmp}, g2)
+    //    ?= e(g1, g2)^{alpha^N+1}
+    // where
+    //   tmp = 1/ \sum value_i*t_i
+
+    // 0. check the validity of the inputs: csid, length, etc
+    check_verify_ciphersuites(verifier_params, com, proof)?;
+    check_verify_set(verifier_params, set, value_sub_vector)?;
+
+    // if the length == 1, call normal verification method
+    if set.len() == 1 {
+        return proof.try_verify_scalar(&verifier_params, &com, scalar_sub_vector[0], set[0]);
+    }
+    // 1. compute tmp
+    // 1.1 get the list of scalas, return an error if this failed
+    let mut ti = hash_to_ti_fr(com, set, value_sub_vector, verifier_params.n)?;
+
+    // 1.2 tmp = 1/\sum value_i*t_i
+    let mut tmp = E::Fr::zero();
+    for k in 0..set.len() {
+        let mut mi = scalar_sub_vector[k];
+        mi.mul_assign(&ti[k]);
+        tmp.add_assign(&mi);
+    }
+
+    // 1.3 if \sum value_i*t_i == 0 (only possible for field elements),
+    // the right-hand side becomes 1 and tmp = 1
+    let (tmp, target) = scale_and_target::<E>(&tmp, &verifier_params.gt_elt);
+
+    // 2 check
+    //   e(com, g2^{\sum_{i \in set} \alpha^{N+1-i} t_i * tmp})
+    //    * e(proof^{-tmp}, g2)
+    //    ?= e(g1, g2)^{alpha^N+1}
+
+    // 2.1 compute t_i*tmp
+    let ti_u64: Vec<[u64; 4]> = (0..ti.len())
+        .map(|k| {
+            ti[k].mul_assign(&tmp);
+            repr_to_u64(&ti[k].into_repr())
+        })
+        .collect();
+
+    // 2.2 - 3 within the groups of the ciphersuite
+    if dispatch_groups!(group_orientation(verifier_params.ciphersuite), G => {
+        same_commit_batch_verify_helper::<E, G>(verifier_params, com, proof, set, &ti_u64, tmp, target)
+    }) {
+        Ok(())
+    } else {
+        Err(VerifyError::PairingCheckFailed)
+    }
+}
+
+/// Computes `Proof::try_cross_commit_batch_verify` given both the values, which are
+/// hashed into t_i and t_j, and the field elements that are committed to.
+/// `value_sub_vector` and `scalar_sub_vector` have a same shape,
+/// except that `scalar_sub_vector` may be cut to `com.len()` rows.
+fn try_cross_commit_batch_verify_core<E: Engine, Blob: AsRef<[u8]>>(
+    proof: &GenericProof<E>,
+    verifier_params: &GenericVerifierParams<E>,
+    com: &[GenericCommitment<E>],
+    set: &[Vec<usize>],
+    value_sub_vector: &[Vec<Blob>],
+    scalar_sub_vector: &[Vec<E::Fr>],
+) -> Result<(), VerifyError>
+where
+    GenericCommitment<E>: SerDes,
+{
+    // check ciphersuite
+    for e in com {
+        check_verify_ciphersuites(verifier_params, e, proof)?;
+    }
+
+    // check length
+    let num_commit = com.len();
+    for len in &[set.len(), value_sub_vector.len()] {
+        if num_commit != *len || num_commit == 0 {
+            return Err(PointproofsError::CrossCommitSizeMismatch {
+                commits: num_commit,
+                found: *len,
             }
+            .into());
         }
+    }
+    for j in 0..num_commit {
+        check_verify_set(verifier_params, &set[j], &value_sub_vector[j])?;
+    }
 
-        // handled the case where there is only 1 commit
-        if num_commit == 1 {
-            // call normal batch verification
-            return self.same_commit_batch_verify(
-                &verifier_params,
-                &com[0],
-                &set[0],
-                &value_sub_vector[0],
-            );
+    // handled the case where there is only 1 commit
+    if num_commit == 1 {
+        // call normal batch verification
+        return try_same_commit_batch_verify_core(
+            proof,
+            &verifier_params,
+            &com[0],
+            &set[0],
+            &value_sub_vector[0],
+            &scalar_sub_vector[0],
+        );
+    }
+
+    // generate all the t_i-s for j \in [num_commit]
+    let mut ti_s: Vec<Vec<E::Fr>> = Vec::with_capacity(num_commit);
+    for j in 0..num_commit {
+        ti_s.push(hash_to_ti_fr(
+            &com[j],
+            &set[j],
+            &value_sub_vector[j],
+            verifier_params.n,
+        )?);
+    }
+    // generate tj
+    let tj = hash_to_tj_repr(&com, &set, &value_sub_vector, verifier_params.n)?;
+
+    // we want to check
+    //  \prod_{j=1}^num_commit e(com[j], g2^{\sum alpha^{n + 1 -i} * t_i,j} ) ^ t_j
+    //      ?= e (proof, g2) * e (g1, g2)^{alpha^{n+1} * {\sum m_i,j * t_i,j * tj}}
+    // step 1. compute tmp = \sum m_i,j * t_i,j * tj
+    let mut tmp = E::Fr::zero();
+    for j in 0..num_commit {
+        let mut tmp2 = E::Fr::zero();
+
+        // tmp2 = sum_i m_ij * t_ij
+        for k in 0..ti_s[j].len() {
+            let mut tmp3 = ti_s[j][k];
+            tmp3.mul_assign(&scalar_sub_vector[j][k]);
+            tmp2.add_assign(&tmp3);
         }
+        // tmp2 = tj * tmp2
+        // safe to unwrap here
+        // the output of hash should always be a field element
+        let tmp3 = E::Fr::from_repr(tj[j]).unwrap();
+        tmp2.mul_assign(&tmp3);
+        // tmp += tj * (sum_i m_ji * t_ij)
+        tmp.add_assign(&tmp2);
+    }
 
-        // generate all the t_i-s for j \in [num_commit]
-        let mut ti_s: Vec<Vec<Fr>> = Vec::with_capacity(num_commit);
-        for j in 0..num_commit {
-            let ti = match hash_to_ti_fr(&com[j], &set[j], &value_sub_vector[j], verifier_params.n)
-            {
-                Err(_e) => return false,
-                Ok(p) => p,
-            };
-            ti_s.push(ti);
-        }
-        // generate tj
-        let tj = match hash_to_tj_repr(&com, &set, &value_sub_vector, verifier_params.n) {
-            Err(_e) => return false,
-            Ok(p) => p,
-        };
+    // if tmp == 0 (only possible for field elements),
+    // the right-hand side becomes 1 and 1/tmp is replaced by 1
+    let (tmp_inverse, target) = scale_and_target::<E>(&tmp, &verifier_params.gt_elt);
 
-        // we want to check
-        //  \prod_{j=1}^num_commit e(com[j], g2^{\sum alpha^{n + 1 -i} * t_i,j} ) ^ t_j
-        //      ?= e (proof, g2) * e (g1, g2)^{alpha^{n+1} * {\sum m_i,j * t_i,j * tj}}
-        // step 1. compute tmp = \sum m_i,j * t_i,j * tj
-        let mut tmp = Fr::zero();
-        for j in 0..num_commit {
-            let mut tmp2 = Fr::zero();
-
-            // tmp2 = sum_i m_ij * t_ij
-            for k in 0..ti_s[j].len() {
-                let mut tmp3 = ti_s[j][k];
-                let mij = hash_to_field_pointproofs(value_sub_vector[j][k].as_ref());
-                tmp3.mul_assign(&mij);
-                tmp2.add_assign(&tmp3);
-            }
-            // tmp2 = tj * tmp2
-            // safe to unwrap here
-            // the output of hash should always be a field element
-            let tmp3 = Fr::from_repr(tj[j]).unwrap();
-            tmp2.mul_assign(&tmp3);
-            // tmp += tj * (sum_i m_ji * t_ij)
-            tmp.add_assign(&tmp2);
+    // step 2. now the formula becomes
+    // \prod e(com[j], g2^{\sum alpha^{n + 1 - i} * t_i,j * tj/tmp} )
+    //  * e(proof^{-1/tmp}, g2)
+    //  ?= e(g1, g2)^{alpha^{n+1}} == verifier_params.gt_elt
+
+    if dispatch_groups!(group_orientation(verifier_params.ciphersuite), G => {
+        cross_commit_batch_verify_helper::<E, G>(
+            verifier_params,
+            com,
+            proof,
+            set,
+            &ti_s,
+            &tj,
+            tmp_inverse,
+            target,
+        )
+    }) {
+        Ok(())
+    } else {
+        Err(VerifyError::PairingCheckFailed)
+    }
+}
+
+/// Returns the factor by which both sides of a verification equation
+/// e(com, ...) = gt_elt^m * e(proof, g2) are scaled, and the resulting target:
+/// (1/m, gt_elt) if m != 0. Hashed values are never 0, but field elements can be:
+/// then the equation is e(com, ...) = e(proof, g2), and (1, 1) is returned.
+fn scale_and_target<E: Engine>(m: &E::Fr, gt_elt: &E::Fqk) -> (E::Fr, E::Fqk) {
+    match m.inverse() {
+        Some(m_inverse) => (m_inverse, *gt_elt),
+        None => (E::Fr::one(), E::Fqk::one()),
+    }
+}
+
+/// Computes the aggregated proof \prod generators[i]^final_scalars[i]
+/// for `Proof::batch_new_aggregated`.
+fn batch_new_aggregated_helper<E: Engine, G: PointproofsGroups<E>>(
+    prover_params: &GenericProverParams<E>,
+    final_scalars: &[E::Fr],
+) -> PointproofsG1<E> {
+    // remove the generators where the scalars are 0s, to form the final basis
+    // also convert Fr-s to FrRepr-s to [u64;4]-s
+    let mut final_indices: Vec<usize> = Vec::with_capacity(2 * prover_params.n);
+    let mut final_scalars_u64: Vec<[u64; 4]> = Vec::with_capacity(2 * prover_params.n);
+    for (i, e) in final_scalars.iter().enumerate() {
+        if !e.is_zero() {
+            final_scalars_u64.push(repr_to_u64(&e.into_repr()));
+            final_indices.push(i);
         }
+    }
+    let scalars_u64: Vec<&[u64; 4]> = final_scalars_u64.iter().collect();
+    wrap_pp_subset_sum_of_prod::<E, G>(prover_params, &final_indices, &scalars_u64)
+}
 
-        let tmp_inverse = match tmp.inverse() {
-            Some(p) => p,
-            // tmp == 0 should never happen in practice
-            None => panic!("some thing is wrong, check verification formula"),
-        };
+/// Computes the proofs for all indices, for `Proof::all_proofs_from_scalars`.
+/// proof[i] = \prod_j generators[n + j - i]^scalars[j] is a Toeplitz matrix-vector product.
+/// With the scalars in reverse order, it is a convolution, which is computed as a
+/// cyclic convolution of size >= 2n via FFTs:
+///     proof[n - 1 - r] = IFFT(FFT(c) * FFT(scalars[n - 1], .., scalars[0], 0, .., 0))[r]
+/// where c = [1, generators[n + 1], .., generators[2n - 1],
+///     1, .., 1, generators[1], .., generators[n - 1]].
+fn all_proofs_helper<E: Engine, G: PointproofsGroups<E>>(
+    prover_params: &GenericProverParams<E>,
+    scalars: &[E::Fr],
+    omega: &E::Fr,
+) -> Vec<PointproofsG1<E>> {
+    let n = prover_params.n;
+    let size = (2 * n).next_power_of_two();
+    let generators = G::g1_slice(&prover_params.generators);
+
+    // the first column of the circulant matrix, in the exponent;
+    // c[0] is for generators[n], the identity
+    let mut c: Vec<G::G1> = vec![G::G1::zero(); size];
+    for t in 1..n {
+        c[t] = generators[n + t].into_projective();
+        c[size - t] = generators[n - t].into_projective();
+    }
+
+    // the reversed scalars, padded with 0s;
+    // they are also multiplied by 1/size, for the inverse FFT
+    let mut size_inverse = E::Fr::one();
+    for _ in 0..size.trailing_zeros() {
+        size_inverse.double();
+    }
+    // safe to unwrap: size is a power of 2, and the characteristic is odd
+    let size_inverse = size_inverse.inverse().unwrap();
+    let mut m: Vec<E::Fr> = vec![E::Fr::zero(); size];
+    for (j, e) in scalars.iter().enumerate() {
+        m[n - 1 - j] = *e;
+        m[n - 1 - j].mul_assign(&size_inverse);
+    }
+
+    // y = IFFT(FFT(c) * FFT(m))
+    fft::fft_fr(&mut m, omega);
+    fft::fft_group(&mut c, omega);
+    for (ck, mk) in c.iter_mut().zip(m.iter()) {
+        ck.mul_assign(*mk);
+    }
+    // safe to unwrap: omega is a root of unity
+    fft::fft_group(&mut c, &omega.inverse().unwrap());
+
+    (0..n).map(|i| G::wrap_g1(c[n - 1 - i])).collect()
+}
+
+/// Computes \prod proofs[i]^scalars[i]; used by the aggregation functions.
+fn proofs_sum_of_prod_helper<E: Engine, G: PointproofsGroups<E>>(
+    proofs: &[GenericProof<E>],
+    scalars_u64: &[&[u64; 4]],
+) -> PointproofsG1<E> {
+    let mut bases: Vec<G::G1> = proofs.iter().map(|s| G::g1(&s.proof)).collect();
+    CurveProjective::batch_normalization(&mut bases);
+    // `into_affine()` here only performs a type conversion
+    // the CurveProjective points are already normalized via batch nomarlization
+    let bases_affine: Vec<G::G1Affine> = bases.iter().map(|s| s.into_affine()).collect();
+    G::wrap_g1(sum_of_prod(&bases_affine[..], &scalars_u64))
+}
+
+/// Step 2 and 3 of `Proof::try_verify_scalar`:
+///     e(com^scale,  param[n-index-1]) * e(proof^{-scale}, generator_of_g2)
+///     ?= target
+/// where (scale, target) is given by `scale_and_target`.
+fn verify_helper<E: Engine, G: PointproofsGroups<E>>(
+    verifier_params: &GenericVerifierParams<E>,
+    com: &GenericCommitment<E>,
+    proof: &GenericProof<E>,
+    scale: E::Fr,
+    target: E::Fqk,
+    index: usize,
+) -> bool {
+    // step 2, compute com^scale and proof^{-scale}
+    let mut com_mut = G::g1(&com.commit);
+    let mut proof_mut = G::g1(&proof.proof);
+    proof_mut.negate();
+    com_mut.mul_assign(scale);
+    proof_mut.mul_assign(scale);
+
+    // step 3. check pairing product
+    G::pairing_product(
+        com_mut.into_affine(),
+        G::g2_slice(&verifier_params.generators)[verifier_params.n - index - 1],
+        proof_mut.into_affine(),
+        G::G2Affine::one(),
+    ) == target
+}
+
+/// Computes g2^{\sum_{i \in set} \alpha^{N+1-i} scalars_i}
+/// from the verifier generators, using their pre-computed data, see `PrecompTable`.
+fn vp_subset_sum_helper<E: Engine, G: PointproofsGroups<E>>(
+    verifier_params: &GenericVerifierParams<E>,
+    set: &[usize],
+    scalars_u64: &[&[u64; 4]],
+) -> G::G2 {
+    let param_indices: Vec<usize> = set
+        .iter()
+        .map(|index| verifier_params.n - index - 1)
+        .collect();
+    vp_table::<E, G>(verifier_params).subset_sum_of_prod(&param_indices, scalars_u64)
+}
+
+/// Step 2.2 - 3 of `Proof::try_same_commit_batch_verify`:
+///   e(com, g2^{\sum_{i \in set} \alpha^{N+1-i} t_i * tmp})
+///    * e(proof^{-tmp}, g2)
+///    ?= target
+/// where `ti_u64` holds t_i * tmp, and (tmp, target) is given by `scale_and_target`.
+fn same_commit_batch_verify_helper<E: Engine, G: PointproofsGroups<E>>(
+    verifier_params: &GenericVerifierParams<E>,
+    com: &GenericCommitment<E>,
+    proof: &GenericProof<E>,
+    set: &[usize],
+    ti_u64: &[[u64; 4]],
+    mut tmp: E::Fr,
+    target: E::Fqk,
+) -> bool {
+    // 2.2 g2^{\sum_{i \in set} \alpha^{N+1-i} t_i*tmp}
+    let scalars_u64: Vec<&[u64; 4]> = ti_u64.iter().collect();
+    let param_subset_sum = vp_subset_sum_helper::<E, G>(verifier_params, set, &scalars_u64);
+
+    // 2.3 proof ^ {-tmp}
+    let mut proof_mut = G::g1(&proof.proof);
+    tmp.negate();
+    proof_mut.mul_assign(tmp);
+
+    // 3 pairing product
+    G::pairing_product(
+        G::g1(&com.commit).into_affine(),
+        param_subset_sum.into_affine(),
+        proof_mut.into_affine(),
+        G::G2Affine::one(),
+    ) == target
+}
 
-        // step 2. now the formula becomes
-        // \prod e(com[j], g2^{\sum alpha^{n + 1 - i} * t_i,j * tj/tmp} )
-        //  * e(proof^{-1/tmp}, g2)
-        //  ?= e(g1, g2)^{alpha^{n+1}} == verifier_params.gt_elt
-
-        // g1_vec stores the g1 components for the pairing product
-        // for j \in [num_commit], store com[j]
-        let mut g1_proj: Vec<PointproofsG1> = com.iter().map(|x| x.commit).collect();
-        // the last element for g1_vec is proof^{-1/tmp}
-        let mut tmp2 = self.proof;
-        tmp2.negate();
-        tmp2.mul_assign(tmp_inverse);
-        g1_proj.push(tmp2);
-
-        // convert g1_proj into g1_affine
-        PointproofsG1::batch_normalization(&mut g1_proj);
-        let g1_vec: Vec<PointproofsG1Affine> = g1_proj.iter().map(|s| s.into_affine()).collect();
-
-        // g2_vec stores the g2 components for the pairing product
-        // for j \in [num_commit], g2^{\sum alpha^{n + 1 - i} * t_i,j} * tj/tmp )
-        let mut g2_proj: Vec<PointproofsG2> = Vec::with_capacity(num_commit + 1);
-        for j in 0..num_commit {
-            let num_proof = ti_s[j].len();
+/// Step 2 of `Proof::try_cross_commit_batch_verify`:
+/// \prod e(com[j], g2^{\sum alpha^{n + 1 - i} * t_i,j * tj/tmp} )
+///  * e(proof^{-1/tmp}, g2)
+///  ?= target
+/// where (1/tmp, target) is given by `scale_and_target`.
+#[allow(clippy::too_many_arguments)]
+fn cross_commit_batch_verify_helper<E: Engine, G: PointproofsGroups<E>>(
+    verifier_params: &GenericVerifierParams<E>,
+    com: &[GenericCommitment<E>],
+    proof: &GenericProof<E>,
+    set: &[Vec<usize>],
+    ti_s: &[Vec<E::Fr>],
+    tj: &[<E::Fr as PrimeField>::Repr],
+    tmp_inverse: E::Fr,
+    target: E::Fqk,
+) -> bool {
+    let num_commit = com.len();
+
+    // g1_vec stores the g1 components for the pairing product
+    // for j \in [num_commit], store com[j]
+    let mut g1_proj: Vec<G::G1> = com.iter().map(|x| G::g1(&x.commit)).collect();
+    // the last element for g1_vec is proof^{-1/tmp}
+    let mut tmp2 = G::g1(&proof.proof);
+    tmp2.negate();
+    tmp2.mul_assign(tmp_inverse);
+    g1_proj.push(tmp2);
+
+    // convert g1_proj into g1_affine
+    G::G1::batch_normalization(&mut g1_proj);
+    let g1_vec: Vec<G::G1Affine> = g1_proj.iter().map(|s| s.into_affine()).collect();
+
+    // g2_vec stores the g2 components for the pairing product
+    // for j \in [num_commit], g2^{\sum alpha^{n + 1 - i} * t_i,j} * tj/tmp )
+    // with the `parallel` feature, the commitments are processed on the thread pool
+    #[cfg(feature = "parallel")]
+    let commit_iter = (0..num_commit).into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let commit_iter = 0..num_commit;
+    let mut g2_proj: Vec<G::G2> = commit_iter
+        .map(|j| {
             let mut tmp3 = tmp_inverse;
             // safe to unwrap here
             // the output of hash should always be a field element
-            let scalar = Fr::from_repr(tj[j]).unwrap();
+            let scalar = E::Fr::from_repr(tj[j]).unwrap();
             tmp3.mul_assign(&scalar);
 
             // subset_sum = \sum alpha^{n + 1 - i} * t_i,j}
-            let mut bases: Vec<PointproofsG2Affine> = Vec::with_capacity(num_proof);
-            let mut scalars_u64: Vec<[u64; 4]> = Vec::with_capacity(num_proof);
-            for k in 0..num_proof {
-                bases.push(verifier_params.generators[verifier_params.n - set[j][k] - 1]);
-                let mut t = ti_s[j][k];
-                t.mul_assign(&tmp3);
-                scalars_u64.push(t.into_repr().0);
-            }
+            let scalars_u64: Vec<[u64; 4]> = ti_s[j]
+                .iter()
+                .map(|t| {
+                    let mut t = *t;
+                    t.mul_assign(&tmp3);
+                    repr_to_u64(&t.into_repr())
+                })
+                .collect();
             let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
 
-            let param_subset_sum = {
-                // the second condition `n <= 1024` comes from benchmarking
-                // pre-computation is faster only when the #basis is <1024
-                if verifier_params.precomp.len() == 256 * verifier_params.n && bases.len() <= 1024 {
-                    let mut bases_precomp: Vec<PointproofsG2Affine> =
-                        Vec::with_capacity(num_proof * 256);
-                    for k in 0..num_proof {
-                        bases_precomp = [
-                            bases_precomp,
-                            verifier_params.precomp[(verifier_params.n - set[j][k] - 1) * 256
-                                ..(verifier_params.n - set[j][k]) * 256]
-                                .to_vec(),
-                        ]
-                        .concat();
-                    }
-                    PointproofsG2Affine::sum_of_products_precomp_256(
-                        &bases,
-                        &scalars_u64_ref,
-                        &bases_precomp,
-                    )
-                } else {
-                    PointproofsG2Affine::sum_of_products(&bases, &scalars_u64_ref)
-                }
-            };
-            g2_proj.push(param_subset_sum);
-        }
-        // the last element for g1_vec is g2
-        g2_proj.push(PointproofsG2::one());
-        // convert g2_proj into g2_affine
-        PointproofsG2::batch_normalization(&mut g2_proj);
-        let g2_vec: Vec<PointproofsG2Affine> = g2_proj.iter().map(|s| s.into_affine()).collect();
-        // now check the pairing product ?= verifier_params.gt_elt
-        pointproofs_pairing_multi_product(&g1_vec[..], &g2_vec[..]) == verifier_params.gt_elt
-    }
+            vp_subset_sum_helper::<E, G>(verifier_params, &set[j], &scalars_u64_ref)
+        })
+        .collect();
+    // the last element for g1_vec is g2
+    g2_proj.push(G::G2::one());
+    // convert g2_proj into g2_affine
+    G::G2::batch_normalization(&mut g2_proj);
+    let g2_vec: Vec<G::G2Affine> = g2_proj.iter().map(|s| s.into_affine()).collect();
+    // now check the pairing product ?= target
+    G::pairing_multi_product(&g1_vec[..], &g2_vec[..]) == target
 }
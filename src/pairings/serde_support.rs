@@ -0,0 +1,122 @@
+//! this file is part of the pointproofs.
+//! It implements serde's `Serialize` and `Deserialize` for the public types,
+//! on top of their canonical, compressed, encodings in `pairings::serdes`:
+//!     * human-readable formats (e.g., JSON) get a lower case hex string
+//!     * binary formats (e.g., CBOR) get a byte string
+
+use pairing::serdes::SerDes;
+use pairing::Engine;
+use pairings::*;
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+macro_rules! impl_serde {
+    ($name:ident, $label:expr) => {
+        impl<E: Engine> Serialize for $name<E>
+        where
+            $name<E>: SerDes,
+        {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut bytes: Vec<u8> = vec![];
+                SerDes::serialize(self, &mut bytes, true).map_err(serde::ser::Error::custom)?;
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&to_hex(&bytes))
+                } else {
+                    serializer.serialize_bytes(&bytes)
+                }
+            }
+        }
+
+        impl<'de, E: Engine> Deserialize<'de> for $name<E>
+        where
+            $name<E>: SerDes,
+        {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let visitor = EncodingVisitor($label);
+                let bytes = if deserializer.is_human_readable() {
+                    deserializer.deserialize_str(visitor)?
+                } else {
+                    deserializer.deserialize_bytes(visitor)?
+                };
+                let mut reader = &bytes[..];
+                let res = <$name<E> as SerDes>::deserialize(&mut reader, true)
+                    .map_err(D::Error::custom)?;
+                if !reader.is_empty() {
+                    return Err(D::Error::custom(PointproofsError::MalformedEncoding(
+                        format!("{} trailing bytes", reader.len()),
+                    )));
+                }
+                Ok(res)
+            }
+        }
+    };
+}
+
+impl_serde!(GenericCommitment, "Commitment");
+impl_serde!(GenericProof, "Proof");
+impl_serde!(GenericProverParams, "ProverParams");
+impl_serde!(GenericVerifierParams, "VerifierParams");
+
+/// Collects a canonical encoding, given as a hex string or as bytes;
+/// holds the name of the encoded type.
+struct EncodingVisitor(&'static str);
+
+impl<'de> Visitor<'de> for EncodingVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the encoding of a {}, as a hex string or bytes", self.0)
+    }
+
+    fn visit_str<F: Error>(self, v: &str) -> Result<Vec<u8>, F> {
+        from_hex(v).map_err(F::custom)
+    }
+
+    fn visit_bytes<F: Error>(self, v: &[u8]) -> Result<Vec<u8>, F> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<F: Error>(self, v: Vec<u8>) -> Result<Vec<u8>, F> {
+        Ok(v)
+    }
+
+    // some binary formats encode bytes as a sequence of integers
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Lower case hex encoding.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hex decoding; accepts both cases.
+fn from_hex(s: &str) -> Result<Vec<u8>, PointproofsError> {
+    if s.len() % 2 != 0 {
+        return Err(PointproofsError::MalformedEncoding(format!(
+            "odd length hex string: {} characters",
+            s.len()
+        )));
+    }
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            match (
+                (pair[0] as char).to_digit(16),
+                (pair[1] as char).to_digit(16),
+            ) {
+                (Some(hi), Some(lo)) => Ok((hi * 16 + lo) as u8),
+                _ => Err(PointproofsError::MalformedEncoding(format!(
+                    "invalid hex digits: {:?}",
+                    String::from_utf8_lossy(pair)
+                ))),
+            }
+        })
+        .collect()
+}
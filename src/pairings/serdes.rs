@@ -1,20 +1,34 @@
 //! this file is part of the pointproofs.
 //! It defines APIs for (de)serialization.
+//! Group elements are either compressed or uncompressed, as selected
+//! by the `compressed` flag; uncompressed elements take twice the space,
+//! but decoding them does not take a square root.
+//! The target group element of the verifier parameters has a single encoding.
+//! Parameters are written in the versioned container of `container`,
+//! and read either from a container or in the headerless format.
 
-use pairing::bls12_381::*;
 use pairing::serdes::SerDes;
-use pairing::CurveProjective;
-use pairings::err::*;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pairings::ciphersuite::group_orientation;
+use pairings::container::*;
 use pairings::param::*;
+use pairings::params_id::*;
 use pairings::pointproofs_groups::*;
+use pairings::precomp::*;
 use pairings::*;
 
 type Compressed = bool;
-impl SerDes for Commitment {
+impl<E: Engine> SerDes for GenericCommitment<E>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+{
     /// Convert a pop into a blob:
     ///
     /// `|ciphersuite id| commit |` => bytes
     ///
+    /// The binding to a `params_id`, if any, is not written,
+    /// see `serialize_with_params_id`.
     /// Returns an error if ciphersuite id is invalid or serialization fails.
     fn serialize<W: std::io::Write>(
         &self,
@@ -23,22 +37,13 @@ impl SerDes for Commitment {
     ) -> std::io::Result<()> {
         // check the cipher suite id
         if !check_ciphersuite(self.ciphersuite) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
-            ));
-        }
-
-        // compressed must be true
-        if !compressed {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
+            return Err(PointproofsError::InvalidCiphersuite(self.ciphersuite).into());
         }
 
         let mut buf: Vec<u8> = vec![self.ciphersuite];
-        self.commit.into_affine().serialize(&mut buf, compressed)?;
+        dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            serialize_g1::<E, G, _>(&self.commit, &mut buf, compressed)
+        })?;
 
         // format the output
         writer.write_all(&buf)?;
@@ -49,19 +54,14 @@ impl SerDes for Commitment {
     ///
     /// bytes => `|ciphersuite id | commit |`
     ///
+    /// The blob does not carry the binding to a `params_id`: the commitment is unbound,
+    /// see `deserialize_with_params_id`.
     /// Returns an error if deserialization fails, or if
-    /// the commit is not compressed.
+    /// the commit is not in the expected (un)compressed form.
     fn deserialize<R: std::io::Read>(
         reader: &mut R,
         compressed: Compressed,
     ) -> std::io::Result<Self> {
-        // compressed must be true
-        if !compressed {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
         // constants stores id and the number of ssk-s
         let mut constants: [u8; 1] = [0u8; 1];
 
@@ -69,24 +69,28 @@ impl SerDes for Commitment {
 
         // check the ciphersuite id in the blob
         if !check_ciphersuite(constants[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
-            ));
+            return Err(PointproofsError::InvalidCiphersuite(constants[0]).into());
         }
 
-        // read into commit
-        let commit = PointproofsG1::deserialize(reader, compressed)?;
+        // read into commit; the group is selected by the ciphersuite id
+        let commit = dispatch_groups!(group_orientation(constants[0]), G => {
+            deserialize_g1::<E, G, _>(reader, compressed)?
+        });
 
         // finished
-        Ok(Commitment {
+        Ok(GenericCommitment {
             ciphersuite: constants[0],
             commit,
+            params_id: None,
         })
     }
 }
 
-impl SerDes for Proof {
+impl<E: Engine> SerDes for GenericProof<E>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+{
     /// Convert a pop into a blob:
     ///
     /// `|ciphersuite id| commit |` => bytes
@@ -97,22 +101,14 @@ impl SerDes for Proof {
         writer: &mut W,
         compressed: Compressed,
     ) -> std::io::Result<()> {
-        // compressed must be true
-        if !compressed {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
         // check the cipher suite id
         if !check_ciphersuite(self.ciphersuite) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
-            ));
+            return Err(PointproofsError::InvalidCiphersuite(self.ciphersuite).into());
         }
         let mut buf: Vec<u8> = vec![self.ciphersuite];
-        self.proof.into_affine().serialize(&mut buf, compressed)?;
+        dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            serialize_g1::<E, G, _>(&self.proof, &mut buf, compressed)
+        })?;
 
         // format the output
         writer.write_all(&buf)?;
@@ -124,18 +120,11 @@ impl SerDes for Proof {
     /// bytes => `|ciphersuite id | commit |`
     ///
     /// Returns an error if deserialization fails, or if
-    /// the commit is not compressed.
+    /// the commit is not in the expected (un)compressed form.
     fn deserialize<R: std::io::Read>(
         reader: &mut R,
         compressed: Compressed,
     ) -> std::io::Result<Self> {
-        // compressed must be true
-        if !compressed {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
         // constants stores ciphersuite id
         let mut constants: [u8; 1] = [0u8; 1];
 
@@ -143,276 +132,481 @@ impl SerDes for Proof {
 
         // check the ciphersuite id in the blob
         if !check_ciphersuite(constants[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
-            ));
+            return Err(PointproofsError::InvalidCiphersuite(constants[0]).into());
         }
 
-        // read into proof
-        let proof = PointproofsG1::deserialize(reader, compressed)?;
+        // read into proof; the group is selected by the ciphersuite id
+        let proof = dispatch_groups!(group_orientation(constants[0]), G => {
+            deserialize_g1::<E, G, _>(reader, compressed)?
+        });
 
         // finished
-        Ok(Proof {
+        Ok(GenericProof {
             ciphersuite: constants[0],
             proof,
         })
     }
 }
 
-impl SerDes for ProverParams {
+impl<E: Engine> SerDes for GenericProverParams<E>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+{
     /// Convert a ProverParam into a blob:
     ///
-    /// `|ciphersuite id | n | generators | pp_len | [pre_compute]` => bytes
+    /// `| header | params_id | verifier digest | ciphersuite id | n | generators | pp_len |
+    ///   [pre_compute] |` => bytes
     ///
+    /// where the header of the container carries a checksum of the rest,
+    /// the verifier digest is the one of the verifier parameters (see `params_id`),
+    /// and both are zeros if the parameters are not linked,
+    /// and `| ciphersuite id | ... |` is the headerless format.
     /// Returns an error if ciphersuite id is invalid or serialization fails.
     fn serialize<W: std::io::Write>(
         &self,
-        mut writer: &mut W,
+        writer: &mut W,
         compressed: Compressed,
     ) -> std::io::Result<()> {
         // check the cipher suite id
         if !check_ciphersuite(self.ciphersuite) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
-            ));
+            return Err(PointproofsError::InvalidCiphersuite(self.ciphersuite).into());
         }
-        if !compressed {
-            // we only support compress == true mode
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
+        // tables for part of the generators are not serialized
+        if self.precomputation != Precomputation::None && self.precomp_range != (0..self.n * 2) {
+            return Err(PointproofsError::PartialPrecomputation.into());
         }
         // check that #generators and #precomp matches sp value
-        if self.n * 2 != self.generators.len() || self.pp_len != self.precomp.len() || self.n == 0 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_INVALID_VALUE,
-            ));
+        let pp_len = self.precomp.len();
+        if self.n * 2 != self.generators.len()
+            || pp_len != self.n * 2 * points_per_generator::<E::Fr>(self.precomputation)
+            || self.n == 0
+        {
+            return Err(PointproofsError::InvalidParam.into());
         }
 
-        if self.n > (u32::max_value() as usize) || self.pp_len > (u32::max_value() as usize) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_INVALID_VALUE,
-            ));
+        if self.n > (u32::max_value() as usize) || pp_len > (u32::max_value() as usize) {
+            return Err(PointproofsError::InvalidParam.into());
         }
 
-        // write csid
-        writer.write_all(&[self.ciphersuite])?;
-        writer.write_all(&(self.n as u32).to_le_bytes())?;
+        let mut payload: Vec<u8> = encode_params_id(self.params_id).to_vec();
+        payload.extend_from_slice(&self.verifier_digest);
+        payload.push(self.ciphersuite);
+        payload.extend_from_slice(&(self.n as u32).to_le_bytes());
 
         // write the generators
-        for e in self.generators.iter() {
-            e.serialize(&mut writer, true)?;
-        }
-        writer.write_all(&(self.pp_len as u32).to_le_bytes())?;
-        if self.pp_len != 0 {
-            for e in self.precomp.iter() {
-                e.serialize(&mut writer, true)?;
-            }
+        dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            serialize_points(G::g1_slice(&self.generators), &mut payload, compressed)
+        })?;
+        payload.extend_from_slice(&(pp_len as u32).to_le_bytes());
+        if pp_len != 0 {
+            dispatch_groups!(group_orientation(self.ciphersuite), G => {
+                serialize_points(G::g1_slice(&self.precomp), &mut payload, compressed)
+            })?;
         }
 
-        Ok(())
+        ContainerHeader {
+            kind: ParamsKind::Prover,
+            ciphersuite: self.ciphersuite,
+            n: self.n,
+            precomputation: self.precomputation,
+            compressed,
+        }
+        .write(writer, &payload)
     }
 
     /// Convert a blob into a ProverParam:
     ///
-    /// bytes => `|ciphersuite id | n | generators | pp_len | [pre_compute]`
+    /// bytes => `| header | params_id | verifier digest | ciphersuite id | n | generators |
+    ///   pp_len | [pre_compute] |`
     ///
+    /// or, for the blobs written before the container, in the headerless format
+    /// `| ciphersuite id | n | generators | pp_len | [pre_compute] |`.
+    /// In a container, the checksum and the description of the parameters are checked,
+    /// and so is the `params_id` against the generators.
+    /// The headerless format does not carry the verifier digest: the parameters
+    /// have no `params_id` until they are linked with `link_params_id`.
     /// Returns an error if ciphersuite id is invalid or serialization fails.
     fn deserialize<R: std::io::Read>(
         reader: &mut R,
         compressed: Compressed,
     ) -> std::io::Result<Self> {
-        if !compressed {
-            // we only support compress == true mode
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
-        // read into buf of compressed size
-        let mut csid = vec![0u8; 1];
-        reader.read_exact(&mut csid)?;
-
-        if !check_ciphersuite(csid[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE.to_owned(),
-            ));
-        }
-
-        // read n
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        let n = u32::from_le_bytes(buf) as usize;
-        if n > 65536 || n == 0 {
-            // set an upper bounded of n
-            // to prevent potential DoS kind of attacks
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_MAX_N,
-            ));
-        }
-
-        // write csid
-        let mut generators: Vec<PointproofsG1Affine> = vec![];
-
-        // write the generators
-        for _i in 0..n * 2 {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
-            generators.push(g);
-        }
+        read_prover_params(reader, compressed, None)
+    }
+}
 
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        let pp_len = u32::from_le_bytes(buf) as usize;
+/// Reads prover parameters from a container or in the headerless format,
+/// and recomputes their `params_id`; fails if it is not `expected`,
+/// before reading the generators of a container.
+pub(crate) fn read_prover_params<E: Engine, R: std::io::Read>(
+    reader: &mut R,
+    compressed: Compressed,
+    expected: Option<&ParamsId>,
+) -> std::io::Result<GenericProverParams<E>>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+{
+    let (pp, header) = read_container(
+        reader,
+        ParamsKind::Prover,
+        compressed,
+        |mut payload, contained| {
+            deserialize_prover_params::<E, _>(&mut payload, compressed, expected, contained)
+        },
+    )?;
+    if let Some(header) = header {
+        header.check(pp.ciphersuite, pp.n, Some(pp.precomputation))?;
+    }
+    Ok(pp)
+}
 
-        let mut precomp: Vec<PointproofsG1Affine> = vec![];
-        for _i in 0..pp_len {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
-            precomp.push(g);
-        }
+/// Reads prover parameters from the payload of a container,
+/// or in the headerless format, and recomputes their `params_id`.
+fn deserialize_prover_params<E: Engine, R: std::io::Read>(
+    reader: &mut R,
+    compressed: Compressed,
+    expected: Option<&ParamsId>,
+    contained: bool,
+) -> std::io::Result<GenericProverParams<E>>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+{
+    // the verifier digest is unknown in the headerless format
+    let stored = read_params_id(reader, contained, expected)?;
+
+    // read the ciphersuite id
+    let mut csid = vec![0u8; 1];
+    reader.read_exact(&mut csid)?;
+
+    if !check_ciphersuite(csid[0]) {
+        return Err(PointproofsError::InvalidCiphersuite(csid[0]).into());
+    }
 
-        // format the output
-        Ok(Self {
-            ciphersuite: csid[0],
-            n,
-            generators,
-            pp_len,
-            precomp,
-        })
+    // read n
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    let n = u32::from_le_bytes(buf) as usize;
+    if n > MAX_N || n == 0 {
+        // set an upper bounded of n
+        // to prevent potential DoS kind of attacks
+        return Err(PointproofsError::InvalidN { n, max: MAX_N }.into());
     }
+
+    // read the generators
+    let orientation = group_orientation(csid[0]);
+    let generators: PointproofsG1Vec<E> = dispatch_groups!(orientation, G => {
+        G::wrap_g1_vec(deserialize_points(reader, n * 2, compressed)?)
+    });
+
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    let pp_len = u32::from_le_bytes(buf) as usize;
+    // the kind of pre-computed data is given by its length
+    let precomputation = match precomputation_from_len::<E::Fr>(pp_len, n * 2) {
+        Some(p) => p,
+        None => return Err(PointproofsError::InvalidParam.into()),
+    };
+
+    let precomp: PointproofsG1Vec<E> = dispatch_groups!(orientation, G => {
+        G::wrap_g1_vec(deserialize_points(reader, pp_len, compressed)?)
+    });
+
+    let verifier_digest = stored.map_or([0u8; 32], |(_, digest)| digest);
+    let params_id = recompute_params_id(csid[0], n, stored, || {
+        (prover_digest(n, &generators), verifier_digest)
+    })?;
+
+    // format the output
+    Ok(GenericProverParams {
+        ciphersuite: csid[0],
+        n,
+        generators,
+        precomputation,
+        precomp_range: match precomputation {
+            Precomputation::None => 0..0,
+            _ => 0..n * 2,
+        },
+        precomp,
+        precomp_threshold: DEFAULT_PRECOMP_THRESHOLD,
+        params_id,
+        verifier_digest,
+    })
 }
 
-impl SerDes for VerifierParams {
+impl<E: Engine> SerDes for GenericVerifierParams<E>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+    E::Fqk: SerDes,
+{
     /// Convert a VerifierParams into a blob:
     ///
-    /// `|ciphersuite id | n | generators | pp_len | pre_comp | gt_element` => bytes
+    /// `| header | params_id | prover digest | ciphersuite id | n | generators | pp_len |
+    ///   pre_comp | gt_element |` => bytes
     ///
+    /// where the header of the container carries a checksum of the rest,
+    /// the prover digest is the one of the prover parameters (see `params_id`),
+    /// and both are zeros if the parameters are not linked,
+    /// and `| ciphersuite id | ... |` is the headerless format.
+    /// The pre-computed tables are not serialized: `pp_len` is 0, and the header
+    /// records no precomputation.
     /// Returns an error if ciphersuite id is invalid or serialization fails.
     fn serialize<W: std::io::Write>(
         &self,
-        mut writer: &mut W,
+        writer: &mut W,
         compressed: Compressed,
     ) -> std::io::Result<()> {
         // check the cipher suite id
         if !check_ciphersuite(self.ciphersuite) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE,
-            ));
-        }
-        if !compressed {
-            // we only support compress == true mode
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
+            return Err(PointproofsError::InvalidCiphersuite(self.ciphersuite).into());
         }
         // check that #generators matches sp
-        if self.n != self.generators.len() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_INVALID_VALUE,
-            ));
-        }
-        if self.n > (u32::max_value() as usize) || self.pp_len > (u32::max_value() as usize) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_INVALID_VALUE,
-            ));
+        if self.n != self.generators.len() || self.n > (u32::max_value() as usize) {
+            return Err(PointproofsError::InvalidParam.into());
         }
 
-        writer.write_all(&[self.ciphersuite])?;
-        writer.write_all(&(self.n as u32).to_le_bytes())?;
+        let mut payload: Vec<u8> = encode_params_id(self.params_id).to_vec();
+        payload.extend_from_slice(&self.prover_digest);
+        payload.push(self.ciphersuite);
+        payload.extend_from_slice(&(self.n as u32).to_le_bytes());
 
         // write the generators
-        for e in self.generators.iter() {
-            e.serialize(&mut writer, true)?;
-        }
+        dispatch_groups!(group_orientation(self.ciphersuite), G => {
+            serialize_points(G::g2_slice(&self.generators), &mut payload, compressed)
+        })?;
 
-        writer.write_all(&(self.pp_len as u32).to_le_bytes())?;
-        if self.pp_len != 0 {
-            for e in self.precomp.iter() {
-                e.serialize(&mut writer, true)?;
-            }
-        }
+        // the pre-computed tables are not kept when reading, so they are not written
+        payload.extend_from_slice(&0u32.to_le_bytes());
 
-        self.gt_elt.serialize(&mut writer, true)?;
+        self.gt_elt.serialize(&mut payload, true)?;
 
-        Ok(())
+        ContainerHeader {
+            kind: ParamsKind::Verifier,
+            ciphersuite: self.ciphersuite,
+            n: self.n,
+            precomputation: Precomputation::None,
+            compressed,
+        }
+        .write(writer, &payload)
     }
     /// Convert a blob into a VerifierParams:
     ///
-    /// bytes => `|ciphersuite id | n | generators | pp_len | pre_comp | gt_element`
+    /// bytes => `| header | params_id | prover digest | ciphersuite id | n | generators |
+    ///   pp_len | pre_comp | gt_element |`
     ///
+    /// or, for the blobs written before the container, in the headerless format
+    /// `| ciphersuite id | n | generators | pp_len | pre_comp | gt_element |`.
+    /// In a container, the checksum and the description of the parameters are checked,
+    /// and so is the `params_id` against the generators; the container has no
+    /// pre-computed tables. Those of the headerless format are skipped.
+    /// The headerless format does not carry the prover digest: the parameters
+    /// have no `params_id` until they are linked with `link_params_id`.
     /// Returns an error if ciphersuite id is invalid or serialization fails.
     fn deserialize<R: std::io::Read>(
         reader: &mut R,
         compressed: Compressed,
     ) -> std::io::Result<Self> {
-        if !compressed {
-            // we only support compress == true mode
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_COMPRESS,
-            ));
-        }
-        // read into buf of compressed size
-        let mut csid = vec![0u8; 1];
-        reader.read_exact(&mut csid)?;
-
-        if !check_ciphersuite(csid[0]) {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_CIPHERSUITE.to_owned(),
-            ));
-        }
+        read_verifier_params(reader, compressed, None)
+    }
+}
 
-        // read n
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        let n = u32::from_le_bytes(buf) as usize;
-        if n > 65536 || n == 0 {
-            // set an upper bounded of n
-            // to prevent potential DoS kind of attacks
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                ERR_MAX_N,
-            ));
-        }
+/// Reads verifier parameters from a container or in the headerless format,
+/// and recomputes their `params_id`; fails if it is not `expected`,
+/// before reading the generators of a container.
+pub(crate) fn read_verifier_params<E: Engine, R: std::io::Read>(
+    reader: &mut R,
+    compressed: Compressed,
+    expected: Option<&ParamsId>,
+) -> std::io::Result<GenericVerifierParams<E>>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+    E::Fqk: SerDes,
+{
+    let (vp, header) = read_container(
+        reader,
+        ParamsKind::Verifier,
+        compressed,
+        |mut payload, contained| {
+            deserialize_verifier_params::<E, _>(&mut payload, compressed, expected, contained)
+        },
+    )?;
+    // the container of the verifier parameters has no pre-computed tables
+    if let Some(header) = header {
+        header.check(vp.ciphersuite, vp.n, Some(Precomputation::None))?;
+    }
+    Ok(vp)
+}
 
-        // write the generators
-        let mut generators: Vec<PointproofsG2Affine> = vec![];
-        for _i in 0..n {
-            let g = PointproofsG2Affine::deserialize(reader, true)?;
-            generators.push(g);
-        }
+/// Reads verifier parameters from the payload of a container,
+/// or in the headerless format, and recomputes their `params_id`.
+fn deserialize_verifier_params<E: Engine, R: std::io::Read>(
+    reader: &mut R,
+    compressed: Compressed,
+    expected: Option<&ParamsId>,
+    contained: bool,
+) -> std::io::Result<GenericVerifierParams<E>>
+where
+    E::G1Affine: SerDes,
+    E::G2Affine: SerDes,
+    E::Fqk: SerDes,
+{
+    // the prover digest is unknown in the headerless format
+    let stored = read_params_id(reader, contained, expected)?;
+
+    // read the ciphersuite id
+    let mut csid = vec![0u8; 1];
+    reader.read_exact(&mut csid)?;
+
+    if !check_ciphersuite(csid[0]) {
+        return Err(PointproofsError::InvalidCiphersuite(csid[0]).into());
+    }
 
-        let mut buf = [0u8; 4];
-        reader.read_exact(&mut buf)?;
-        let pp_len = u32::from_le_bytes(buf) as usize;
+    // read n
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    let n = u32::from_le_bytes(buf) as usize;
+    if n > MAX_N || n == 0 {
+        // set an upper bounded of n
+        // to prevent potential DoS kind of attacks
+        return Err(PointproofsError::InvalidN { n, max: MAX_N }.into());
+    }
 
-        let mut precomp: Vec<PointproofsG1Affine> = vec![];
-        for _i in 0..pp_len {
-            let g = PointproofsG1Affine::deserialize(reader, true)?;
-            precomp.push(g);
-        }
+    // read the generators
+    let orientation = group_orientation(csid[0]);
+    let generators: PointproofsG2Vec<E> = dispatch_groups!(orientation, G => {
+        G::wrap_g2_vec(deserialize_points(reader, n, compressed)?)
+    });
+
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    let pp_len = u32::from_le_bytes(buf) as usize;
+    if contained && pp_len != 0 {
+        return Err(PointproofsError::MalformedEncoding(
+            "the verifier parameters have pre-computed tables".to_string(),
+        )
+        .into());
+    }
 
-        let gt_elt = Fq12::deserialize(reader, true)?;
+    // the pre-computed data of the headerless format is read but not kept
+    dispatch_groups!(orientation, G => {
+        deserialize_points::<_, <G as PointproofsGroups<E>>::G2Affine>(reader, pp_len, compressed)
+            .map(|_| ())
+    })?;
+
+    let gt_elt = E::Fqk::deserialize(reader, true)?;
+
+    let prover_digest = stored.map_or([0u8; 32], |(_, digest)| digest);
+    let params_id = recompute_params_id(csid[0], n, stored, || {
+        (prover_digest, verifier_digest(&generators, &gt_elt))
+    })?;
+
+    // format the output
+    Ok(GenericVerifierParams {
+        ciphersuite: csid[0],
+        n,
+        generators,
+        precomputation: Precomputation::None,
+        precomp_range: 0..0,
+        precomp: PointproofsG2Vec::empty(orientation),
+        precomp_threshold: DEFAULT_PRECOMP_THRESHOLD,
+        gt_elt,
+        params_id,
+        prover_digest,
+    })
+}
 
-        // format the output
-        Ok(Self {
-            ciphersuite: csid[0],
-            n,
-            generators,
-            pp_len: 0,
-            precomp: vec![],
-            gt_elt,
-        })
+/// Reads the `params_id` and the digest of the other half of the parameters
+/// at the beginning of a container, if the parameters are linked;
+/// none are read in the headerless format.
+/// Fails if the `params_id` is not `expected`, if any.
+fn read_params_id<R: std::io::Read>(
+    reader: &mut R,
+    contained: bool,
+    expected: Option<&ParamsId>,
+) -> std::io::Result<Option<(ParamsId, [u8; 32])>> {
+    let mut stored = None;
+    if contained {
+        let mut params_id = [0u8; 32];
+        reader.read_exact(&mut params_id)?;
+        let mut digest = [0u8; 32];
+        reader.read_exact(&mut digest)?;
+        stored = decode_params_id(params_id).map(|id| (id, digest));
+    }
+    if expected.is_some() && stored.map(|(id, _)| id).as_ref() != expected {
+        return Err(PointproofsError::ParamsIdMismatch.into());
+    }
+    Ok(stored)
+}
+
+/// Recomputes the `params_id` of linked parameters from the digests
+/// of the two halves of the parameters, and checks it against the stored one.
+fn recompute_params_id<F: FnOnce() -> ([u8; 32], [u8; 32])>(
+    ciphersuite: Ciphersuite,
+    n: usize,
+    stored: Option<(ParamsId, [u8; 32])>,
+    digests: F,
+) -> Result<Option<ParamsId>, PointproofsError> {
+    let stored = match stored {
+        Some((id, _)) => id,
+        None => return Ok(None),
+    };
+    let (prover_digest, verifier_digest) = digests();
+    let params_id = compute_params_id(ciphersuite, n, &prover_digest, &verifier_digest);
+    if params_id != stored {
+        return Err(PointproofsError::ParamsIdMismatch);
+    }
+    Ok(Some(params_id))
+}
+
+/// Writes a PointproofsG1 element, i.e., a commitment or a proof.
+fn serialize_g1<E: Engine, G: PointproofsGroups<E>, W: std::io::Write>(
+    point: &PointproofsG1<E>,
+    writer: &mut W,
+    compressed: Compressed,
+) -> std::io::Result<()>
+where
+    G::G1Affine: SerDes,
+{
+    G::g1(point).into_affine().serialize(writer, compressed)
+}
+
+/// Reads a PointproofsG1 element, i.e., a commitment or a proof.
+fn deserialize_g1<E: Engine, G: PointproofsGroups<E>, R: std::io::Read>(
+    reader: &mut R,
+    compressed: Compressed,
+) -> std::io::Result<PointproofsG1<E>>
+where
+    G::G1Affine: SerDes,
+{
+    Ok(G::wrap_g1(
+        G::G1Affine::deserialize(reader, compressed)?.into_projective(),
+    ))
+}
+
+/// Writes a list of group elements, without a length prefix.
+fn serialize_points<W: std::io::Write, A: SerDes>(
+    points: &[A],
+    writer: &mut W,
+    compressed: Compressed,
+) -> std::io::Result<()> {
+    for e in points.iter() {
+        e.serialize(writer, compressed)?;
+    }
+    Ok(())
+}
+
+/// Reads a list of `len` group elements.
+fn deserialize_points<R: std::io::Read, A: SerDes>(
+    reader: &mut R,
+    len: usize,
+    compressed: Compressed,
+) -> std::io::Result<Vec<A>> {
+    let mut points = vec![];
+    for _i in 0..len {
+        points.push(A::deserialize(reader, compressed)?);
     }
+    Ok(points)
 }
@@ -0,0 +1,291 @@
+//! this file is part of the pointproofs.
+//! It defines a prover state that keeps a commitment and its proofs
+//! in sync with a vector of values.
+
+use ff::{Field, PrimeField};
+use pairing::serdes::SerDes;
+use pairing::Engine;
+use pairings::ciphersuite::group_orientation;
+use pairings::hash_to_field_pointproofs::*;
+use pairings::misc::*;
+use pairings::*;
+use std::collections::BTreeMap;
+
+impl<'a, E: Engine> GenericProverState<'a, E> {
+    /// Build a new state for a vector of values.
+    ///     * input: prover parameter set
+    ///     * input: a list of n values
+    ///     * output: a state holding the values and their commitment,
+    ///       with an empty cache of proofs
+    ///     * error: invalid ciphersuite/parameters
+    pub fn new<Blob: AsRef<[u8]>>(
+        prover_params: &'a GenericProverParams<E>,
+        values: &[Blob],
+    ) -> Result<Self, PointproofsError> {
+        let commit = GenericCommitment::new(prover_params, values)?;
+        Ok(Self {
+            prover_params,
+            values: values.iter().map(|v| v.as_ref().to_vec()).collect(),
+            scalars: hash_to_field_vec_pointproofs(prover_params.ciphersuite, values),
+            commit,
+            proofs: vec![None; prover_params.n],
+            changes: vec![],
+        })
+    }
+
+    /// Build a new state for a vector of field elements.
+    ///     * input: prover parameter set
+    ///     * input: a list of n field elements
+    ///     * output: a state holding the field elements and their commitment,
+    ///       with an empty cache of proofs
+    ///     * error: invalid ciphersuite/parameters
+    /// A state built from field elements should be updated with `set_scalar`
+    /// and `batch_set_scalars`.
+    pub fn new_from_scalars(
+        prover_params: &'a GenericProverParams<E>,
+        scalars: &[E::Fr],
+    ) -> Result<Self, PointproofsError> {
+        let commit = GenericCommitment::new_from_scalars(prover_params, scalars)?;
+        Ok(Self {
+            prover_params,
+            values: scalars_to_blobs(scalars),
+            scalars: scalars.to_vec(),
+            commit,
+            proofs: vec![None; prover_params.n],
+            changes: vec![],
+        })
+    }
+
+    /// Returns the current commitment.
+    pub fn commitment(&self) -> &GenericCommitment<E> {
+        &self.commit
+    }
+
+    /// Set the value at an index.
+    ///     * input: the index
+    ///     * input: the new value
+    ///     * output: update the commitment; the cached proofs are refreshed lazily
+    ///     * error: invalid index
+    pub fn set<Blob: AsRef<[u8]>>(
+        &mut self,
+        index: usize,
+        value: Blob,
+    ) -> Result<(), PointproofsError> {
+        self.batch_set(&[index], &[value])
+    }
+
+    /// Set the field element at an index.
+    ///     * input: the index
+    ///     * input: the new field element
+    ///     * output: update the commitment; the cached proofs are refreshed lazily
+    ///     * error: invalid index
+    pub fn set_scalar(&mut self, index: usize, scalar: E::Fr) -> Result<(), PointproofsError> {
+        self.batch_set_scalars(&[index], &[scalar])
+    }
+
+    /// Set the values at a list of indices.
+    ///     * input: the indices
+    ///     * input: the new values
+    ///     * output: update the commitment; the cached proofs are refreshed lazily
+    ///     * error: invalid/duplicated indices, or the lengths do not match
+    pub fn batch_set<Blob: AsRef<[u8]>>(
+        &mut self,
+        indices: &[usize],
+        values: &[Blob],
+    ) -> Result<(), PointproofsError> {
+        let scalars: Vec<E::Fr> =
+            hash_to_field_vec_pointproofs(self.prover_params.ciphersuite, values);
+        let values: Vec<Vec<u8>> = values.iter().map(|v| v.as_ref().to_vec()).collect();
+        self.apply_changes(indices, values, &scalars)
+    }
+
+    /// Set the field elements at a list of indices.
+    ///     * input: the indices
+    ///     * input: the new field elements
+    ///     * output: update the commitment; the cached proofs are refreshed lazily
+    ///     * error: invalid/duplicated indices, or the lengths do not match
+    pub fn batch_set_scalars(
+        &mut self,
+        indices: &[usize],
+        scalars: &[E::Fr],
+    ) -> Result<(), PointproofsError> {
+        self.apply_changes(indices, scalars_to_blobs(scalars), scalars)
+    }
+
+    /// Returns the proof for an index, from the cache when possible.
+    ///     * input: the index
+    ///     * output: the proof for the current value at the index
+    ///     * error: invalid index
+    pub fn proof(&mut self, index: usize) -> Result<GenericProof<E>, PointproofsError> {
+        if index >= self.prover_params.n {
+            return Err(PointproofsError::InvalidIndex {
+                index,
+                n: self.prover_params.n,
+            });
+        }
+        self.refresh_proof(index)
+    }
+
+    /// Returns the same-commit aggregated proof for a list of indices,
+    /// aggregated from the cached proofs.
+    ///     * input: the indices
+    ///     * output: the aggregated proof for the current values at the indices
+    ///     * error: invalid/duplicated indices
+    pub fn aggregated_proof(
+        &mut self,
+        indices: &[usize],
+    ) -> Result<GenericProof<E>, PointproofsError>
+    where
+        GenericCommitment<E>: SerDes,
+    {
+        if indices.is_empty() || indices.len() > self.prover_params.n {
+            return Err(PointproofsError::InvalidIndexCount {
+                count: indices.len(),
+                n: self.prover_params.n,
+            });
+        }
+        misc::check_unique_indices(indices)?;
+
+        let mut proofs: Vec<GenericProof<E>> = Vec::with_capacity(indices.len());
+        for index in indices {
+            proofs.push(self.proof(*index)?);
+        }
+        let value_sub_vector: Vec<&[u8]> =
+            indices.iter().map(|i| self.values[*i].as_ref()).collect();
+        GenericProof::same_commit_aggregate(
+            &self.commit,
+            &proofs,
+            indices,
+            &value_sub_vector,
+            self.prover_params.n,
+        )
+    }
+
+    /// Fills the cache with the proofs for all indices,
+    /// using `Proof::all_proofs_from_scalars`.
+    pub fn cache_all_proofs(&mut self) -> Result<(), PointproofsError> {
+        let proofs = GenericProof::all_proofs_from_scalars(self.prover_params, &self.scalars)?;
+        let position = self.changes.len();
+        self.proofs = proofs.into_iter().map(|p| Some((p, position))).collect();
+        Ok(())
+    }
+
+    /// Updates the values and the commitment, and logs the changes for the cached proofs.
+    fn apply_changes(
+        &mut self,
+        indices: &[usize],
+        values: Vec<Vec<u8>>,
+        scalars: &[E::Fr],
+    ) -> Result<(), PointproofsError> {
+        let n = self.prover_params.n;
+        // check the parameters are valid before mutating the state
+        for index in indices {
+            if *index >= n {
+                return Err(PointproofsError::InvalidIndex { index: *index, n });
+            }
+        }
+        if indices.len() != scalars.len() {
+            return Err(PointproofsError::IndexValueMismatch {
+                indices: indices.len(),
+                values: scalars.len(),
+            });
+        }
+        misc::check_unique_indices(indices)?;
+
+        // log the deltas = new scalar - old scalar
+        let mut deltas: Vec<(usize, E::Fr)> = Vec::with_capacity(indices.len());
+        for ((index, value), scalar) in indices.iter().zip(values).zip(scalars) {
+            let mut delta = *scalar;
+            delta.sub_assign(&self.scalars[*index]);
+            self.values[*index] = value;
+            self.scalars[*index] = *scalar;
+            if !delta.is_zero() {
+                deltas.push((*index, delta));
+            }
+        }
+
+        // new_commit = old_commit * \prod g[index]^delta
+        let (changed_index, scalars_u64): (Vec<usize>, Vec<[u64; 4]>) = deltas
+            .iter()
+            .map(|(i, d)| (*i, repr_to_u64(&d.into_repr())))
+            .unzip();
+        let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
+        let prover_params = self.prover_params;
+        self.commit.commit = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+            add_pp_subset_sum_of_prod::<E, G>(
+                &self.commit.commit,
+                prover_params,
+                &changed_index,
+                &scalars_u64_ref,
+            )
+        });
+        self.changes.extend(deltas);
+
+        // the log is merged per index when it is folded into a proof, so that a refresh
+        // takes at most min(log length, n) exponentiations; once the log is longer than n,
+        // it is folded into all the cached proofs and cleared, rather than kept growing
+        if self.changes.len() > n {
+            for index in 0..n {
+                if let Some((mut proof, from)) = self.proofs[index].take() {
+                    self.fold_changes(index, &mut proof, from);
+                    self.proofs[index] = Some((proof, 0));
+                }
+            }
+            self.changes.clear();
+        }
+        Ok(())
+    }
+
+    /// Brings the cached proof for an index up to date with the log of changes,
+    /// or generates it if it is not cached; returns the proof.
+    fn refresh_proof(&mut self, index: usize) -> Result<GenericProof<E>, PointproofsError> {
+        let position = self.changes.len();
+        let (mut proof, from) = match self.proofs[index].take() {
+            Some(p) => p,
+            None => {
+                let proof =
+                    GenericProof::new_from_scalars(self.prover_params, &self.scalars, index)?;
+                self.proofs[index] = Some((proof.clone(), position));
+                return Ok(proof);
+            }
+        };
+        self.fold_changes(index, &mut proof, from);
+        self.proofs[index] = Some((proof.clone(), position));
+        Ok(proof)
+    }
+
+    /// Updates a proof for an index with the changes of the log from position `from` on.
+    fn fold_changes(&self, index: usize, proof: &mut GenericProof<E>, from: usize) {
+        let n = self.prover_params.n;
+
+        // merge the changes per index; the value at the index itself does not affect its proof
+        let mut deltas: BTreeMap<usize, E::Fr> = BTreeMap::new();
+        for (changed_index, delta) in self.changes[from..].iter() {
+            if *changed_index != index {
+                deltas
+                    .entry(*changed_index)
+                    .or_insert_with(E::Fr::zero)
+                    .add_assign(delta);
+            }
+        }
+
+        // proof = proof * \prod g[changed_index + n - index]^delta
+        let (param_index, scalars_u64): (Vec<usize>, Vec<[u64; 4]>) = deltas
+            .iter()
+            .filter(|(_, d)| !d.is_zero())
+            .map(|(i, d)| (*i + n - index, repr_to_u64(&d.into_repr())))
+            .unzip();
+        if !param_index.is_empty() {
+            let scalars_u64_ref: Vec<&[u64; 4]> = scalars_u64.iter().collect();
+            let prover_params = self.prover_params;
+            proof.proof = dispatch_groups!(group_orientation(prover_params.ciphersuite), G => {
+                add_pp_subset_sum_of_prod::<E, G>(
+                    &proof.proof,
+                    prover_params,
+                    &param_index,
+                    &scalars_u64_ref,
+                )
+            });
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! this file is part of the pointproofs.
+//! It defines explicit checks of commitments and proofs,
+//! for verifiers that accept untrusted inputs.
+//!
+//! The policy is:
+//!     * the ciphersuite id must be supported
+//!     * the point must be in the prime order subgroup of PointproofsG1
+//!     * the identity is rejected only on request: commitments and proofs of
+//!       all-zero field elements are the identity, see `Commitment::new_from_scalars`
+//! The verification APIs themselves accept any point;
+//! inputs are checked with `validate` or `deserialize_checked` before.
+
+use ff::PrimeField;
+use pairing::serdes::SerDes;
+use pairing::{CurveAffine, CurveProjective, Engine};
+use pairings::ciphersuite::group_orientation;
+use pairings::param::check_ciphersuite;
+use pairings::*;
+
+/// Whether the identity is a valid commitment or proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdentityPolicy {
+    /// The identity is valid.
+    Accept,
+    /// The identity is rejected with `PointproofsError::IdentityPoint`.
+    Reject,
+}
+
+macro_rules! impl_validate {
+    ($name:ident, $point:ident) => {
+        impl<E: Engine> $name<E> {
+            /// Checks that the ciphersuite id is supported,
+            /// and that the point is in the prime order subgroup.
+            /// The identity is accepted.
+            pub fn validate(&self) -> Result<(), PointproofsError> {
+                self.validate_with(IdentityPolicy::Accept)
+            }
+
+            /// Checks that the ciphersuite id is supported,
+            /// and that the point is in the prime order subgroup;
+            /// the identity is accepted or rejected as per `identity`.
+            pub fn validate_with(&self, identity: IdentityPolicy) -> Result<(), PointproofsError> {
+                if !check_ciphersuite(self.ciphersuite) {
+                    return Err(PointproofsError::InvalidCiphersuite(self.ciphersuite));
+                }
+                dispatch_groups!(group_orientation(self.ciphersuite), G => {
+                    check_point::<E, G>(&self.$point, identity)
+                })
+            }
+
+            /// Deserializes and validates the input with `validate_with`.
+            /// Returns `PointproofsError::MalformedEncoding` if deserialization fails.
+            pub fn deserialize_checked<R: std::io::Read>(
+                reader: &mut R,
+                compressed: bool,
+                identity: IdentityPolicy,
+            ) -> Result<Self, PointproofsError>
+            where
+                Self: SerDes,
+            {
+                let res = <Self as SerDes>::deserialize(reader, compressed).map_err(io_error)?;
+                res.validate_with(identity)?;
+                Ok(res)
+            }
+        }
+    };
+}
+
+impl_validate!(GenericCommitment, commit);
+impl_validate!(GenericProof, proof);
+
+/// Checks a PointproofsG1 element against the policy.
+fn check_point<E: Engine, G: PointproofsGroups<E>>(
+    point: &PointproofsG1<E>,
+    identity: IdentityPolicy,
+) -> Result<(), PointproofsError> {
+    let point = G::g1(point);
+    if point.is_zero() {
+        return match identity {
+            IdentityPolicy::Accept => Ok(()),
+            IdentityPolicy::Reject => Err(PointproofsError::IdentityPoint),
+        };
+    }
+    // the order of the subgroup maps exactly its elements to the identity;
+    // points are on the curve by construction
+    if !point.into_affine().mul(E::Fr::char()).is_zero() {
+        return Err(PointproofsError::PointNotInSubgroup);
+    }
+    Ok(())
+}
+
+/// Unwraps the errors of the pointproofs APIs carried by an io error,
+/// and reports any other io error as a malformed encoding.
+pub(crate) fn io_error(e: std::io::Error) -> PointproofsError {
+    match e
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<PointproofsError>())
+    {
+        Some(inner) => inner.clone(),
+        None => PointproofsError::MalformedEncoding(e.to_string()),
+    }
+}
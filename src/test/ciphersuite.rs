@@ -0,0 +1,373 @@
+use ff::PrimeField;
+use pairing::bls12_381::*;
+use pairing::serdes::SerDes;
+use pairings::ciphersuite::*;
+use pairings::hash_to_field_pointproofs::*;
+use pairings::param::*;
+use pairings::pointproofs_groups::*;
+use pairings::*;
+use sha2::{Digest, Sha512};
+
+#[test]
+fn test_ciphersuite_registry() {
+    let p = ciphersuite_params(0).unwrap();
+    assert_eq!(p.hash, HashFunction::Sha512);
+    assert_eq!(p.dst, b"");
+    assert_eq!(p.orientation, GroupOrientation::Standard);
+
+    assert_eq!(ciphersuite_params(1).unwrap().hash, HashFunction::Sha256);
+    assert_eq!(ciphersuite_params(2).unwrap().hash, HashFunction::Blake2b);
+    assert_eq!(
+        ciphersuite_params(3).unwrap().hash,
+        HashFunction::ExpandMessageXmdSha256
+    );
+    assert_eq!(
+        ciphersuite_params(SWITCHED_GROUPS_BIT).unwrap().orientation,
+        GroupOrientation::Switched
+    );
+    assert!(ciphersuite_params(0x7f).is_none());
+    assert!(!check_ciphersuite(0x7f));
+
+    assert_eq!(
+        ciphersuite_params(4).unwrap().hash,
+        HashFunction::ExpandMessageXmdSha256
+    );
+    assert_eq!(
+        ciphersuite_params(4).unwrap().transcript,
+        TranscriptEncoding::LengthPrefixed
+    );
+    assert_ne!(
+        ciphersuite_params(4).unwrap().dst,
+        ciphersuite_params(3).unwrap().dst
+    );
+
+    assert_eq!(
+        supported_ciphersuites(),
+        vec![0, 1, 2, 3, 4, 0x80, 0x81, 0x82, 0x83, 0x84]
+    );
+    for csid in supported_ciphersuites() {
+        assert!(check_ciphersuite(csid));
+        assert_eq!(
+            ciphersuite_params(csid).unwrap().orientation,
+            group_orientation(csid)
+        );
+    }
+}
+
+#[test]
+fn test_legacy_ciphersuite_hash() {
+    // ciphersuite 0 keeps using a bare SHA512(input) mod r
+    let input = b"this is a legacy message";
+    let mut hasher = Sha512::new();
+    hasher.input(&input[..]);
+    let expected = Fr::from_repr(os2ip_mod_p::<Fr>(&hasher.result())).unwrap();
+    assert_eq!(hash_to_field_pointproofs::<Fr, _>(0, &input[..]), expected);
+
+    // other ciphersuites hash differently
+    assert_ne!(hash_to_field_pointproofs::<Fr, _>(1, &input[..]), expected);
+    assert_ne!(hash_to_field_pointproofs::<Fr, _>(2, &input[..]), expected);
+    assert_ne!(
+        hash_to_field_pointproofs::<Fr, _>(1, &input[..]),
+        hash_to_field_pointproofs::<Fr, _>(2, &input[..])
+    );
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len() / 2)
+        .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_expand_message_xmd() {
+    // test vectors of RFC 9380, appendix K.1
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    assert_eq!(
+        expand_message_xmd(b"", dst, 0x20),
+        from_hex("68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235")
+    );
+    assert_eq!(
+        expand_message_xmd(b"abc", dst, 0x20),
+        from_hex("d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615")
+    );
+    assert_eq!(
+        expand_message_xmd(b"", dst, 0x80),
+        from_hex(
+            "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbe\
+             e0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18\
+             eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dc\
+             c541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced"
+        )
+    );
+}
+
+#[test]
+fn test_hash_domains() {
+    let domains = [
+        HashDomain::Value,
+        HashDomain::SameCommit,
+        HashDomain::CrossCommit,
+        HashDomain::ParamSeed,
+    ];
+    let input = b"this is a message";
+    for csid in supported_ciphersuites() {
+        let hashes: Vec<Vec<u8>> = domains
+            .iter()
+            .map(|d| hash_to_64_bytes(csid, *d, &input[..]))
+            .collect();
+        for i in 0..hashes.len() {
+            for j in i + 1..hashes.len() {
+                // only expand_message_xmd separates the domains
+                assert_eq!(
+                    hashes[i] != hashes[j],
+                    ciphersuite_params(csid).unwrap().hash == HashFunction::ExpandMessageXmdSha256
+                );
+            }
+        }
+        // values are hashed in the value domain
+        assert_eq!(
+            hash_to_field_pointproofs::<Fr, _>(csid, &input[..]).into_repr(),
+            hash_to_field_repr_pointproofs::<Fr, _>(csid, HashDomain::Value, &input[..])
+        );
+    }
+
+    // a value that is the input of a coefficient, i.e., `index | digest`,
+    // does not hash to the coefficient
+    let (pp, _) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 3, 8).unwrap();
+    let values: Vec<String> = (0..8)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let com = Commitment::new(&pp, &values).unwrap();
+    let set = [1usize, 4];
+    let sub_values = [&values[1], &values[4]];
+    let mut transcript: Vec<u8> = vec![];
+    assert!(com.serialize(&mut transcript, true).is_ok());
+    for index in set.iter() {
+        transcript.extend_from_slice(&index.to_be_bytes());
+    }
+    for value in sub_values.iter() {
+        transcript.extend_from_slice(value.as_bytes());
+    }
+    let digest = hash_to_64_bytes(3, HashDomain::SameCommit, &transcript);
+    let ti = hash_to_ti_repr(&com, &set, &sub_values, 8).unwrap();
+    let value = [&1usize.to_be_bytes()[..], &digest].concat();
+    assert_eq!(
+        ti[0],
+        hash_to_field_repr_pointproofs::<Fr, _>(3, HashDomain::SameCommit, &value)
+    );
+    assert_ne!(
+        ti[0],
+        hash_to_field_pointproofs::<Fr, _>(3, &value).into_repr()
+    );
+}
+
+#[test]
+fn test_length_prefixed_transcript() {
+    let n = 8usize;
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    let other_values: Vec<String> = (0..n)
+        .map(|i| format!("this is another message number {}", i))
+        .collect();
+    for csid in &[4, 0x84] {
+        let (pp, vp) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let com = Commitment::new(&pp, &values).unwrap();
+        let other_com = Commitment::new(&pp, &other_values).unwrap();
+
+        // t_i = hash_to_field(i: u64 | digest),
+        // digest = hash(C | #S: u64 | S: u64 each | (len: u64 | m) for m in m[S])
+        let set = [1usize, 4];
+        let sub_values = [&values[1], &values[4]];
+        let mut transcript: Vec<u8> = vec![];
+        assert!(com.serialize(&mut transcript, true).is_ok());
+        transcript.extend_from_slice(&2u64.to_be_bytes());
+        transcript.extend_from_slice(&1u64.to_be_bytes());
+        transcript.extend_from_slice(&4u64.to_be_bytes());
+        for value in sub_values.iter() {
+            transcript.extend_from_slice(&(value.len() as u64).to_be_bytes());
+            transcript.extend_from_slice(value.as_bytes());
+        }
+        let digest = hash_to_64_bytes(*csid, HashDomain::SameCommit, &transcript);
+        let ti = hash_to_ti_repr(&com, &set, &sub_values, n).unwrap();
+        for (index, t) in set.iter().zip(ti.iter()) {
+            let input = [&(*index as u64).to_be_bytes()[..], &digest].concat();
+            assert_eq!(
+                *t,
+                hash_to_field_repr_pointproofs::<Fr, _>(*csid, HashDomain::SameCommit, &input)
+            );
+        }
+
+        // t_j, with the same encoding for each commitment
+        let commits = [com.clone(), other_com.clone()];
+        let sets = vec![vec![1usize, 4], vec![2usize]];
+        let sub_values = vec![vec![&values[1], &values[4]], vec![&other_values[2]]];
+        let mut transcript: Vec<u8> = vec![];
+        for ((com, set), sub_values) in commits.iter().zip(sets.iter()).zip(sub_values.iter()) {
+            assert!(com.serialize(&mut transcript, true).is_ok());
+            transcript.extend_from_slice(&(set.len() as u64).to_be_bytes());
+            for index in set.iter() {
+                transcript.extend_from_slice(&(*index as u64).to_be_bytes());
+            }
+            for value in sub_values.iter() {
+                transcript.extend_from_slice(&(value.len() as u64).to_be_bytes());
+                transcript.extend_from_slice(value.as_bytes());
+            }
+        }
+        let digest = hash_to_64_bytes(*csid, HashDomain::CrossCommit, &transcript);
+        let tj = hash_to_tj_repr(&commits, &sets, &sub_values, n).unwrap();
+        for (j, t) in tj.iter().enumerate() {
+            let input = [&(j as u64).to_be_bytes()[..], &digest].concat();
+            assert_eq!(
+                *t,
+                hash_to_field_repr_pointproofs::<Fr, _>(*csid, HashDomain::CrossCommit, &input)
+            );
+        }
+
+        // aggregated proofs verify
+        let proofs: Vec<Vec<Proof>> = vec![
+            vec![
+                Proof::new(&pp, &values, 1).unwrap(),
+                Proof::new(&pp, &values, 4).unwrap(),
+            ],
+            vec![Proof::new(&pp, &other_values, 2).unwrap()],
+        ];
+        let proof =
+            Proof::cross_commit_aggregate_full(&commits, &proofs, &sets, &sub_values, n).unwrap();
+        assert!(proof.cross_commit_batch_verify(&vp, &commits, &sets, &sub_values));
+    }
+
+    // moving bytes from a value to the next one changes the transcript
+    // only if the values are length-prefixed, or belong to different commitments
+    for (csid, collides) in [(3, true), (4, false)].iter() {
+        let (pp, _) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let com = Commitment::new(&pp, &values).unwrap();
+        let ti = hash_to_ti_repr(&com, &[1, 4], &["ab", "c"], n).unwrap();
+        let ti_moved = hash_to_ti_repr(&com, &[1, 4], &["a", "bc"], n).unwrap();
+        assert_eq!(ti == ti_moved, *collides);
+        let tj = hash_to_tj_repr(
+            &[com.clone(), com.clone()],
+            &[vec![1], vec![4]],
+            &[vec!["ab"], vec!["c"]],
+            n,
+        )
+        .unwrap();
+        let tj_moved = hash_to_tj_repr(
+            &[com.clone(), com.clone()],
+            &[vec![1], vec![4]],
+            &[vec!["a"], vec!["bc"]],
+            n,
+        )
+        .unwrap();
+        assert_ne!(tj, tj_moved);
+    }
+}
+
+#[test]
+fn test_ciphersuites_end_to_end() {
+    let n = 8usize;
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let mut commits: Vec<Commitment> = vec![];
+
+    for csid in supported_ciphersuites() {
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", csid, n)
+                .unwrap();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let indices = [1, 4, 6];
+        let sub_values = [&values[1], &values[4], &values[6]];
+        for i in 0..n {
+            let proof = Proof::new(&prover_params, &values, i).unwrap();
+            assert!(proof.verify(&verifier_params, &com, &values[i], i));
+        }
+        let agg_proof =
+            Proof::batch_new_aggregated(&prover_params, &com, &values, &indices).unwrap();
+        assert!(agg_proof.same_commit_batch_verify(&verifier_params, &com, &indices, &sub_values));
+
+        // the ciphersuite id survives a round trip
+        let mut buf: Vec<u8> = vec![];
+        assert!(com.serialize(&mut buf, true).is_ok());
+        assert_eq!(buf[0], csid);
+        assert_eq!(buf.len(), group_orientation(csid).commit_len());
+        assert_eq!(
+            Commitment::deserialize(&mut buf[..].as_ref(), true).unwrap(),
+            com
+        );
+        commits.push(com);
+    }
+
+    // distinct ciphersuites produce distinct commitments
+    for i in 0..commits.len() {
+        for j in i + 1..commits.len() {
+            assert_ne!(commits[i].commit, commits[j].commit);
+        }
+    }
+}
+
+#[test]
+fn test_group_orientations_side_by_side() {
+    let n = 8usize;
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+
+    let (pp, vp) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let (pp_switched, vp_switched) = paramgen_from_seed(
+        "This is Leo's Favourite very very very long Seed",
+        SWITCHED_GROUPS_BIT,
+        n,
+    )
+    .unwrap();
+
+    let com = Commitment::new(&pp, &values).unwrap();
+    let proof = Proof::new(&pp, &values, 3).unwrap();
+    let com_switched = Commitment::new(&pp_switched, &values).unwrap();
+    let proof_switched = Proof::new(&pp_switched, &values, 3).unwrap();
+
+    // both orientations are served by the same verifier
+    let mut com_buf: Vec<u8> = vec![];
+    let mut proof_buf: Vec<u8> = vec![];
+    assert!(com_switched.serialize(&mut com_buf, true).is_ok());
+    assert!(proof_switched.serialize(&mut proof_buf, true).is_ok());
+    assert_eq!(com_buf.len(), SWITCHED_COMMIT_LEN);
+    assert_eq!(proof_buf.len(), SWITCHED_PROOF_LEN);
+    let com_switched = Commitment::deserialize(&mut com_buf[..].as_ref(), true).unwrap();
+    let proof_switched = Proof::deserialize(&mut proof_buf[..].as_ref(), true).unwrap();
+    assert!(proof.verify(&vp, &com, &values[3], 3));
+    assert!(proof_switched.verify(&vp_switched, &com_switched, &values[3], 3));
+
+    // but objects of different orientations are rejected
+    assert_eq!(
+        proof_switched.try_verify(&vp, &com_switched, &values[3], 3),
+        Err(VerifyError::Malformed(
+            PointproofsError::CiphersuiteMismatch {
+                expected: 0,
+                found: SWITCHED_GROUPS_BIT,
+            }
+        ))
+    );
+    assert_eq!(
+        proof.try_verify(&vp_switched, &com_switched, &values[3], 3),
+        Err(VerifyError::Malformed(
+            PointproofsError::CiphersuiteMismatch {
+                expected: SWITCHED_GROUPS_BIT,
+                found: 0,
+            }
+        ))
+    );
+    let mut com_mixed = com.clone();
+    assert!(com_mixed
+        .update(&pp_switched, 0, &values[0], &values[1])
+        .is_err());
+    assert_eq!(com_mixed, com);
+}
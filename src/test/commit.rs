@@ -1,4 +1,7 @@
-use pairing::CurveProjective;
+use ff::{Field, PrimeField};
+use pairing::bls12_381::Fr;
+use pairing::serdes::SerDes;
+use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
 use pairings::param::paramgen_from_seed;
 use pairings::*;
 
@@ -8,7 +11,7 @@ fn negative_test_commit() {
     let (prover_params, _verifier_params) =
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
     let mut prover_params2 = prover_params.clone();
-    prover_params2.ciphersuite = 1;
+    prover_params2.ciphersuite = 0x7f;
 
     let mut init_values = Vec::with_capacity(n);
     for i in 0..n {
@@ -42,7 +45,7 @@ fn negative_test_commit() {
         .update(&prover_params2, 0, &values[0], &new_values[0][..].as_ref())
         .is_err());
 
-    com.ciphersuite = 1;
+    com.ciphersuite = 0x7f;
     assert!(com
         .update(&prover_params2, 0, &values[0], &new_values[0][..].as_ref())
         .is_err());
@@ -69,7 +72,7 @@ fn negative_test_commit_batch_update() {
 
     let mut com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
-    com2.ciphersuite = 1;
+    com2.ciphersuite = 0x7f;
 
     // update values
     let mut new_init_values = Vec::with_capacity(n);
@@ -94,7 +97,7 @@ fn negative_test_commit_batch_update() {
         .is_err());
 
     let mut pp2 = prover_params.clone();
-    pp2.ciphersuite = 1;
+    pp2.ciphersuite = 0x7f;
     assert!(com2
         .batch_update(&pp2, &indices, &value_before, &value_after)
         .is_err());
@@ -213,7 +216,7 @@ fn test_commit_batch_update() {
     com256
         .batch_update(&pp256, &indices, &value_before, &value_after)
         .unwrap();
-    assert_eq!(com.commit.into_affine(), com256.commit.into_affine());
+    assert_eq!(com.commit, com256.commit);
 
     for e in indices.iter() {
         com2.update(&prover_params, *e, value_before[*e], value_after[*e])
@@ -242,7 +245,7 @@ fn test_commit_batch_update() {
 fn test_commit() {
     let n = 8usize;
     let mut f = std::fs::File::open("crs.param").unwrap();
-    let (prover_params, verifier_params) = param::read_param(&mut f).unwrap();
+    let (prover_params, verifier_params) = param::read_param(&mut f, 0).unwrap();
 
     let mut prover_params3 = prover_params.clone();
     prover_params3.precomp_3();
@@ -470,3 +473,151 @@ fn test_commit_full() {
         }
     }
 }
+
+#[test]
+fn test_commit_error_variants() {
+    let n = 8usize;
+    let (prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut init_values = Vec::with_capacity(n);
+    for i in 0..n {
+        let s = format!("this is message number {}", i);
+        init_values.push(s.into_bytes());
+    }
+    let values: Vec<&[u8]> = init_values.iter().map(|e| e.as_ref()).collect();
+
+    assert_eq!(
+        Commitment::new(&prover_params, &values[..4]),
+        Err(PointproofsError::InvalidValueCount {
+            expected: n,
+            found: 4
+        })
+    );
+
+    let mut com = Commitment::new(&prover_params, &values).unwrap();
+    assert_eq!(
+        com.update(&prover_params, n, values[0], values[1]),
+        Err(PointproofsError::InvalidIndex { index: n, n })
+    );
+    assert_eq!(
+        com.batch_update(&prover_params, &[1, 2, 1], &values[..3], &values[3..6]),
+        Err(PointproofsError::DuplicatedIndex(1))
+    );
+    assert_eq!(
+        com.batch_update(&prover_params, &[1, 2], &values[..3], &values[3..5]),
+        Err(PointproofsError::IndexValueMismatch {
+            indices: 2,
+            values: 3
+        })
+    );
+
+    let mut prover_params2 = prover_params.clone();
+    prover_params2.ciphersuite = 0x7f;
+    assert_eq!(
+        com.update(&prover_params2, 0, values[0], values[1]),
+        Err(PointproofsError::CiphersuiteMismatch {
+            expected: 0x7f,
+            found: 0
+        })
+    );
+
+    // errors convert into io errors for the SerDes interfaces
+    let e: std::io::Error = PointproofsError::DuplicatedIndex(1).into();
+    assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_sparse_commit() {
+    let n = 16usize;
+    let (mut prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let entries: Vec<(usize, String)> = [1usize, 5, 13]
+        .iter()
+        .map(|i| (*i, format!("this is message number {}", i)))
+        .collect();
+
+    // the dense vector has 0s in the empty entries
+    let mut dense = vec![Fr::zero(); n];
+    for (i, v) in entries.iter() {
+        dense[*i] = hash_to_field_pointproofs(prover_params.ciphersuite, v);
+    }
+    let com = Commitment::new_from_scalars(&prover_params, &dense).unwrap();
+    for _ in 0..2 {
+        assert_eq!(
+            Commitment::new_sparse(&prover_params, &entries).unwrap(),
+            com
+        );
+        prover_params.precomp_256();
+    }
+
+    // an empty vector commits to the identity, and can be filled by updates
+    let mut empty = Commitment::new_sparse::<&[u8]>(&prover_params, &[]).unwrap();
+    for (i, _) in entries.iter() {
+        empty
+            .update_scalar(&prover_params, *i, Fr::zero(), dense[*i])
+            .unwrap();
+    }
+    assert_eq!(empty, com);
+
+    assert_eq!(
+        Commitment::new_sparse(&prover_params, &[(1, "a"), (n, "b")]),
+        Err(PointproofsError::InvalidIndex { index: n, n })
+    );
+    assert_eq!(
+        Commitment::new_sparse(&prover_params, &[(1, "a"), (2, "b"), (1, "c")]),
+        Err(PointproofsError::DuplicatedIndex(1))
+    );
+}
+
+#[test]
+fn test_multi_exp_matches_single_exp() {
+    // n is large enough for the multi-exponentiations to be split into chunks
+    // with the `parallel` feature; the results must be the same group elements,
+    // and serialize to the same bytes, as with single exponentiations
+    let n = 130usize;
+    let (mut prover_params, _verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let scalars: Vec<Fr> = (0..n)
+        .map(|i| Fr::from_str(&format!("{}", 1000 + i)).unwrap())
+        .collect();
+    let zeros = vec![Fr::zero(); n];
+    let proof_indices = [0usize, 65, n - 1];
+
+    for _ in 0..2 {
+        // single exponentiations, via updates from the all-zero vector
+        let mut expected_com = Commitment::new_from_scalars(&prover_params, &zeros).unwrap();
+        let mut expected_proofs =
+            Proof::batch_new_from_scalars(&prover_params, &zeros, &proof_indices).unwrap();
+        for (j, s) in scalars.iter().enumerate() {
+            expected_com
+                .update_scalar(&prover_params, j, Fr::zero(), *s)
+                .unwrap();
+            for (proof, i) in expected_proofs.iter_mut().zip(proof_indices.iter()) {
+                if *i != j {
+                    proof
+                        .update_scalar(&prover_params, *i, j, Fr::zero(), *s)
+                        .unwrap();
+                }
+            }
+        }
+
+        let com = Commitment::new_from_scalars(&prover_params, &scalars).unwrap();
+        let proofs =
+            Proof::batch_new_from_scalars(&prover_params, &scalars, &proof_indices).unwrap();
+        assert_eq!(com, expected_com);
+        assert_eq!(proofs, expected_proofs);
+
+        let mut buf: Vec<u8> = vec![];
+        let mut expected_buf: Vec<u8> = vec![];
+        com.serialize(&mut buf, true).unwrap();
+        expected_com.serialize(&mut expected_buf, true).unwrap();
+        for (proof, expected_proof) in proofs.iter().zip(expected_proofs.iter()) {
+            proof.serialize(&mut buf, true).unwrap();
+            expected_proof.serialize(&mut expected_buf, true).unwrap();
+        }
+        assert_eq!(buf, expected_buf);
+
+        prover_params.precomp_256();
+    }
+}
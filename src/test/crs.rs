@@ -0,0 +1,171 @@
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::param::{read_param, read_param_with_progress, read_verifier_param, CrsProgress};
+use pairings::{CrsGroup, PointproofsError};
+use sha2::{Digest, Sha256, Sha512};
+
+/// Swaps two elements of `len` bytes at `a` and `b` of the CRS.
+fn swap(crs: &[u8], a: usize, b: usize, len: usize) -> Vec<u8> {
+    let mut res = crs.to_vec();
+    res[a..a + len].copy_from_slice(&crs[b..b + len]);
+    res[b..b + len].copy_from_slice(&crs[a..a + len]);
+    res
+}
+
+#[test]
+fn test_read_param_with_progress() {
+    let crs = std::fs::read("crs.param").unwrap();
+    let n = 8;
+    let total = 4 * n - 1;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let mut events = vec![];
+        let (pp, vp) =
+            read_param_with_progress(&mut crs.as_slice(), *csid, |p| events.push(p)).unwrap();
+        let (pp_ref, vp_ref) = read_param(&mut crs.as_slice(), *csid).unwrap();
+        assert_eq!(pp, pp_ref);
+        assert_eq!(vp, vp_ref);
+
+        // decoding then checking, with increasing counts up to the total
+        let decoding: Vec<usize> = events
+            .iter()
+            .filter_map(|e| match e {
+                CrsProgress::Decoding { done, total: t } if *t == total => Some(*done),
+                _ => None,
+            })
+            .collect();
+        let checking: Vec<usize> = events
+            .iter()
+            .filter_map(|e| match e {
+                CrsProgress::Checking { done, total: t } if *t == total => Some(*done),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(decoding.len() + checking.len(), events.len());
+        assert_eq!(decoding.last(), Some(&total));
+        assert_eq!(checking.last(), Some(&total));
+        assert!(decoding.windows(2).all(|w| w[0] < w[1]));
+        assert!(checking.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(
+            events
+                .iter()
+                .position(|e| matches!(e, CrsProgress::Checking { .. })),
+            Some(decoding.len())
+        );
+    }
+
+    // the elements are at
+    // 4 + 48 * i for g1^{alpha^{i+1}}, i in [0, 8) and g1^{alpha^{i+2}}, i in [8, 15),
+    // 724 + 96 * i for the same powers of g2, and 2164 for the target group element
+    let g1 = |i: usize| 4 + 48 * i;
+    let g2 = |i: usize| 724 + 96 * i;
+    let cases = [
+        // the relation of g1^alpha and g2^alpha does not tell which one is inconsistent
+        (swap(&crs, g1(0), g1(1), 48), CrsGroup::G1OrG2, 1),
+        (swap(&crs, g2(0), g2(1), 96), CrsGroup::G1OrG2, 1),
+        // the relations of step 2 rely on g2^{alpha^2}, which is checked first
+        (swap(&crs, g2(1), g2(2), 96), CrsGroup::G2, 2),
+        (swap(&crs, g1(2), g1(3), 48), CrsGroup::G1, 3),
+        (swap(&crs, g1(8), g1(9), 48), CrsGroup::G1, 10),
+        (swap(&crs, g1(13), g1(14), 48), CrsGroup::G1, 15),
+        (swap(&crs, g2(5), g2(6), 96), CrsGroup::G2, 6),
+        (swap(&crs, g2(7), g2(8), 96), CrsGroup::G2, 8),
+    ];
+    for (invalid_crs, group, power) in cases.iter() {
+        assert_eq!(
+            read_param_with_progress(&mut invalid_crs.as_slice(), 0, |_| ()).unwrap_err(),
+            PointproofsError::InconsistentCrsElement {
+                group: *group,
+                power: *power
+            }
+        );
+    }
+
+    let mut invalid_gt = crs.clone();
+    let last = invalid_gt.len() - 1;
+    invalid_gt[last] ^= 1;
+    assert_eq!(
+        read_param_with_progress(&mut invalid_gt.as_slice(), 0, |_| ()).unwrap_err(),
+        PointproofsError::InconsistentCrsElement {
+            group: CrsGroup::Gt,
+            power: 9
+        }
+    );
+
+    assert!(matches!(
+        read_param_with_progress(&mut &crs[..1000], 0, |_| ()),
+        Err(PointproofsError::MalformedEncoding(_))
+    ));
+    assert_eq!(
+        read_param_with_progress(&mut crs.as_slice(), 0x7f, |_| ()).unwrap_err(),
+        PointproofsError::InvalidCiphersuite(0x7f)
+    );
+}
+
+#[test]
+fn test_read_verifier_param() {
+    let crs = std::fs::read("crs.param").unwrap();
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (_, vp_ref) = read_param(&mut crs.as_slice(), *csid).unwrap();
+        let (vp, digest) = read_verifier_param::<Sha512, _>(&mut crs.as_slice(), *csid).unwrap();
+        assert_eq!(vp, vp_ref);
+        assert_eq!(digest, Sha512::digest(&crs).to_vec());
+        let (vp, digest) = read_verifier_param::<Sha256, _>(&mut crs.as_slice(), *csid).unwrap();
+        assert_eq!(vp, vp_ref);
+        assert_eq!(digest, Sha256::digest(&crs).to_vec());
+    }
+
+    let g1 = |i: usize| 4 + 48 * i;
+    let g2 = |i: usize| 724 + 96 * i;
+
+    // the powers of PointproofsG2 are checked
+    let invalid_crs = swap(&crs, g2(2), g2(3), 96);
+    assert_eq!(
+        read_verifier_param::<Sha256, _>(&mut invalid_crs.as_slice(), 0).unwrap_err(),
+        PointproofsError::InconsistentCrsElement {
+            group: CrsGroup::G2,
+            power: 3
+        }
+    );
+    let invalid_crs = swap(&crs, g2(0), g2(1), 96);
+    assert_eq!(
+        read_verifier_param::<Sha256, _>(&mut invalid_crs.as_slice(), 0).unwrap_err(),
+        PointproofsError::InconsistentCrsElement {
+            group: CrsGroup::G1OrG2,
+            power: 1
+        }
+    );
+    let invalid_crs = swap(&crs, g1(2), g1(3), 48);
+    assert_eq!(
+        read_verifier_param::<Sha256, _>(&mut invalid_crs.as_slice(), SWITCHED_GROUPS_BIT)
+            .unwrap_err(),
+        PointproofsError::InconsistentCrsElement {
+            group: CrsGroup::G1,
+            power: 3
+        }
+    );
+
+    // the prover section is only covered by the digest
+    let (vp, digest) = read_verifier_param::<Sha256, _>(&mut invalid_crs.as_slice(), 0).unwrap();
+    assert_eq!(vp, read_param(&mut crs.as_slice(), 0).unwrap().1);
+    assert_ne!(digest, Sha256::digest(&crs).to_vec());
+
+    let mut invalid_gt = crs.clone();
+    let last = invalid_gt.len() - 1;
+    invalid_gt[last] ^= 1;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        assert_eq!(
+            read_verifier_param::<Sha256, _>(&mut invalid_gt.as_slice(), *csid).unwrap_err(),
+            PointproofsError::InconsistentCrsElement {
+                group: CrsGroup::Gt,
+                power: 9
+            }
+        );
+    }
+
+    // truncated in the skipped prover section, and in the verifier section
+    for len in &[400, 1000] {
+        assert!(matches!(
+            read_verifier_param::<Sha256, _>(&mut &crs[..*len], 0),
+            Err(PointproofsError::MalformedEncoding(_))
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use ff::{Field, PrimeField};
+use ff::{Field, PrimeField, PrimeFieldRepr};
 use pairing::bls12_381::*;
 use pairings::hash_to_field_pointproofs::os2ip_mod_p;
 
@@ -13,24 +13,57 @@ use pairings::hash_to_field_pointproofs::os2ip_mod_p;
 fn test_os2ip() {
     assert_eq!(
         Fr::from_str("0").unwrap(),
-        Fr::from_repr(os2ip_mod_p(&[0u8, 0u8])).unwrap()
+        Fr::from_repr(os2ip_mod_p::<Fr>(&[0u8, 0u8])).unwrap()
     );
     assert_eq!(
         Fr::from_str("1").unwrap(),
-        Fr::from_repr(os2ip_mod_p(&[0u8, 1u8])).unwrap()
+        Fr::from_repr(os2ip_mod_p::<Fr>(&[0u8, 1u8])).unwrap()
     );
     assert_eq!(
         Fr::from_str("255").unwrap(),
-        Fr::from_repr(os2ip_mod_p(&[0u8, 0xffu8])).unwrap()
+        Fr::from_repr(os2ip_mod_p::<Fr>(&[0u8, 0xffu8])).unwrap()
     );
     assert_eq!(
         Fr::from_str("256").unwrap(),
-        Fr::from_repr(os2ip_mod_p(&[1u8, 0u8])).unwrap()
+        Fr::from_repr(os2ip_mod_p::<Fr>(&[1u8, 0u8])).unwrap()
     );
     assert_eq!(
         Fr::from_str("65535").unwrap(),
-        Fr::from_repr(os2ip_mod_p(&[0xffu8, 0xffu8])).unwrap()
+        Fr::from_repr(os2ip_mod_p::<Fr>(&[0xffu8, 0xffu8])).unwrap()
     );
 
     assert_eq!(Fr::from_repr(FrRepr([1, 0, 0, 0])).unwrap(), Fr::one());
 }
+
+#[test]
+fn test_os2ip_reduces_mod_r() {
+    // r = 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+    let mut r_bytes = vec![0u8; 32];
+    Fr::char().write_be(&mut r_bytes[..]).unwrap();
+    assert_eq!(
+        Fr::from_repr(os2ip_mod_p::<Fr>(&r_bytes)).unwrap(),
+        Fr::zero()
+    );
+
+    // r + 1, as a 64 bytes input, reduces to 1
+    let mut r_plus_one = vec![0u8; 64];
+    r_plus_one[32..].copy_from_slice(&r_bytes);
+    r_plus_one[63] += 1;
+    assert_eq!(
+        Fr::from_repr(os2ip_mod_p::<Fr>(&r_plus_one)).unwrap(),
+        Fr::one()
+    );
+
+    // 2^512 - 1 matches the reduction computed in Fr
+    let mut expected = Fr::from_str("18446744073709551615").unwrap(); // 2^64 - 1
+    let mut two_to_64 = expected;
+    two_to_64.add_assign(&Fr::one());
+    for _ in 0..7 {
+        expected.mul_assign(&two_to_64);
+        expected.add_assign(&Fr::from_str("18446744073709551615").unwrap());
+    }
+    assert_eq!(
+        Fr::from_repr(os2ip_mod_p::<Fr>(&[0xffu8; 64])).unwrap(),
+        expected
+    );
+}
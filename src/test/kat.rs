@@ -0,0 +1,190 @@
+//! Known-answer test vectors for the whole scheme, in both group orientations.
+//! The vectors are generated with `generate_kat`, i.e.,
+//!     `cargo test generate_kat -- --ignored`
+//! and checked against the committed file by `test_kat`.
+//! Regenerate them only for an intended change of the wire-level math.
+//!
+//! Every byte string is lower case hex; field elements are 32 bytes, big endian;
+//! commitments and proofs are in the compressed encoding of `serialize`,
+//! i.e., prefixed by the ciphersuite id.
+
+use ff::{PrimeField, PrimeFieldRepr};
+use pairing::bls12_381::*;
+use pairing::serdes::SerDes;
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::hash_to_field_pointproofs::*;
+use pairings::param::{paramgen_from_seed, Ciphersuite};
+use pairings::*;
+use serde_json::Value;
+
+const KAT_FILE: &str = "test_vectors/pointproofs_kat.json";
+const SEED: &str = "This is Leo's Favourite very very very long Seed";
+const N: usize = 8;
+// the indices of the same commit aggregation
+const SAME_COMMIT_SET: [usize; 4] = [1, 3, 4, 6];
+// the indices of the cross commit aggregation, for each commitment
+const CROSS_COMMIT_SETS: [[usize; 3]; 2] = [[0, 2, 5], [1, 2, 7]];
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn repr_to_hex(repr: &FrRepr) -> String {
+    let mut bytes: Vec<u8> = vec![];
+    repr.write_be(&mut bytes).unwrap();
+    to_hex(&bytes)
+}
+
+fn serialize_to_hex<T: SerDes>(t: &T) -> String {
+    let mut bytes: Vec<u8> = vec![];
+    t.serialize(&mut bytes, true).unwrap();
+    to_hex(&bytes)
+}
+
+/// The two committed vectors.
+fn kat_values() -> Vec<Vec<String>> {
+    vec![
+        (0..N)
+            .map(|i| format!("this is message number {}", i))
+            .collect(),
+        (0..N)
+            .map(|i| format!("this is another message number {}", i))
+            .collect(),
+    ]
+}
+
+/// The vectors of a ciphersuite.
+fn kat_vectors(csid: Ciphersuite) -> Value {
+    let values = kat_values();
+    let (pp, vp) = paramgen_from_seed(SEED, csid, N).unwrap();
+
+    let hash_to_field: Vec<String> = values[0]
+        .iter()
+        .map(|v| repr_to_hex(&hash_to_field_pointproofs::<Fr, _>(csid, v).into_repr()))
+        .collect();
+    let commits: Vec<Commitment> = values
+        .iter()
+        .map(|v| Commitment::new(&pp, v).unwrap())
+        .collect();
+    let proofs: Vec<Proof> = (0..N)
+        .map(|i| Proof::new(&pp, &values[0], i).unwrap())
+        .collect();
+    for (i, proof) in proofs.iter().enumerate() {
+        assert!(proof.verify(&vp, &commits[0], &values[0][i], i));
+    }
+
+    // same commit aggregation
+    let sub_values: Vec<&String> = SAME_COMMIT_SET.iter().map(|i| &values[0][*i]).collect();
+    let ti = hash_to_ti_repr(&commits[0], &SAME_COMMIT_SET, &sub_values, N).unwrap();
+    let sub_proofs: Vec<Proof> = SAME_COMMIT_SET.iter().map(|i| proofs[*i].clone()).collect();
+    let same_commit_proof =
+        Proof::same_commit_aggregate(&commits[0], &sub_proofs, &SAME_COMMIT_SET, &sub_values, N)
+            .unwrap();
+    assert_eq!(
+        same_commit_proof,
+        Proof::batch_new_aggregated(&pp, &commits[0], &values[0], &SAME_COMMIT_SET).unwrap()
+    );
+    assert!(same_commit_proof.same_commit_batch_verify(
+        &vp,
+        &commits[0],
+        &SAME_COMMIT_SET,
+        &sub_values
+    ));
+
+    // cross commit aggregation
+    let sets: Vec<Vec<usize>> = CROSS_COMMIT_SETS.iter().map(|s| s.to_vec()).collect();
+    let sub_values: Vec<Vec<&String>> = sets
+        .iter()
+        .zip(values.iter())
+        .map(|(set, v)| set.iter().map(|i| &v[*i]).collect())
+        .collect();
+    let tj = hash_to_tj_repr(&commits, &sets, &sub_values, N).unwrap();
+    let sub_proofs: Vec<Vec<Proof>> = sets
+        .iter()
+        .zip(values.iter())
+        .map(|(set, v)| {
+            set.iter()
+                .map(|i| Proof::new(&pp, v, *i).unwrap())
+                .collect()
+        })
+        .collect();
+    let cross_commit_proof =
+        Proof::cross_commit_aggregate_full(&commits, &sub_proofs, &sets, &sub_values, N).unwrap();
+    assert!(cross_commit_proof.cross_commit_batch_verify(&vp, &commits, &sets, &sub_values));
+
+    serde_json::json!({
+        "ciphersuite": csid,
+        "params_id": to_hex(&pp.params_id().unwrap()),
+        "hash_to_field": hash_to_field,
+        "commitments": commits.iter().map(serialize_to_hex).collect::<Vec<String>>(),
+        "proofs": proofs.iter().map(serialize_to_hex).collect::<Vec<String>>(),
+        "same_commit": {
+            "indices": SAME_COMMIT_SET.to_vec(),
+            "hash_to_ti": ti.iter().map(repr_to_hex).collect::<Vec<String>>(),
+            "aggregated_proof": serialize_to_hex(&same_commit_proof),
+        },
+        "cross_commit": {
+            "indices": sets,
+            "hash_to_tj": tj.iter().map(repr_to_hex).collect::<Vec<String>>(),
+            "aggregated_proof": serialize_to_hex(&cross_commit_proof),
+        },
+    })
+}
+
+/// The whole file: the inputs, then the vectors of each ciphersuite, in both orientations.
+fn kat() -> Value {
+    serde_json::json!({
+        "seed": SEED,
+        "n": N,
+        "values": kat_values(),
+        "vectors": [
+            kat_vectors(0),
+            kat_vectors(SWITCHED_GROUPS_BIT),
+            kat_vectors(3),
+            kat_vectors(3 | SWITCHED_GROUPS_BIT),
+            kat_vectors(4),
+            kat_vectors(4 | SWITCHED_GROUPS_BIT),
+        ],
+    })
+}
+
+#[test]
+#[ignore]
+fn generate_kat() {
+    let mut json = serde_json::to_string_pretty(&kat()).unwrap();
+    json.push('\n');
+    std::fs::create_dir_all("test_vectors").unwrap();
+    std::fs::write(KAT_FILE, json).unwrap();
+}
+
+#[test]
+fn test_kat() {
+    let json = match std::fs::read_to_string(KAT_FILE) {
+        Ok(json) => json,
+        Err(e) => panic!(
+            "{}: {}; generate it with `cargo test generate_kat -- --ignored`",
+            KAT_FILE, e
+        ),
+    };
+    let expected: Value = serde_json::from_str(&json).unwrap();
+    let computed = kat();
+    for key in ["seed", "n", "values"].iter() {
+        assert_eq!(computed[key], expected[key], "input {}", key);
+    }
+    for (computed, expected) in computed["vectors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .zip(expected["vectors"].as_array().unwrap().iter())
+    {
+        // compare field by field, to point at the part of the math that changed
+        for (key, value) in computed.as_object().unwrap().iter() {
+            assert_eq!(
+                *value, expected[key],
+                "ciphersuite {}: {}",
+                computed["ciphersuite"], key
+            );
+        }
+    }
+    assert_eq!(computed, expected);
+}
@@ -0,0 +1,119 @@
+use pairing::serdes::SerDes;
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::param::{paramgen_from_seed, Precomputation};
+use pairings::{Commitment, Proof, ProverParams};
+
+#[test]
+fn test_mapped_prover_param() {
+    let n = 16usize;
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (prover_params, _verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let proofs = Proof::batch_new(&prover_params, &values, &[0, 5, 15]).unwrap();
+
+        let mut window3 = prover_params.clone();
+        window3.precomp_3();
+        let mut custom = prover_params.clone();
+        custom.precompute(3).unwrap();
+        let mut partial = prover_params.clone();
+        partial
+            .precompute_with_budget(Precomputation::Window3, 1 << 10)
+            .unwrap();
+
+        for pp in &[prover_params.clone(), window3, custom, partial] {
+            let mut buf: Vec<u8> = vec![];
+            assert!(pp.write_mapped(&mut buf).is_ok());
+            let pp_recover = unsafe { ProverParams::read_mapped(&mut buf[..].as_ref()) }.unwrap();
+            assert_eq!(*pp, pp_recover);
+            assert_eq!(Commitment::new(&pp_recover, &values).unwrap(), com);
+            assert_eq!(
+                Proof::batch_new(&pp_recover, &values, &[0, 5, 15]).unwrap(),
+                proofs
+            );
+
+            // truncated or extended files
+            assert!(
+                unsafe { ProverParams::read_mapped(&mut buf[..buf.len() - 1].as_ref()) }.is_err()
+            );
+            let mut longer = buf.clone();
+            longer.push(0);
+            assert!(unsafe { ProverParams::read_mapped(&mut longer[..].as_ref()) }.is_err());
+            // magic, version, precomputation and n
+            for i in &[0usize, 8, 10, 24] {
+                let mut invalid_buf = buf.clone();
+                invalid_buf[*i] ^= 0xff;
+                assert!(
+                    unsafe { ProverParams::read_mapped(&mut invalid_buf[..].as_ref()) }.is_err()
+                );
+            }
+            // point size, and the canary
+            for i in &[12usize, 128] {
+                let mut invalid_buf = buf.clone();
+                invalid_buf[*i] ^= 1;
+                assert!(
+                    unsafe { ProverParams::read_mapped(&mut invalid_buf[..].as_ref()) }.is_err()
+                );
+            }
+        }
+
+        // conversion from the compressed format
+        let mut compressed: Vec<u8> = vec![];
+        assert!(prover_params.serialize(&mut compressed, true).is_ok());
+        let mut converted: Vec<u8> = vec![];
+        assert!(
+            ProverParams::convert_to_mapped(&mut compressed[..].as_ref(), &mut converted).is_ok()
+        );
+        let mut buf: Vec<u8> = vec![];
+        assert!(prover_params.write_mapped(&mut buf).is_ok());
+        assert_eq!(converted, buf);
+        assert!(ProverParams::convert_to_mapped(&mut converted[..].as_ref(), &mut buf).is_err());
+    }
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn test_map_file() {
+    use std::io::Write;
+
+    let n = 16usize;
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (mut prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        prover_params.precomp_256();
+
+        let path = std::env::temp_dir().join(format!(
+            "pointproofs_test_map_file_{}_{}.param",
+            std::process::id(),
+            csid
+        ));
+        let mut file = std::fs::File::create(&path).unwrap();
+        let mut buf: Vec<u8> = vec![];
+        assert!(prover_params.write_mapped(&mut buf).is_ok());
+        file.write_all(&buf).unwrap();
+        drop(file);
+
+        let file = std::fs::File::open(&path).unwrap();
+        let mapped = unsafe { ProverParams::map_file(&file) }.unwrap();
+        drop(file);
+        std::fs::remove_file(&path).unwrap();
+
+        // the mapping outlives the file handle, and is shared by clones
+        let mapped_clone = mapped.clone();
+        drop(mapped);
+        assert_eq!(prover_params, mapped_clone);
+
+        let com = Commitment::new(&mapped_clone, &values).unwrap();
+        assert_eq!(com, Commitment::new(&prover_params, &values).unwrap());
+        let proof = Proof::new(&mapped_clone, &values, 3).unwrap();
+        assert!(proof.verify(&verifier_params, &com, &values[3], 3));
+    }
+}
@@ -1,7 +1,17 @@
 mod aggregate;
 mod c_api;
+mod ciphersuite;
 mod commit;
+mod crs;
 mod hashes;
+mod kat;
+mod mapped;
 mod param;
+mod params_id;
 mod prove;
+mod scalars;
+#[cfg(feature = "serde")]
+mod serde_support;
 mod serdes;
+mod state;
+mod validate;
@@ -1,4 +1,15 @@
-use pairings::param::paramgen_from_seed;
+use ff::{Field, PrimeField};
+use pairing::bls12_381::{Bls12, Fr, G1Affine, G2Affine};
+use pairing::serdes::SerDes;
+use pairing::{CurveAffine, CurveProjective};
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::hash_to_field_pointproofs::hash_seed_to_field_pointproofs;
+use pairings::param::{
+    check_consistency, paramgen_from_seed, paramgen_from_seed_generic, read_param, Precomputation,
+};
+use pairings::{Commitment, PointproofsError, Proof, ProverParams, VerifierParams};
+use rand_core::SeedableRng;
+use rand_xorshift::XorShiftRng;
 
 #[test]
 fn test_param() {
@@ -30,7 +41,7 @@ fn negative_test_param() {
 
     assert!(paramgen_from_seed(
         "This is Leo's Second Favourite very very very long Seed",
-        1,
+        0x7f,
         n
     )
     .is_err());
@@ -76,3 +87,335 @@ fn test_param_slow() {
         assert!(!pp2.check_parameters(&vp1));
     }
 }
+
+#[test]
+fn test_param_switched() {
+    let n = 16;
+    let (pp1, vp1) = paramgen_from_seed(
+        "This is Leo's Favourite very very very long Seed",
+        SWITCHED_GROUPS_BIT,
+        n,
+    )
+    .unwrap();
+    let (pp2, vp2) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    assert!(pp1.check_parameters(&vp1));
+    assert!(pp2.check_parameters(&vp2));
+
+    // parameters of different orientations do not mix
+    assert!(!pp1.check_parameters(&vp2));
+    assert!(!pp2.check_parameters(&vp1));
+}
+
+#[test]
+fn test_param_generic_engine() {
+    let n = 16;
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    for csid in [0, SWITCHED_GROUPS_BIT].iter() {
+        // BLS12-381 is the default instantiation
+        let (pp, vp): (GenericProverParams<Bls12>, GenericVerifierParams<Bls12>) =
+            paramgen_from_seed_generic(
+                "This is Leo's Favourite very very very long Seed",
+                *csid,
+                n,
+            )
+            .unwrap();
+        let (pp_default, vp_default): (ProverParams, VerifierParams) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        assert_eq!(pp, pp_default);
+        assert_eq!(vp, vp_default);
+        assert!(pp.check_parameters(&vp));
+
+        let com: GenericCommitment<Bls12> = GenericCommitment::new(&pp, &values).unwrap();
+        let proof: GenericProof<Bls12> = GenericProof::new(&pp, &values, 5).unwrap();
+        assert!(proof.verify(&vp, &com, &values[5], 5));
+    }
+}
+
+#[test]
+fn test_precomputation() {
+    let n = 8usize;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let values: Vec<String> = (0..n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let proofs = Proof::batch_new(&prover_params, &values, &[1, 3, 6]).unwrap();
+        let agg_proof =
+            Proof::batch_new_aggregated(&prover_params, &com, &values, &[1, 3, 6]).unwrap();
+        let mut new_values = values.clone();
+        new_values[7] = "b".to_string();
+        let updated_proof = Proof::new(&prover_params, &new_values, 1).unwrap();
+        let mut updated_com = com.clone();
+        updated_com
+            .batch_update(
+                &prover_params,
+                &[5, 7],
+                &[values[5].as_str(), values[7].as_str()],
+                &["a", "b"],
+            )
+            .unwrap();
+
+        for precomputation in &[
+            Precomputation::None,
+            Precomputation::Window3,
+            Precomputation::Window256,
+            Precomputation::Custom(1),
+            Precomputation::Custom(4),
+        ] {
+            // the threshold only decides when the tables are used
+            for threshold in &[0, n, 1024] {
+                let mut pp = prover_params.clone();
+                let mut vp = verifier_params.clone();
+                pp.set_precomputation(*precomputation).unwrap();
+                vp.set_precomputation(*precomputation).unwrap();
+                pp.set_precomp_threshold(*threshold);
+                vp.set_precomp_threshold(*threshold);
+                assert_eq!(pp.precomputation(), *precomputation);
+                assert_eq!(vp.precomputation(), *precomputation);
+
+                assert_eq!(Commitment::new(&pp, &values).unwrap(), com);
+                assert_eq!(Proof::batch_new(&pp, &values, &[1, 3, 6]).unwrap(), proofs);
+                assert_eq!(
+                    Proof::batch_new_aggregated(&pp, &com, &values, &[1, 3, 6]).unwrap(),
+                    agg_proof
+                );
+                let mut com_mut = com.clone();
+                com_mut
+                    .batch_update(
+                        &pp,
+                        &[5, 7],
+                        &[values[5].as_str(), values[7].as_str()],
+                        &["a", "b"],
+                    )
+                    .unwrap();
+                assert_eq!(com_mut, updated_com);
+                let mut proof = proofs[0].clone();
+                proof.update(&pp, 1, 7, &values[7], &new_values[7]).unwrap();
+                assert_eq!(proof, updated_proof);
+
+                assert!(proofs[1].verify(&vp, &com, &values[3], 3));
+                assert!(agg_proof.same_commit_batch_verify(
+                    &vp,
+                    &com,
+                    &[1, 3, 6],
+                    &[&values[1], &values[3], &values[6]]
+                ));
+            }
+
+            // the kind of pre-computed data is recovered from its length
+            let mut pp = prover_params.clone();
+            pp.set_precomputation(*precomputation).unwrap();
+            let mut buf: Vec<u8> = vec![];
+            pp.serialize(&mut buf, true).unwrap();
+            let pp_recover = ProverParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
+            assert_eq!(pp_recover.precomputation(), *precomputation);
+            assert_eq!(pp_recover, pp);
+        }
+
+        let mut pp = prover_params.clone();
+        for bits in &[0, 17] {
+            assert_eq!(
+                pp.set_precomputation(Precomputation::Custom(*bits)),
+                Err(PointproofsError::InvalidWindow {
+                    bits: *bits,
+                    max: 16
+                })
+            );
+        }
+        assert_eq!(pp, prover_params);
+    }
+}
+
+#[test]
+fn test_precomputation_budget() {
+    let n = 8usize;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let (pp_point_size, vp_point_size) = if *csid == 0 {
+            (
+                std::mem::size_of::<G1Affine>(),
+                std::mem::size_of::<G2Affine>(),
+            )
+        } else {
+            (
+                std::mem::size_of::<G2Affine>(),
+                std::mem::size_of::<G1Affine>(),
+            )
+        };
+        let values: Vec<String> = (0..n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let proofs = Proof::batch_new(&prover_params, &values, &[0, 4, 7]).unwrap();
+        let agg_proof =
+            Proof::batch_new_aggregated(&prover_params, &com, &values, &[0, 4, 7]).unwrap();
+        let mut updated_proofs = proofs.clone();
+        for (proof, i) in updated_proofs.iter_mut().zip([0usize, 4, 7].iter()) {
+            proof
+                .batch_update(&prover_params, *i, &[1, 2], &["a", "b"], &["c", "d"])
+                .unwrap();
+        }
+
+        let mut pp = prover_params.clone();
+        pp.precompute(4).unwrap();
+        assert_eq!(pp.precomputation(), Precomputation::Custom(4));
+        assert_eq!(pp.precomputed_generators(), 2 * n);
+        assert_eq!(Commitment::new(&pp, &values).unwrap(), com);
+
+        for (precomputation, points) in &[
+            (Precomputation::Window3, 3),
+            (Precomputation::Window256, 256),
+            (Precomputation::Custom(2), 128 * 3),
+        ] {
+            // no budget, a budget for some generators, and a budget for all of them
+            for count in &[0, 1, 5, 2 * n] {
+                let mut pp = prover_params.clone();
+                let mut vp = verifier_params.clone();
+                assert_eq!(
+                    pp.precompute_with_budget(*precomputation, count * points * pp_point_size)
+                        .unwrap(),
+                    *count
+                );
+                assert_eq!(
+                    vp.precompute_with_budget(*precomputation, count * points * vp_point_size)
+                        .unwrap(),
+                    std::cmp::min(*count, n)
+                );
+                assert_eq!(pp.precomputed_generators(), *count);
+
+                assert_eq!(Commitment::new(&pp, &values).unwrap(), com);
+                assert_eq!(Proof::batch_new(&pp, &values, &[0, 4, 7]).unwrap(), proofs);
+                assert_eq!(
+                    Proof::batch_new_aggregated(&pp, &com, &values, &[0, 4, 7]).unwrap(),
+                    agg_proof
+                );
+                let mut proofs_mut = proofs.clone();
+                for (proof, i) in proofs_mut.iter_mut().zip([0usize, 4, 7].iter()) {
+                    proof
+                        .batch_update(&pp, *i, &[1, 2], &["a", "b"], &["c", "d"])
+                        .unwrap();
+                }
+                assert_eq!(proofs_mut, updated_proofs);
+                assert!(agg_proof.same_commit_batch_verify(
+                    &vp,
+                    &com,
+                    &[0, 4, 7],
+                    &[&values[0], &values[4], &values[7]]
+                ));
+
+                // tables for part of the generators are not serialized
+                let mut buf: Vec<u8> = vec![];
+                if *count == 2 * n {
+                    assert!(pp.serialize(&mut buf, true).is_ok());
+                } else if *count > 0 {
+                    assert!(pp.serialize(&mut buf, true).is_err());
+                    assert!(vp.serialize(&mut buf, true).is_err());
+                }
+            }
+        }
+    }
+}
+
+/// Swaps the two encodings `a` and `b` within `bytes`.
+fn swap_encodings(bytes: &[u8], a: &[u8], b: &[u8]) -> Vec<u8> {
+    let find = |x: &[u8]| bytes.windows(x.len()).position(|w| w == x).unwrap();
+    let (pos_a, pos_b) = (find(a), find(b));
+    let mut res = bytes.to_vec();
+    res[pos_a..pos_a + a.len()].copy_from_slice(b);
+    res[pos_b..pos_b + b.len()].copy_from_slice(a);
+    res
+}
+
+#[test]
+fn test_check_consistency() {
+    let mut rng = XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+    for n in [1usize, 2, 16].iter() {
+        for csid in [0, SWITCHED_GROUPS_BIT].iter() {
+            let (pp1, vp1) = paramgen_from_seed(
+                "This is Leo's Favourite very very very long Seed",
+                *csid,
+                *n,
+            )
+            .unwrap();
+            let (pp2, vp2) = paramgen_from_seed(
+                "This is Leo's Second Favourite very very very long Seed",
+                *csid,
+                *n,
+            )
+            .unwrap();
+            assert!(check_consistency(&pp1, &vp1, &mut rng));
+            assert!(check_consistency(&pp2, &vp2, &mut rng));
+            assert!(!check_consistency(&pp1, &vp2, &mut rng));
+            assert!(!check_consistency(&pp2, &vp1, &mut rng));
+        }
+    }
+
+    // the orientations and dimensions must match
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let (pp, _) = paramgen_from_seed(seed, 0, 16).unwrap();
+    let (_, vp) = paramgen_from_seed(seed, SWITCHED_GROUPS_BIT, 16).unwrap();
+    assert!(!check_consistency(&pp, &vp, &mut rng));
+    let (_, vp) = paramgen_from_seed(seed, 0, 15).unwrap();
+    assert!(!check_consistency(&pp, &vp, &mut rng));
+
+    // swapped powers of alpha, on either side of the missing alpha^{n+1}; n = 8 in crs.param
+    let crs = std::fs::read("crs.param").unwrap();
+    let (pp, vp) = read_param(&mut crs.as_slice(), 0).unwrap();
+    assert!(check_consistency(&pp, &vp, &mut rng));
+    let mut pp_bytes: Vec<u8> = vec![];
+    assert!(pp.serialize(&mut pp_bytes, true).is_ok());
+    let mut vp_bytes: Vec<u8> = vec![];
+    assert!(vp.serialize(&mut vp_bytes, true).is_ok());
+    // the encodings are swapped in the headerless payloads, which have no checksum
+    let (pp_bytes, vp_bytes) = (&pp_bytes[128..], &vp_bytes[128..]);
+    let g1 = |i: usize| &crs[4 + 48 * i..4 + 48 * (i + 1)];
+    let g2 = |i: usize| &crs[724 + 96 * i..724 + 96 * (i + 1)];
+    for (a, b) in [(0, 1), (2, 3), (7, 8), (13, 14)].iter() {
+        let invalid = swap_encodings(pp_bytes, g1(*a), g1(*b));
+        let invalid_pp = ProverParams::deserialize(&mut invalid.as_slice(), true).unwrap();
+        assert!(!check_consistency(&invalid_pp, &vp, &mut rng));
+    }
+    for (a, b) in [(0, 1), (3, 4), (6, 7)].iter() {
+        let invalid = swap_encodings(vp_bytes, g2(*a), g2(*b));
+        let invalid_vp = VerifierParams::deserialize(&mut invalid.as_slice(), true).unwrap();
+        assert!(!check_consistency(&pp, &invalid_vp, &mut rng));
+    }
+
+    // g1^{alpha^{n+1}} in place of the identity at pp_generators[n]
+    let n = 8;
+    let (pp, vp) = paramgen_from_seed(seed, 0, n).unwrap();
+    let alpha = hash_seed_to_field_pointproofs::<Fr, _>(0, seed);
+    let hole = G1Affine::one()
+        .mul(alpha.pow([n as u64 + 1]).into_repr())
+        .into_affine();
+    let mut hole_bytes: Vec<u8> = vec![];
+    assert!(hole.serialize(&mut hole_bytes, true).is_ok());
+    let mut identity_bytes: Vec<u8> = vec![];
+    assert!(G1Affine::zero()
+        .serialize(&mut identity_bytes, true)
+        .is_ok());
+    let mut pp_bytes: Vec<u8> = vec![];
+    assert!(pp.serialize(&mut pp_bytes, true).is_ok());
+    let mut invalid = pp_bytes[128..].to_vec();
+    let pos = invalid
+        .windows(identity_bytes.len())
+        .position(|w| w == &identity_bytes[..])
+        .unwrap();
+    invalid[pos..pos + hole_bytes.len()].copy_from_slice(&hole_bytes);
+    let invalid_pp = ProverParams::deserialize(&mut invalid.as_slice(), true).unwrap();
+    assert!(!check_consistency(&invalid_pp, &vp, &mut rng));
+}
@@ -0,0 +1,236 @@
+use pairing::serdes::SerDes;
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::param::{paramgen_from_seed, read_param, read_verifier_param};
+use pairings::*;
+use sha2::{Digest, Sha256};
+
+#[test]
+fn test_params_id() {
+    let seed = "This is Leo's Favourite very very very long Seed";
+    let other_seed = "This is Leo's Second Favourite very very very long Seed";
+    let n = 8;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (pp, vp) = paramgen_from_seed(seed, *csid, n).unwrap();
+        let (other_pp, other_vp) = paramgen_from_seed(other_seed, *csid, n).unwrap();
+        assert!(pp.params_id().is_some());
+        assert_eq!(pp.params_id(), vp.params_id());
+        assert_eq!(other_pp.params_id(), other_vp.params_id());
+        assert_ne!(pp.params_id(), other_pp.params_id());
+        // the id is stable
+        assert_eq!(
+            pp.params_id(),
+            paramgen_from_seed(seed, *csid, n).unwrap().0.params_id()
+        );
+
+        // the id is part of the serialized parameters
+        for compressed in &[true, false] {
+            let mut pp_buf: Vec<u8> = vec![];
+            assert!(pp.serialize(&mut pp_buf, *compressed).is_ok());
+            let mut vp_buf: Vec<u8> = vec![];
+            assert!(vp.serialize(&mut vp_buf, *compressed).is_ok());
+            assert_eq!(pp_buf[64..96], pp.params_id().unwrap());
+            assert_eq!(vp_buf[64..96], vp.params_id().unwrap());
+
+            let pp_recover =
+                ProverParams::deserialize(&mut pp_buf[..].as_ref(), *compressed).unwrap();
+            assert_eq!(pp_recover.params_id(), pp.params_id());
+            let pp_recover = ProverParams::deserialize_matching(
+                &mut pp_buf[..].as_ref(),
+                *compressed,
+                &vp.params_id().unwrap(),
+            )
+            .unwrap();
+            assert_eq!(pp_recover, pp);
+            let vp_recover = VerifierParams::deserialize_matching(
+                &mut vp_buf[..].as_ref(),
+                *compressed,
+                &pp.params_id().unwrap(),
+            )
+            .unwrap();
+            assert_eq!(vp_recover, vp);
+
+            // parameters of another setup are rejected
+            assert_eq!(
+                ProverParams::deserialize_matching(
+                    &mut pp_buf[..].as_ref(),
+                    *compressed,
+                    &other_vp.params_id().unwrap(),
+                )
+                .unwrap_err(),
+                PointproofsError::ParamsIdMismatch
+            );
+            assert_eq!(
+                VerifierParams::deserialize_matching(
+                    &mut vp_buf[..].as_ref(),
+                    *compressed,
+                    &other_pp.params_id().unwrap(),
+                )
+                .unwrap_err(),
+                PointproofsError::ParamsIdMismatch
+            );
+
+            // a params_id, or a digest of the other half, that does not match the generators,
+            // with a valid checksum
+            for i in &[64usize, 96] {
+                let mut invalid_pp = pp_buf.clone();
+                invalid_pp[*i] ^= 1;
+                let checksum = Sha256::digest(&invalid_pp[64..]);
+                invalid_pp[32..64].copy_from_slice(&checksum);
+                let mut stored = [0u8; 32];
+                stored.copy_from_slice(&invalid_pp[64..96]);
+                assert_eq!(
+                    ProverParams::deserialize_matching(
+                        &mut invalid_pp[..].as_ref(),
+                        *compressed,
+                        &stored,
+                    )
+                    .unwrap_err(),
+                    PointproofsError::ParamsIdMismatch
+                );
+                let mut invalid_vp = vp_buf.clone();
+                invalid_vp[*i] ^= 1;
+                let checksum = Sha256::digest(&invalid_vp[64..]);
+                invalid_vp[32..64].copy_from_slice(&checksum);
+                stored.copy_from_slice(&invalid_vp[64..96]);
+                assert_eq!(
+                    VerifierParams::deserialize_matching(
+                        &mut invalid_vp[..].as_ref(),
+                        *compressed,
+                        &stored,
+                    )
+                    .unwrap_err(),
+                    PointproofsError::ParamsIdMismatch
+                );
+            }
+
+            // the headerless format does not carry the digest of the other half:
+            // the parameters are the same, without params_id until linked
+            let mut pp_legacy =
+                ProverParams::deserialize(&mut pp_buf[128..].as_ref(), *compressed).unwrap();
+            let mut vp_legacy =
+                VerifierParams::deserialize(&mut vp_buf[128..].as_ref(), *compressed).unwrap();
+            assert_eq!(pp_legacy, pp);
+            assert_eq!(vp_legacy, vp);
+            assert_eq!(pp_legacy.params_id(), None);
+            assert_eq!(vp_legacy.params_id(), None);
+            assert!(ProverParams::deserialize_matching(
+                &mut pp_buf[128..].as_ref(),
+                *compressed,
+                &pp.params_id().unwrap(),
+            )
+            .is_err());
+
+            // parameters that are not linked are written and read as such
+            let mut unlinked_buf: Vec<u8> = vec![];
+            assert!(pp_legacy.serialize(&mut unlinked_buf, *compressed).is_ok());
+            assert_eq!(unlinked_buf[64..128], [0u8; 64][..]);
+            let pp_unlinked =
+                ProverParams::deserialize(&mut unlinked_buf[..].as_ref(), *compressed).unwrap();
+            assert_eq!(pp_unlinked, pp);
+            assert_eq!(pp_unlinked.params_id(), None);
+            let mut unlinked_buf: Vec<u8> = vec![];
+            assert!(vp_legacy.serialize(&mut unlinked_buf, *compressed).is_ok());
+            assert_eq!(unlinked_buf[64..128], [0u8; 64][..]);
+            let vp_unlinked =
+                VerifierParams::deserialize(&mut unlinked_buf[..].as_ref(), *compressed).unwrap();
+            assert_eq!(vp_unlinked, vp);
+            assert_eq!(vp_unlinked.params_id(), None);
+
+            assert!(link_params_id(&mut pp_legacy, &mut vp_legacy).is_ok());
+            assert_eq!(pp_legacy.params_id(), pp.params_id());
+            assert_eq!(vp_legacy.params_id(), vp.params_id());
+        }
+    }
+
+    // the orientations have different ids
+    assert_ne!(
+        paramgen_from_seed(seed, 0, n).unwrap().0.params_id(),
+        paramgen_from_seed(seed, SWITCHED_GROUPS_BIT, n)
+            .unwrap()
+            .0
+            .params_id()
+    );
+
+    // the parameters read from a CRS, in full or verifier only, have the same id
+    let crs = std::fs::read("crs.param").unwrap();
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (pp, vp) = read_param(&mut crs.as_slice(), *csid).unwrap();
+        assert_eq!(pp.params_id(), vp.params_id());
+        let (vp_only, _) = read_verifier_param::<Sha256, _>(&mut crs.as_slice(), *csid).unwrap();
+        assert_eq!(vp_only.params_id(), vp.params_id());
+    }
+}
+
+#[test]
+fn test_commitment_params_id() {
+    let n = 8;
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (pp, vp) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let (other_pp, other_vp) = paramgen_from_seed(
+            "This is Leo's Second Favourite very very very long Seed",
+            *csid,
+            n,
+        )
+        .unwrap();
+
+        let mut com = Commitment::new(&pp, &values).unwrap();
+        assert_eq!(com.params_id(), None);
+        let proof = Proof::new(&pp, &values, 1).unwrap();
+
+        // unbound commitments are only rejected by the pairing check
+        assert_eq!(
+            proof.try_verify(&other_vp, &com, &values[1], 1),
+            Err(VerifyError::PairingCheckFailed)
+        );
+
+        com.bind_params_id(pp.params_id().unwrap());
+        assert_eq!(com.params_id(), vp.params_id());
+        assert!(proof.try_verify(&vp, &com, &values[1], 1).is_ok());
+        assert_eq!(
+            proof.try_verify(&other_vp, &com, &values[1], 1),
+            Err(VerifyError::Malformed(PointproofsError::ParamsIdMismatch))
+        );
+        assert!(!proof.verify(&other_vp, &com, &values[1], 1));
+        assert_eq!(
+            com.clone()
+                .update(&other_pp, 1, &values[1], &values[2])
+                .unwrap_err(),
+            PointproofsError::ParamsIdMismatch
+        );
+        assert!(Proof::batch_new_aggregated(&other_pp, &com, &values[0..2], &[0, 1]).is_err());
+
+        // parameters without params_id do not match a bound commitment
+        let mut vp_buf: Vec<u8> = vec![];
+        assert!(vp.serialize(&mut vp_buf, true).is_ok());
+        let vp_legacy = VerifierParams::deserialize(&mut vp_buf[128..].as_ref(), true).unwrap();
+        assert_eq!(
+            proof.try_verify(&vp_legacy, &com, &values[1], 1),
+            Err(VerifyError::Malformed(PointproofsError::ParamsIdMismatch))
+        );
+
+        // the binding is carried by serialize_with_params_id only
+        let mut buf: Vec<u8> = vec![];
+        assert!(com.serialize_with_params_id(&mut buf, true).is_ok());
+        let com_recover =
+            Commitment::deserialize_with_params_id(&mut buf[..].as_ref(), true).unwrap();
+        assert_eq!(com_recover.params_id(), com.params_id());
+        let com_recover = Commitment::deserialize(&mut buf[..].as_ref(), true).unwrap();
+        assert_eq!(com_recover, com);
+        assert_eq!(com_recover.params_id(), None);
+
+        let unbound = Commitment::new(&pp, &values).unwrap();
+        let mut buf: Vec<u8> = vec![];
+        assert!(unbound.serialize_with_params_id(&mut buf, true).is_ok());
+        let com_recover =
+            Commitment::deserialize_with_params_id(&mut buf[..].as_ref(), true).unwrap();
+        assert_eq!(com_recover.params_id(), None);
+        let last = buf.len() - 1;
+        buf[last] = 2;
+        assert!(Commitment::deserialize_with_params_id(&mut buf[..].as_ref(), true).is_err());
+    }
+}
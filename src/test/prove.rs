@@ -1,4 +1,7 @@
-use pairing::CurveProjective;
+use ff::Field;
+use pairing::bls12_381::Fr;
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
 use pairings::param::paramgen_from_seed;
 use pairings::*;
 
@@ -9,7 +12,7 @@ fn negative_test_batch_new_proof() {
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
 
     let mut pp2 = prover_params.clone();
-    pp2.ciphersuite = 1;
+    pp2.ciphersuite = 0x7f;
     let mut pp3 = prover_params.clone();
     pp3.n = 2;
 
@@ -45,9 +48,9 @@ fn negative_test_proof() {
     let (prover_params, verifier_params) =
         paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
     let mut prover_params2 = prover_params.clone();
-    prover_params2.ciphersuite = 1;
+    prover_params2.ciphersuite = 0x7f;
     let mut verifier_params2 = verifier_params.clone();
-    verifier_params2.ciphersuite = 1;
+    verifier_params2.ciphersuite = 0x7f;
 
     let mut prover_params256 = prover_params.clone();
     prover_params256.precomp_256();
@@ -81,14 +84,14 @@ fn negative_test_proof() {
         .is_err());
 
     let mut proof2 = proof.clone();
-    proof2.ciphersuite = 1;
+    proof2.ciphersuite = 0x7f;
     assert!(proof2
         .update(&prover_params, 0, 1, &values2[1], &values2[2])
         .is_err());
 
     let com = Commitment::new(&prover_params, &values).unwrap();
     let mut com2 = com.clone();
-    com2.ciphersuite = 1;
+    com2.ciphersuite = 0x7f;
     assert!(!proof.verify(&verifier_params2, &com, &values[0], 0));
     assert!(!proof.verify(&verifier_params, &com, &values[0], 9));
     assert!(!proof.verify(&verifier_params, &com2, &values[0], 0));
@@ -141,7 +144,7 @@ fn test_proof_edge_case() {
 fn test_proof_edge_case2() {
     let n = 8usize;
     let mut f = std::fs::File::open("crs.param").unwrap();
-    let (prover_params, verifier_params) = param::read_param(&mut f).unwrap();
+    let (prover_params, verifier_params) = param::read_param(&mut f, 0).unwrap();
 
     let mut prover_params3 = prover_params.clone();
     prover_params3.precomp_3();
@@ -250,7 +253,7 @@ fn test_proof_edge_case2() {
 fn test_proof_edge_case3() {
     let n = 8usize;
     let mut f = std::fs::File::open("crs.param").unwrap();
-    let (prover_params, verifier_params) = param::read_param(&mut f).unwrap();
+    let (prover_params, verifier_params) = param::read_param(&mut f, 0).unwrap();
 
     let mut prover_params3 = prover_params.clone();
     prover_params3.precomp_3();
@@ -425,10 +428,7 @@ fn test_batch_new_proof() {
         "pre_compute256 failed"
     );
 
-    assert_eq!(
-        agg_proof.proof.into_affine(),
-        agg_proof2.proof.into_affine()
-    );
+    assert_eq!(agg_proof.proof, agg_proof2.proof);
     assert!(agg_proof.same_commit_batch_verify(
         &verifier_params,
         &com,
@@ -444,3 +444,304 @@ fn test_batch_new_proof() {
         "pre_compute256 failed"
     );
 }
+
+#[test]
+fn test_try_verify_errors() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+
+    let mut values: Vec<String> = vec![];
+    for i in 0..n {
+        values.push(format!("this is message number {}", i));
+    }
+    let com = Commitment::new(&prover_params, &values).unwrap();
+    let proof = Proof::new(&prover_params, &values, 0).unwrap();
+
+    assert_eq!(
+        proof.try_verify(&verifier_params, &com, &values[0], 0),
+        Ok(())
+    );
+    assert_eq!(
+        proof.try_verify(&verifier_params, &com, &values[1], 0),
+        Err(VerifyError::PairingCheckFailed)
+    );
+    assert_eq!(
+        proof.try_verify(&verifier_params, &com, &values[0], n),
+        Err(VerifyError::Malformed(PointproofsError::InvalidIndex {
+            index: n,
+            n
+        }))
+    );
+    let mut com2 = com.clone();
+    com2.ciphersuite = 0x7f;
+    assert_eq!(
+        proof.try_verify(&verifier_params, &com2, &values[0], 0),
+        Err(VerifyError::Malformed(
+            PointproofsError::CiphersuiteMismatch {
+                expected: 0,
+                found: 0x7f
+            }
+        ))
+    );
+
+    // batch verification
+    let indices = [1, 3, 5];
+    let sub_values = [&values[1], &values[3], &values[5]];
+    let agg_proof = Proof::batch_new_aggregated(&prover_params, &com, &values, &indices).unwrap();
+    assert_eq!(
+        agg_proof.try_same_commit_batch_verify(&verifier_params, &com, &indices, &sub_values),
+        Ok(())
+    );
+    assert_eq!(
+        agg_proof.try_same_commit_batch_verify(&verifier_params, &com, &[1, 3, 3], &sub_values),
+        Err(VerifyError::Malformed(PointproofsError::DuplicatedIndex(3)))
+    );
+    assert_eq!(
+        agg_proof.try_same_commit_batch_verify(&verifier_params, &com, &indices[..2], &sub_values),
+        Err(VerifyError::Malformed(
+            PointproofsError::IndexValueMismatch {
+                indices: 2,
+                values: 3
+            }
+        ))
+    );
+    assert_eq!(
+        proof.try_same_commit_batch_verify(&verifier_params, &com, &indices, &sub_values),
+        Err(VerifyError::PairingCheckFailed)
+    );
+
+    // cross commit verification
+    let com_list = [com.clone(), com.clone()];
+    let set_list = [indices.to_vec(), indices.to_vec()];
+    let value_list = [sub_values.to_vec(), sub_values.to_vec()];
+    assert_eq!(
+        agg_proof.try_cross_commit_batch_verify(
+            &verifier_params,
+            &com_list,
+            &set_list,
+            &value_list[..1]
+        ),
+        Err(VerifyError::Malformed(
+            PointproofsError::CrossCommitSizeMismatch {
+                commits: 2,
+                found: 1
+            }
+        ))
+    );
+    assert_eq!(
+        agg_proof.try_cross_commit_batch_verify(
+            &verifier_params,
+            &com_list,
+            &set_list,
+            &value_list
+        ),
+        Err(VerifyError::PairingCheckFailed)
+    );
+}
+
+#[test]
+fn test_sparse_proof() {
+    let n = 16usize;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (mut prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let entries: Vec<(usize, String)> = [0usize, 6, 15]
+            .iter()
+            .map(|i| (*i, format!("this is message number {}", i)))
+            .collect();
+        let mut dense = vec![Fr::zero(); n];
+        for (i, v) in entries.iter() {
+            dense[*i] = hash_to_field_pointproofs(*csid, v);
+        }
+
+        let com = Commitment::new_sparse(&prover_params, &entries).unwrap();
+        for _ in 0..2 {
+            for index in 0..n {
+                let proof = Proof::new_sparse(&prover_params, &entries, index).unwrap();
+                assert_eq!(
+                    proof,
+                    Proof::new_from_scalars(&prover_params, &dense, index).unwrap()
+                );
+                match entries.iter().find(|e| e.0 == index) {
+                    // populated entries open to their values
+                    Some((_, v)) => assert!(proof.verify(&verifier_params, &com, v, index)),
+                    // empty entries open to 0
+                    None => {
+                        assert!(proof.verify_scalar(&verifier_params, &com, Fr::zero(), index))
+                    }
+                }
+            }
+            prover_params.precomp_256();
+        }
+
+        assert_eq!(
+            Proof::new_sparse(&prover_params, &entries, n),
+            Err(PointproofsError::InvalidIndex { index: n, n })
+        );
+        assert_eq!(
+            Proof::new_sparse(&prover_params, &[(3, "a"), (3, "b")], 0),
+            Err(PointproofsError::DuplicatedIndex(3))
+        );
+    }
+}
+
+#[test]
+fn test_all_proofs() {
+    for n in &[1usize, 5, 8] {
+        for csid in &[0, SWITCHED_GROUPS_BIT] {
+            let (mut prover_params, verifier_params) = paramgen_from_seed(
+                "This is Leo's Favourite very very very long Seed",
+                *csid,
+                *n,
+            )
+            .unwrap();
+            let values: Vec<String> = (0..*n)
+                .map(|i| format!("this is message number {}", i))
+                .collect();
+            let indices: Vec<usize> = (0..*n).collect();
+            let com = Commitment::new(&prover_params, &values).unwrap();
+
+            for _ in 0..2 {
+                let proofs = Proof::all_proofs(&prover_params, &values).unwrap();
+                assert_eq!(
+                    proofs,
+                    Proof::batch_new(&prover_params, &values, &indices).unwrap()
+                );
+                for (i, proof) in proofs.iter().enumerate() {
+                    assert!(proof.verify(&verifier_params, &com, &values[i], i));
+                }
+                prover_params.precomp_3();
+            }
+
+            assert_eq!(
+                Proof::all_proofs(&prover_params, &values[1..]),
+                Err(PointproofsError::InvalidValueCount {
+                    expected: *n,
+                    found: *n - 1
+                })
+            );
+        }
+    }
+}
+
+#[test]
+fn test_proof_batch_update() {
+    let n = 16usize;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (mut prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let init_values: Vec<String> = (0..n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let changed_index = vec![2usize, 5, 6, 11];
+        let value_before: Vec<&String> = changed_index.iter().map(|i| &init_values[*i]).collect();
+        let value_after: Vec<String> = changed_index
+            .iter()
+            .map(|i| format!("this is new message number {}", i))
+            .collect();
+        let mut new_values = init_values.clone();
+        for (i, v) in changed_index.iter().zip(value_after.iter()) {
+            new_values[*i] = v.clone();
+        }
+        let value_after: Vec<&String> = value_after.iter().collect();
+        let proof_indices: Vec<usize> = (0..n).collect();
+
+        for precomp in 0..3 {
+            // no precomputation, then precomp_3, then precomp_256
+            if precomp == 1 {
+                prover_params.precomp_3();
+            } else if precomp == 2 {
+                prover_params.precomp_256();
+            }
+            let com = Commitment::new(&prover_params, &new_values).unwrap();
+            let new_proofs = Proof::batch_new(&prover_params, &new_values, &proof_indices).unwrap();
+
+            // one proof at a time
+            let mut proofs =
+                Proof::batch_new(&prover_params, &init_values, &proof_indices).unwrap();
+            for (i, proof) in proofs.iter_mut().enumerate() {
+                proof
+                    .batch_update(
+                        &prover_params,
+                        i,
+                        &changed_index,
+                        &value_before,
+                        &value_after,
+                    )
+                    .unwrap();
+                assert!(proof.verify(&verifier_params, &com, &new_values[i], i));
+            }
+            assert_eq!(proofs, new_proofs);
+
+            // all proofs at once
+            let mut proofs =
+                Proof::batch_new(&prover_params, &init_values, &proof_indices).unwrap();
+            Proof::batch_update_proofs(
+                &mut proofs,
+                &prover_params,
+                &proof_indices,
+                &changed_index,
+                &value_before,
+                &value_after,
+            )
+            .unwrap();
+            assert_eq!(proofs, new_proofs);
+        }
+
+        // errors leave the proofs unchanged
+        let mut proofs = Proof::batch_new(&prover_params, &init_values, &[0, 1]).unwrap();
+        let old_proofs = proofs.clone();
+        assert_eq!(
+            Proof::batch_update_proofs(
+                &mut proofs,
+                &prover_params,
+                &[0],
+                &changed_index,
+                &value_before,
+                &value_after,
+            ),
+            Err(PointproofsError::IndexProofMismatch {
+                indices: 1,
+                proofs: 2
+            })
+        );
+        assert_eq!(
+            Proof::batch_update_proofs(
+                &mut proofs,
+                &prover_params,
+                &[0, 1],
+                &[2, 2],
+                &value_before[..2],
+                &value_after[..2],
+            ),
+            Err(PointproofsError::DuplicatedIndex(2))
+        );
+        assert_eq!(
+            proofs[0].batch_update(
+                &prover_params,
+                0,
+                &[2, n],
+                &value_before[..2],
+                &value_after[..2]
+            ),
+            Err(PointproofsError::InvalidIndex { index: n, n })
+        );
+        assert_eq!(
+            proofs[0].batch_update(
+                &prover_params,
+                0,
+                &changed_index,
+                &value_before,
+                &value_after[..2]
+            ),
+            Err(PointproofsError::IndexValueMismatch {
+                indices: 4,
+                values: 2
+            })
+        );
+        assert_eq!(proofs, old_proofs);
+    }
+}
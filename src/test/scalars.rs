@@ -0,0 +1,261 @@
+use ff::{Field, PrimeField};
+use pairing::bls12_381::*;
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::hash_to_field_pointproofs::hash_to_field_pointproofs;
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+fn scalars(n: usize) -> Vec<Fr> {
+    (0..n)
+        .map(|i| Fr::from_str(&format!("{}", 1000 + i)).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_scalars_match_hashed_values() {
+    let n = 8usize;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+
+        let values: Vec<Vec<u8>> = (0..n)
+            .map(|i| format!("this is message number {}", i).into_bytes())
+            .collect();
+        let hashes: Vec<Fr> = values
+            .iter()
+            .map(|v| hash_to_field_pointproofs(*csid, v))
+            .collect();
+
+        // committing to values is committing to their hashes
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        assert_eq!(
+            com,
+            Commitment::new_from_scalars(&prover_params, &hashes).unwrap()
+        );
+        for i in 0..n {
+            let proof = Proof::new(&prover_params, &values, i).unwrap();
+            assert_eq!(
+                proof,
+                Proof::new_from_scalars(&prover_params, &hashes, i).unwrap()
+            );
+            assert!(proof.verify_scalar(&verifier_params, &com, hashes[i], i));
+        }
+        assert_eq!(
+            Proof::batch_new(&prover_params, &values, &[1, 4]).unwrap(),
+            Proof::batch_new_from_scalars(&prover_params, &hashes, &[1, 4]).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_scalar_commit_prove_update() {
+    let n = 8usize;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let mut values = scalars(n);
+        // zero is a valid field element to commit to
+        values[2] = Fr::zero();
+
+        let mut com = Commitment::new_from_scalars(&prover_params, &values).unwrap();
+        let mut proofs: Vec<Proof> = (0..n)
+            .map(|i| Proof::new_from_scalars(&prover_params, &values, i).unwrap())
+            .collect();
+        for i in 0..n {
+            assert!(proofs[i].verify_scalar(&verifier_params, &com, values[i], i));
+            let mut wrong = values[i];
+            wrong.add_assign(&Fr::one());
+            assert_eq!(
+                proofs[i].try_verify_scalar(&verifier_params, &com, wrong, i),
+                Err(VerifyError::PairingCheckFailed)
+            );
+        }
+
+        // update the 3rd value, and all proofs
+        let new_value = Fr::from_str("42").unwrap();
+        com.update_scalar(&prover_params, 3, values[3], new_value)
+            .unwrap();
+        for (i, proof) in proofs.iter_mut().enumerate() {
+            proof
+                .update_scalar(&prover_params, i, 3, values[3], new_value)
+                .unwrap();
+        }
+        values[3] = new_value;
+        assert_eq!(
+            com,
+            Commitment::new_from_scalars(&prover_params, &values).unwrap()
+        );
+        for i in 0..n {
+            assert!(proofs[i].verify_scalar(&verifier_params, &com, values[i], i));
+        }
+
+        // batch update a value to zero
+        let mut new_values = values.clone();
+        new_values[0] = Fr::zero();
+        new_values[5] = Fr::from_str("7").unwrap();
+        com.batch_update_scalars(
+            &prover_params,
+            &[0, 5],
+            &[values[0], values[5]],
+            &[new_values[0], new_values[5]],
+        )
+        .unwrap();
+        assert_eq!(
+            com,
+            Commitment::new_from_scalars(&prover_params, &new_values).unwrap()
+        );
+        let proof = Proof::new_from_scalars(&prover_params, &new_values, 0).unwrap();
+        assert!(proof.verify_scalar(&verifier_params, &com, Fr::zero(), 0));
+        assert!(!proof.verify_scalar(&verifier_params, &com, Fr::one(), 0));
+
+        assert_eq!(
+            Commitment::new_from_scalars(&prover_params, &values[1..]),
+            Err(PointproofsError::InvalidValueCount {
+                expected: n,
+                found: n - 1
+            })
+        );
+    }
+}
+
+#[test]
+fn test_scalar_aggregation() {
+    let n = 8usize;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+
+        let mut values: Vec<Vec<Fr>> = vec![scalars(n), scalars(n)];
+        values[1][1] = Fr::zero();
+        values[1][4] = Fr::from_str("5").unwrap();
+        let commits: Vec<Commitment> = values
+            .iter()
+            .map(|v| Commitment::new_from_scalars(&prover_params, v).unwrap())
+            .collect();
+
+        let set = vec![vec![1usize, 4, 7], vec![1usize, 4]];
+        let mut proofs: Vec<Vec<Proof>> = vec![];
+        let mut sub_values: Vec<Vec<Fr>> = vec![];
+        let mut agg_proofs: Vec<Proof> = vec![];
+        for j in 0..commits.len() {
+            proofs
+                .push(Proof::batch_new_from_scalars(&prover_params, &values[j], &set[j]).unwrap());
+            sub_values.push(set[j].iter().map(|i| values[j][*i]).collect());
+
+            let agg_proof = Proof::same_commit_aggregate_scalars(
+                &commits[j],
+                &proofs[j],
+                &set[j],
+                &sub_values[j],
+                n,
+            )
+            .unwrap();
+            assert_eq!(
+                agg_proof,
+                Proof::batch_new_aggregated_from_scalars(
+                    &prover_params,
+                    &commits[j],
+                    &values[j],
+                    &set[j]
+                )
+                .unwrap()
+            );
+            assert!(agg_proof.same_commit_batch_verify_scalars(
+                &verifier_params,
+                &commits[j],
+                &set[j],
+                &sub_values[j]
+            ));
+            agg_proofs.push(agg_proof);
+        }
+
+        // a wrong field element is rejected
+        let mut wrong_values = sub_values[0].clone();
+        wrong_values[1].add_assign(&Fr::one());
+        assert_eq!(
+            agg_proofs[0].try_same_commit_batch_verify_scalars(
+                &verifier_params,
+                &commits[0],
+                &set[0],
+                &wrong_values
+            ),
+            Err(VerifyError::PairingCheckFailed)
+        );
+
+        let cross_proof = Proof::cross_commit_aggregate_partial_scalars(
+            &commits,
+            &agg_proofs,
+            &set,
+            &sub_values,
+            n,
+        )
+        .unwrap();
+        assert_eq!(
+            cross_proof,
+            Proof::cross_commit_aggregate_full_scalars(&commits, &proofs, &set, &sub_values, n)
+                .unwrap()
+        );
+        assert!(cross_proof.cross_commit_batch_verify_scalars(
+            &verifier_params,
+            &commits,
+            &set,
+            &sub_values
+        ));
+        let wrong_sub_values = vec![wrong_values, sub_values[1].clone()];
+        assert_eq!(
+            cross_proof.try_cross_commit_batch_verify_scalars(
+                &verifier_params,
+                &commits,
+                &set,
+                &wrong_sub_values
+            ),
+            Err(VerifyError::PairingCheckFailed)
+        );
+    }
+}
+
+#[test]
+fn test_scalar_batch_verify_zero_sum() {
+    // opening only zeros makes \sum m_i * t_i == 0
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut values = scalars(n);
+    values[2] = Fr::zero();
+    values[6] = Fr::zero();
+    let com = Commitment::new_from_scalars(&prover_params, &values).unwrap();
+
+    let set = vec![2usize, 6];
+    let sub_values = vec![Fr::zero(), Fr::zero()];
+    let proof =
+        Proof::batch_new_aggregated_from_scalars(&prover_params, &com, &values, &set).unwrap();
+    assert!(proof.same_commit_batch_verify_scalars(&verifier_params, &com, &set, &sub_values));
+    assert!(!proof.same_commit_batch_verify_scalars(
+        &verifier_params,
+        &com,
+        &set,
+        &[Fr::zero(), Fr::one()]
+    ));
+
+    let proofs = vec![proof.clone(), proof];
+    let commits = vec![com.clone(), com];
+    let sets = vec![set.clone(), set];
+    let cross_sub_values = vec![sub_values.clone(), sub_values];
+    let cross_proof = Proof::cross_commit_aggregate_partial_scalars(
+        &commits,
+        &proofs,
+        &sets,
+        &cross_sub_values,
+        n,
+    )
+    .unwrap();
+    assert!(cross_proof.cross_commit_batch_verify_scalars(
+        &verifier_params,
+        &commits,
+        &sets,
+        &cross_sub_values
+    ));
+}
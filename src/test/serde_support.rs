@@ -0,0 +1,72 @@
+use pairing::serdes::SerDes;
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::param::paramgen_from_seed;
+use pairings::{Commitment, Proof, ProverParams, VerifierParams};
+
+#[test]
+fn test_serde() {
+    let n = 8usize;
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (mut prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        prover_params.precomp_3();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let proof = Proof::new(&prover_params, &values, 2).unwrap();
+
+        // JSON: a hex string of the canonical encoding
+        let mut com_bytes: Vec<u8> = vec![];
+        assert!(com.serialize(&mut com_bytes, true).is_ok());
+        let com_json = serde_json::to_string(&com).unwrap();
+        let hex: String = com_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(com_json, format!("\"{}\"", hex));
+        assert_eq!(com, serde_json::from_str::<Commitment>(&com_json).unwrap());
+        assert_eq!(
+            com,
+            serde_json::from_str::<Commitment>(&com_json.to_uppercase()).unwrap()
+        );
+
+        let proof_json = serde_json::to_string(&proof).unwrap();
+        assert_eq!(proof, serde_json::from_str::<Proof>(&proof_json).unwrap());
+        let pp_json = serde_json::to_string(&prover_params).unwrap();
+        assert_eq!(
+            prover_params,
+            serde_json::from_str::<ProverParams>(&pp_json).unwrap()
+        );
+        let vp_json = serde_json::to_string(&verifier_params).unwrap();
+        assert_eq!(
+            verifier_params,
+            serde_json::from_str::<VerifierParams>(&vp_json).unwrap()
+        );
+
+        // CBOR: a byte string of the canonical encoding
+        let com_cbor = serde_cbor::to_vec(&com).unwrap();
+        assert_eq!(com_cbor[com_cbor.len() - com_bytes.len()..], com_bytes[..]);
+        assert_eq!(
+            com,
+            serde_cbor::from_slice::<Commitment>(&com_cbor).unwrap()
+        );
+        let proof_cbor = serde_cbor::to_vec(&proof).unwrap();
+        assert_eq!(proof, serde_cbor::from_slice::<Proof>(&proof_cbor).unwrap());
+        let vp_cbor = serde_cbor::to_vec(&verifier_params).unwrap();
+        assert_eq!(
+            verifier_params,
+            serde_cbor::from_slice::<VerifierParams>(&vp_cbor).unwrap()
+        );
+
+        // malformed inputs
+        let odd = format!("\"{}\"", &hex[1..]);
+        assert!(serde_json::from_str::<Commitment>(&odd).is_err());
+        let not_hex = format!("\"zz{}\"", &hex[2..]);
+        assert!(serde_json::from_str::<Commitment>(&not_hex).is_err());
+        let plus = format!("\"+{}\"", &hex[1..]);
+        assert!(serde_json::from_str::<Commitment>(&plus).is_err());
+        let trailing = format!("\"{}00\"", hex);
+        assert!(serde_json::from_str::<Commitment>(&trailing).is_err());
+        assert!(serde_json::from_str::<Commitment>("42").is_err());
+        assert!(serde_json::from_str::<Proof>(&com_json[..com_json.len() - 3]).is_err());
+    }
+}
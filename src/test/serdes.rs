@@ -1,21 +1,29 @@
+use pairing::bls12_381::*;
 use pairing::serdes::SerDes;
 use pairing::CurveProjective;
-use pairings::param::paramgen_from_seed;
+use pairings::ciphersuite::*;
+use pairings::param::{paramgen_from_seed, read_param};
 use pairings::pointproofs_groups::*;
 use pairings::*;
 
 #[test]
 fn test_serdes_prover_param() {
     let n_array = [16];
-    for n in n_array.iter() {
+    for (n, csid) in n_array
+        .iter()
+        .flat_map(|n| vec![(*n, 0), (*n, SWITCHED_GROUPS_BIT)])
+    {
         let (mut prover_params, _verifier_params) =
-            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, *n).unwrap();
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", csid, n)
+                .unwrap();
 
         let mut buf: Vec<u8> = vec![];
-        assert!(prover_params.serialize(&mut buf, false).is_err());
         assert!(prover_params.serialize(&mut buf, true).is_ok());
 
-        assert_eq!(buf.len(), 9 + n * POINTPROOFSG1_LEN * 2);
+        assert_eq!(
+            buf.len(),
+            137 + n * group_orientation(csid).pointproofs_g1_len() * 2
+        );
 
         let mut invalid_buf = buf.clone();
         let mut valid_buf = buf.clone();
@@ -30,7 +38,6 @@ fn test_serdes_prover_param() {
 
         prover_params.precomp_3();
         let mut buf: Vec<u8> = vec![];
-        assert!(prover_params.serialize(&mut buf, false).is_err());
         assert!(prover_params.serialize(&mut buf, true).is_ok());
 
         assert!(ProverParams::deserialize(&mut buf[..].as_ref(), false).is_err());
@@ -39,7 +46,6 @@ fn test_serdes_prover_param() {
 
         prover_params.precomp_256();
         let mut buf: Vec<u8> = vec![];
-        assert!(prover_params.serialize(&mut buf, false).is_err());
         assert!(prover_params.serialize(&mut buf, true).is_ok());
         assert!(ProverParams::deserialize(&mut buf[..].as_ref(), false).is_err());
         let prover_params_recover = ProverParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
@@ -51,15 +57,21 @@ fn test_serdes_prover_param() {
 #[ignore]
 fn test_serdes_prover_param_slow() {
     let n_array = [32, 256];
-    for n in n_array.iter() {
+    for (n, csid) in n_array
+        .iter()
+        .flat_map(|n| vec![(*n, 0), (*n, SWITCHED_GROUPS_BIT)])
+    {
         let (mut prover_params, _verifier_params) =
-            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, *n).unwrap();
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", csid, n)
+                .unwrap();
 
         let mut buf: Vec<u8> = vec![];
-        assert!(prover_params.serialize(&mut buf, false).is_err());
         assert!(prover_params.serialize(&mut buf, true).is_ok());
 
-        assert_eq!(buf.len(), 9 + n * POINTPROOFSG1_LEN * 2);
+        assert_eq!(
+            buf.len(),
+            137 + n * group_orientation(csid).pointproofs_g1_len() * 2
+        );
 
         let mut invalid_buf = buf.clone();
         let mut valid_buf = buf.clone();
@@ -74,7 +86,6 @@ fn test_serdes_prover_param_slow() {
 
         prover_params.precomp_3();
         let mut buf: Vec<u8> = vec![];
-        assert!(prover_params.serialize(&mut buf, false).is_err());
         assert!(prover_params.serialize(&mut buf, true).is_ok());
 
         assert!(ProverParams::deserialize(&mut buf[..].as_ref(), false).is_err());
@@ -83,7 +94,6 @@ fn test_serdes_prover_param_slow() {
 
         prover_params.precomp_256();
         let mut buf: Vec<u8> = vec![];
-        assert!(prover_params.serialize(&mut buf, false).is_err());
         assert!(prover_params.serialize(&mut buf, true).is_ok());
         assert!(ProverParams::deserialize(&mut buf[..].as_ref(), false).is_err());
         let prover_params_recover = ProverParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
@@ -94,22 +104,32 @@ fn test_serdes_prover_param_slow() {
 #[test]
 fn test_serdes_verifier_param() {
     let n_array = [16];
-    for n in n_array.iter() {
+    for (n, csid) in n_array
+        .iter()
+        .flat_map(|n| vec![(*n, 0), (*n, SWITCHED_GROUPS_BIT)])
+    {
         let (_prover_params, verifier_params) =
-            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, *n).unwrap();
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", csid, n)
+                .unwrap();
 
         let mut buf: Vec<u8> = vec![];
-        assert!(verifier_params.serialize(&mut buf, false).is_err());
         assert!(verifier_params.serialize(&mut buf, true).is_ok());
         let len = buf.len();
 
-        assert_eq!(len, 585 + n * POINTPROOFSG2_LEN);
+        assert_eq!(len, 713 + n * group_orientation(csid).pointproofs_g2_len());
 
         assert!(VerifierParams::deserialize(&mut buf[..].as_ref(), false).is_err());
         let verifier_params_recover =
             VerifierParams::deserialize(&mut buf[..].as_ref(), true).unwrap();
         assert_eq!(verifier_params, verifier_params_recover);
 
+        // the pre-computed tables are not serialized, nor recorded in the header
+        let mut precomputed = verifier_params.clone();
+        precomputed.precompute(3).unwrap();
+        let mut precomputed_buf: Vec<u8> = vec![];
+        assert!(precomputed.serialize(&mut precomputed_buf, true).is_ok());
+        assert_eq!(precomputed_buf, buf);
+
         let mut valid_buf1 = vec![0; len];
         assert!(VerifierParams::deserialize(&mut valid_buf1[..].as_ref(), true).is_err());
         assert!(VerifierParams::deserialize(&mut valid_buf1[..].as_ref(), false).is_err());
@@ -126,16 +146,19 @@ fn test_serdes_verifier_param() {
 #[ignore]
 fn test_serdes_verifier_param_slow() {
     let n_array = [32, 256];
-    for n in n_array.iter() {
+    for (n, csid) in n_array
+        .iter()
+        .flat_map(|n| vec![(*n, 0), (*n, SWITCHED_GROUPS_BIT)])
+    {
         let (_prover_params, verifier_params) =
-            paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, *n).unwrap();
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", csid, n)
+                .unwrap();
 
         let mut buf: Vec<u8> = vec![];
-        assert!(verifier_params.serialize(&mut buf, false).is_err());
         assert!(verifier_params.serialize(&mut buf, true).is_ok());
         let len = buf.len();
 
-        assert_eq!(len, 585 + n * POINTPROOFSG2_LEN);
+        assert_eq!(len, 713 + n * group_orientation(csid).pointproofs_g2_len());
 
         assert!(VerifierParams::deserialize(&mut buf[..].as_ref(), false).is_err());
         let verifier_params_recover =
@@ -154,64 +177,265 @@ fn test_serdes_verifier_param_slow() {
     }
 }
 
+/// The error carried by a deserialization error.
+fn pointproofs_error(e: std::io::Error) -> PointproofsError {
+    e.get_ref()
+        .and_then(|inner| inner.downcast_ref::<PointproofsError>())
+        .unwrap()
+        .clone()
+}
+
+/// The blobs of the parameters of crs.param in the headerless format, as written by
+/// `serialize` before the container, i.e., `| 0 | n: u32 | generators | 0: u32 |`,
+/// followed by `gt_element` for the verifier parameters.
+#[test]
+fn test_serdes_baseline() {
+    let crs = std::fs::read("crs.param").unwrap();
+    let (pp, vp) = read_param(&mut crs.as_slice(), 0).unwrap();
+    let pp_blob = std::fs::read("test_vectors/baseline_prover_params.bin").unwrap();
+    let vp_blob = std::fs::read("test_vectors/baseline_verifier_params.bin").unwrap();
+
+    let mut pp_recover = ProverParams::deserialize(&mut pp_blob.as_slice(), true).unwrap();
+    let mut vp_recover = VerifierParams::deserialize(&mut vp_blob.as_slice(), true).unwrap();
+    assert_eq!(pp_recover, pp);
+    assert_eq!(vp_recover, vp);
+    assert_eq!(pp_recover.params_id(), None);
+    assert!(link_params_id(&mut pp_recover, &mut vp_recover).is_ok());
+    assert_eq!(pp_recover.params_id(), pp.params_id());
+    assert_eq!(vp_recover.params_id(), vp.params_id());
+
+    // the payload of a container ends with the headerless format
+    let mut pp_buf: Vec<u8> = vec![];
+    assert!(pp.serialize(&mut pp_buf, true).is_ok());
+    assert_eq!(pp_buf[128..], pp_blob[..]);
+    let mut vp_buf: Vec<u8> = vec![];
+    assert!(vp.serialize(&mut vp_buf, true).is_ok());
+    assert_eq!(vp_buf[128..], vp_blob[..]);
+
+    // truncated blobs
+    assert!(ProverParams::deserialize(&mut pp_blob[..pp_blob.len() - 1].as_ref(), true).is_err());
+    assert!(VerifierParams::deserialize(&mut vp_blob[..vp_blob.len() - 1].as_ref(), true).is_err());
+}
+
+#[test]
+fn test_serdes_container() {
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (mut prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, 8)
+                .unwrap();
+        prover_params.precomp_3();
+
+        let mut pp_buf: Vec<u8> = vec![];
+        assert!(prover_params.serialize(&mut pp_buf, true).is_ok());
+        let mut vp_buf: Vec<u8> = vec![];
+        assert!(verifier_params.serialize(&mut vp_buf, true).is_ok());
+        assert_eq!(pp_buf[0..8], *b"PPPARAMS");
+        assert_eq!(vp_buf[0..8], *b"PPPARAMS");
+
+        // the headerless format is still read, and linked to set the params_id
+        let mut prover_params_recover =
+            ProverParams::deserialize(&mut pp_buf[128..].as_ref(), true).unwrap();
+        let mut verifier_params_recover =
+            VerifierParams::deserialize(&mut vp_buf[128..].as_ref(), true).unwrap();
+        assert_eq!(prover_params, prover_params_recover);
+        assert_eq!(verifier_params, verifier_params_recover);
+        assert!(link_params_id(&mut prover_params_recover, &mut verifier_params_recover).is_ok());
+        assert_eq!(prover_params_recover.params_id(), prover_params.params_id());
+
+        // a bit flip in the payload or in the checksum
+        for i in [32, 63, 64, 100, 600].iter() {
+            let mut invalid = pp_buf.clone();
+            invalid[*i] ^= 1;
+            let e = ProverParams::deserialize(&mut invalid[..].as_ref(), true).unwrap_err();
+            assert_eq!(pointproofs_error(e), PointproofsError::ChecksumMismatch);
+            let mut invalid = vp_buf.clone();
+            invalid[*i] ^= 1;
+            let e = VerifierParams::deserialize(&mut invalid[..].as_ref(), true).unwrap_err();
+            assert_eq!(pointproofs_error(e), PointproofsError::ChecksumMismatch);
+        }
+
+        // truncated blobs
+        for len in [0, 8, 63, 64, 100, pp_buf.len() - 1].iter() {
+            assert!(ProverParams::deserialize(&mut pp_buf[..*len].as_ref(), true).is_err());
+        }
+        let e = ProverParams::deserialize(&mut pp_buf[..100].as_ref(), true).unwrap_err();
+        assert!(matches!(
+            pointproofs_error(e),
+            PointproofsError::MalformedEncoding(_)
+        ));
+
+        // the header must describe the payload
+        assert!(VerifierParams::deserialize(&mut pp_buf[..].as_ref(), true).is_err());
+        assert!(ProverParams::deserialize(&mut vp_buf[..].as_ref(), true).is_err());
+        assert!(ProverParams::deserialize(&mut pp_buf[..].as_ref(), false).is_err());
+        for (i, byte) in [(8, 2), (10, 0x7f), (11, 2), (16, 9)].iter() {
+            let mut invalid = pp_buf.clone();
+            invalid[*i] = *byte;
+            assert!(ProverParams::deserialize(&mut invalid[..].as_ref(), true).is_err());
+        }
+        let mut invalid = pp_buf.clone();
+        invalid[10] ^= SWITCHED_GROUPS_BIT;
+        assert_eq!(
+            pointproofs_error(
+                ProverParams::deserialize(&mut invalid[..].as_ref(), true).unwrap_err()
+            ),
+            PointproofsError::CiphersuiteMismatch {
+                expected: *csid ^ SWITCHED_GROUPS_BIT,
+                found: *csid
+            }
+        );
+    }
+}
+
 #[test]
 fn test_serdes_commit() {
-    let mut buf: Vec<u8> = vec![];
-    let valid_commit = Commitment {
-        ciphersuite: 0,
-        commit: PointproofsG1::one(),
-    };
-    assert!(valid_commit.serialize(&mut buf, false).is_err());
-    assert!(valid_commit.serialize(&mut buf, true).is_ok());
-    let len = buf.len();
-    let valid_commit_recover = match Commitment::deserialize(&mut buf[..].as_ref(), true) {
-        Ok(p) => p,
-        Err(e) => panic!("Error deserilization: {}", e),
-    };
-    assert_eq!(valid_commit, valid_commit_recover);
-    assert!(Commitment::deserialize(&mut buf[..].as_ref(), false).is_err());
-
-    let invalid_commit = Commitment {
-        ciphersuite: 1,
-        commit: PointproofsG1::one(),
-    };
-    assert!(invalid_commit.serialize(&mut buf, true).is_err());
-
-    let mut invalid_buf1 = vec![0; len];
-    let mut invalid_buf2 = vec![1; len];
-    assert!(Commitment::deserialize(&mut invalid_buf1[..].as_ref(), true).is_err());
-    assert!(Commitment::deserialize(&mut invalid_buf1[..].as_ref(), false).is_err());
-    assert!(Commitment::deserialize(&mut invalid_buf2[..].as_ref(), true).is_err());
-    assert!(Commitment::deserialize(&mut invalid_buf2[..].as_ref(), false).is_err());
+    let valid_commits: [Commitment; 2] = [
+        Commitment {
+            ciphersuite: 0,
+            commit: PointproofsG1::Standard(G1::one()),
+            params_id: None,
+        },
+        Commitment {
+            ciphersuite: SWITCHED_GROUPS_BIT,
+            commit: PointproofsG1::Switched(G2::one()),
+            params_id: None,
+        },
+    ];
+    for valid_commit in valid_commits.iter() {
+        let mut buf: Vec<u8> = vec![];
+        assert!(valid_commit.serialize(&mut buf, true).is_ok());
+        let len = buf.len();
+        assert_eq!(
+            len,
+            group_orientation(valid_commit.ciphersuite).commit_len()
+        );
+        let valid_commit_recover = match Commitment::deserialize(&mut buf[..].as_ref(), true) {
+            Ok(p) => p,
+            Err(e) => panic!("Error deserilization: {}", e),
+        };
+        assert_eq!(*valid_commit, valid_commit_recover);
+        assert!(Commitment::deserialize(&mut buf[..].as_ref(), false).is_err());
+
+        let invalid_commit = Commitment {
+            ciphersuite: 0x7f,
+            commit: valid_commit.commit,
+            params_id: None,
+        };
+        assert!(invalid_commit.serialize(&mut buf, true).is_err());
+
+        let mut invalid_buf1 = vec![0; len];
+        let mut invalid_buf2 = vec![1; len];
+        assert!(Commitment::deserialize(&mut invalid_buf1[..].as_ref(), true).is_err());
+        assert!(Commitment::deserialize(&mut invalid_buf1[..].as_ref(), false).is_err());
+        assert!(Commitment::deserialize(&mut invalid_buf2[..].as_ref(), true).is_err());
+        assert!(Commitment::deserialize(&mut invalid_buf2[..].as_ref(), false).is_err());
+    }
 }
 
 #[test]
 fn test_serdes_proof() {
-    let mut buf: Vec<u8> = vec![];
-    let valid_proof = Proof {
-        ciphersuite: 0,
-        proof: PointproofsG1::one(),
-    };
-
-    assert!(valid_proof.serialize(&mut buf, true).is_ok());
-    let len = buf.len();
-    let valid_proof_recover = match Proof::deserialize(&mut buf[..].as_ref(), true) {
-        Ok(p) => p,
-        Err(e) => panic!("Error deserilization: {}", e),
-    };
-    assert_eq!(valid_proof, valid_proof_recover);
-    assert!(Proof::deserialize(&mut buf[..].as_ref(), false).is_err());
-
-    let invalid_proof = Proof {
-        ciphersuite: 1,
-        proof: PointproofsG1::one(),
-    };
-    assert!(invalid_proof.serialize(&mut buf, true).is_err());
-
-    let mut invalid_buf1 = vec![0; len];
-    let mut invalid_buf2 = vec![1; len];
-    assert!(Proof::deserialize(&mut invalid_buf1[..].as_ref(), true).is_err());
-    assert!(Proof::deserialize(&mut invalid_buf1[..].as_ref(), false).is_err());
-    assert!(Proof::deserialize(&mut invalid_buf2[..].as_ref(), true).is_err());
-    assert!(Proof::deserialize(&mut invalid_buf2[..].as_ref(), false).is_err());
+    let valid_proofs: [Proof; 2] = [
+        Proof {
+            ciphersuite: 0,
+            proof: PointproofsG1::Standard(G1::one()),
+        },
+        Proof {
+            ciphersuite: SWITCHED_GROUPS_BIT,
+            proof: PointproofsG1::Switched(G2::one()),
+        },
+    ];
+    for valid_proof in valid_proofs.iter() {
+        let mut buf: Vec<u8> = vec![];
+        assert!(valid_proof.serialize(&mut buf, true).is_ok());
+        let len = buf.len();
+        assert_eq!(len, group_orientation(valid_proof.ciphersuite).proof_len());
+        let valid_proof_recover = match Proof::deserialize(&mut buf[..].as_ref(), true) {
+            Ok(p) => p,
+            Err(e) => panic!("Error deserilization: {}", e),
+        };
+        assert_eq!(*valid_proof, valid_proof_recover);
+        assert!(Proof::deserialize(&mut buf[..].as_ref(), false).is_err());
+
+        let invalid_proof = Proof {
+            ciphersuite: 0x7f,
+            proof: valid_proof.proof,
+        };
+        assert!(invalid_proof.serialize(&mut buf, true).is_err());
+
+        let mut invalid_buf1 = vec![0; len];
+        let mut invalid_buf2 = vec![1; len];
+        assert!(Proof::deserialize(&mut invalid_buf1[..].as_ref(), true).is_err());
+        assert!(Proof::deserialize(&mut invalid_buf1[..].as_ref(), false).is_err());
+        assert!(Proof::deserialize(&mut invalid_buf2[..].as_ref(), true).is_err());
+        assert!(Proof::deserialize(&mut invalid_buf2[..].as_ref(), false).is_err());
+    }
+}
+
+#[test]
+fn test_serdes_uncompressed() {
+    let n = 16;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let orientation = group_orientation(*csid);
+        let (mut prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let values: Vec<String> = (0..n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let proof = Proof::new(&prover_params, &values, 3).unwrap();
+
+        let mut buf: Vec<u8> = vec![];
+        assert!(com.serialize(&mut buf, false).is_ok());
+        assert_eq!(buf.len(), orientation.uncompressed_commit_len());
+        assert_eq!(
+            com,
+            Commitment::deserialize(&mut buf[..].as_ref(), false).unwrap()
+        );
+        assert!(Commitment::deserialize(&mut buf[..].as_ref(), true).is_err());
+
+        let mut buf: Vec<u8> = vec![];
+        assert!(proof.serialize(&mut buf, false).is_ok());
+        assert_eq!(buf.len(), orientation.uncompressed_proof_len());
+        let proof_recover = Proof::deserialize(&mut buf[..].as_ref(), false).unwrap();
+        assert_eq!(proof, proof_recover);
+        assert!(proof_recover.verify(&verifier_params, &com, &values[3], 3));
+
+        let mut buf: Vec<u8> = vec![];
+        assert!(verifier_params.serialize(&mut buf, false).is_ok());
+        assert_eq!(
+            buf.len(),
+            617 + n * orientation.pointproofs_g2_uncompressed_len()
+        );
+        assert_eq!(
+            verifier_params,
+            VerifierParams::deserialize(&mut buf[..].as_ref(), false).unwrap()
+        );
+        assert!(VerifierParams::deserialize(&mut buf[..].as_ref(), true).is_err());
+
+        for precomp in 0..3 {
+            match precomp {
+                1 => prover_params.precomp_3(),
+                2 => prover_params.precomp_256(),
+                _ => (),
+            }
+            let mut buf: Vec<u8> = vec![];
+            assert!(prover_params.serialize(&mut buf, false).is_ok());
+            let mut compressed_buf: Vec<u8> = vec![];
+            assert!(prover_params.serialize(&mut compressed_buf, true).is_ok());
+            // the group elements take twice the space
+            assert_eq!(buf.len() - 41, (compressed_buf.len() - 41) * 2);
+            assert_eq!(
+                prover_params,
+                ProverParams::deserialize(&mut buf[..].as_ref(), false).unwrap()
+            );
+            assert!(ProverParams::deserialize(&mut buf[..].as_ref(), true).is_err());
+            if precomp == 0 {
+                assert_eq!(
+                    buf.len(),
+                    41 + n * 2 * orientation.pointproofs_g1_uncompressed_len()
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,155 @@
+use ff::Field;
+use pairing::bls12_381::Fr;
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_prover_state() {
+    let n = 8usize;
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let mut values: Vec<String> = (0..n)
+            .map(|i| format!("this is message number {}", i))
+            .collect();
+
+        let mut state = ProverState::new(&prover_params, &values).unwrap();
+        assert_eq!(
+            *state.commitment(),
+            Commitment::new(&prover_params, &values).unwrap()
+        );
+        // cache a few proofs
+        for i in &[0usize, 3, 5] {
+            assert!(state.proof(*i).unwrap().verify(
+                &verifier_params,
+                state.commitment(),
+                &values[*i],
+                *i
+            ));
+        }
+
+        // update the values; the cached proofs are refreshed when requested
+        for (k, (index, value)) in [(3usize, "new 3"), (6, "new 6"), (3, "newer 3")]
+            .iter()
+            .enumerate()
+        {
+            state.set(*index, value).unwrap();
+            values[*index] = value.to_string();
+            let com = Commitment::new(&prover_params, &values).unwrap();
+            assert_eq!(*state.commitment(), com);
+            for i in 0..n {
+                if (i + k) % 2 == 0 {
+                    let proof = state.proof(i).unwrap();
+                    assert_eq!(proof, Proof::new(&prover_params, &values, i).unwrap());
+                    assert!(proof.verify(&verifier_params, &com, &values[i], i));
+                }
+            }
+        }
+
+        state
+            .batch_set(&[1, 2, 7], &["batch 1", "batch 2", "batch 7"])
+            .unwrap();
+        values[1] = "batch 1".to_string();
+        values[2] = "batch 2".to_string();
+        values[7] = "batch 7".to_string();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        assert_eq!(*state.commitment(), com);
+        let set = vec![0usize, 3, 7];
+        let agg_proof = state.aggregated_proof(&set).unwrap();
+        let value_sub_vector: Vec<&String> = set.iter().map(|i| &values[*i]).collect();
+        assert!(agg_proof.same_commit_batch_verify(
+            &verifier_params,
+            &com,
+            &set,
+            &value_sub_vector
+        ));
+
+        // a long log of changes is folded into the cached proofs, which are kept
+        state.cache_all_proofs().unwrap();
+        for k in 0..2 * n {
+            let value = format!("changed {} times", k);
+            state.set(k % n, &value).unwrap();
+            values[k % n] = value;
+        }
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        assert_eq!(*state.commitment(), com);
+        assert!(state.proofs.iter().all(|p| p.is_some()));
+        for k in 0..n + 1 {
+            let value = format!("hot index changed {} times", k);
+            state.set(2, &value).unwrap();
+            values[2] = value;
+        }
+        assert!(state.proofs.iter().all(|p| p.is_some()));
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        assert_eq!(*state.commitment(), com);
+        for i in 0..n {
+            assert_eq!(
+                state.proof(i).unwrap(),
+                Proof::new(&prover_params, &values, i).unwrap()
+            );
+        }
+        for i in 0..n {
+            assert!(state
+                .proof(i)
+                .unwrap()
+                .verify(&verifier_params, &com, &values[i], i));
+        }
+
+        // errors leave the state unchanged
+        assert_eq!(
+            state.set(n, "a"),
+            Err(PointproofsError::InvalidIndex { index: n, n })
+        );
+        assert_eq!(
+            state.batch_set(&[1, 1], &["a", "b"]),
+            Err(PointproofsError::DuplicatedIndex(1))
+        );
+        assert_eq!(
+            state.batch_set(&[1, 2], &["a"]),
+            Err(PointproofsError::IndexValueMismatch {
+                indices: 2,
+                values: 1
+            })
+        );
+        assert_eq!(
+            state.proof(n),
+            Err(PointproofsError::InvalidIndex { index: n, n })
+        );
+        assert_eq!(*state.commitment(), com);
+    }
+}
+
+#[test]
+fn test_prover_state_scalars() {
+    let n = 8usize;
+    let (prover_params, verifier_params) =
+        paramgen_from_seed("This is Leo's Favourite very very very long Seed", 0, n).unwrap();
+    let mut scalars = vec![Fr::one(); n];
+
+    let mut state = ProverState::new_from_scalars(&prover_params, &scalars).unwrap();
+    state.cache_all_proofs().unwrap();
+    state
+        .batch_set_scalars(&[2, 4], &[Fr::zero(), Fr::zero()])
+        .unwrap();
+    scalars[2] = Fr::zero();
+    scalars[4] = Fr::zero();
+    state.set_scalar(5, Fr::zero()).unwrap();
+    state.set_scalar(5, Fr::one()).unwrap();
+
+    let com = Commitment::new_from_scalars(&prover_params, &scalars).unwrap();
+    assert_eq!(*state.commitment(), com);
+    for i in 0..n {
+        assert!(state
+            .proof(i)
+            .unwrap()
+            .verify_scalar(&verifier_params, &com, scalars[i], i));
+    }
+    let set = vec![1usize, 2, 4];
+    let sub_scalars: Vec<Fr> = set.iter().map(|i| scalars[*i]).collect();
+    assert!(state
+        .aggregated_proof(&set)
+        .unwrap()
+        .same_commit_batch_verify_scalars(&verifier_params, &com, &set, &sub_scalars));
+}
@@ -0,0 +1,103 @@
+use ff::Field;
+use pairing::bls12_381::*;
+use pairing::serdes::SerDes;
+use pairings::ciphersuite::SWITCHED_GROUPS_BIT;
+use pairings::param::paramgen_from_seed;
+use pairings::*;
+
+#[test]
+fn test_validate() {
+    let n = 8usize;
+    let values: Vec<String> = (0..n)
+        .map(|i| format!("this is message number {}", i))
+        .collect();
+    for csid in &[0, SWITCHED_GROUPS_BIT] {
+        let (prover_params, verifier_params) =
+            paramgen_from_seed("This is Leo's Favourite very very very long Seed", *csid, n)
+                .unwrap();
+        let com = Commitment::new(&prover_params, &values).unwrap();
+        let proof = Proof::new(&prover_params, &values, 1).unwrap();
+        assert!(com.validate().is_ok());
+        assert!(com.validate_with(IdentityPolicy::Reject).is_ok());
+        assert!(proof.validate().is_ok());
+        assert!(proof.validate_with(IdentityPolicy::Reject).is_ok());
+
+        // the identity is a valid commitment and proof of all-zero field elements
+        let zeros = vec![Fr::zero(); n];
+        let zero_com = Commitment::new_from_scalars(&prover_params, &zeros).unwrap();
+        let zero_proof = Proof::new_from_scalars(&prover_params, &zeros, 1).unwrap();
+        assert!(zero_proof.verify_scalar(&verifier_params, &zero_com, Fr::zero(), 1));
+        assert!(zero_com.validate().is_ok());
+        assert!(zero_proof.validate().is_ok());
+        assert_eq!(
+            zero_com.validate_with(IdentityPolicy::Reject),
+            Err(PointproofsError::IdentityPoint)
+        );
+        assert_eq!(
+            zero_proof.validate_with(IdentityPolicy::Reject),
+            Err(PointproofsError::IdentityPoint)
+        );
+
+        // deserialize_checked
+        for compressed in &[true, false] {
+            let mut buf: Vec<u8> = vec![];
+            assert!(proof.serialize(&mut buf, *compressed).is_ok());
+            assert_eq!(
+                Proof::deserialize_checked(
+                    &mut buf[..].as_ref(),
+                    *compressed,
+                    IdentityPolicy::Reject
+                )
+                .unwrap(),
+                proof
+            );
+            assert!(matches!(
+                Proof::deserialize_checked(
+                    &mut buf[..buf.len() - 1].as_ref(),
+                    *compressed,
+                    IdentityPolicy::Accept
+                ),
+                Err(PointproofsError::MalformedEncoding(_))
+            ));
+            buf[0] = 0x7f;
+            assert_eq!(
+                Proof::deserialize_checked(
+                    &mut buf[..].as_ref(),
+                    *compressed,
+                    IdentityPolicy::Accept
+                ),
+                Err(PointproofsError::InvalidCiphersuite(0x7f))
+            );
+
+            let mut buf: Vec<u8> = vec![];
+            assert!(zero_com.serialize(&mut buf, *compressed).is_ok());
+            assert_eq!(
+                Commitment::deserialize_checked(
+                    &mut buf[..].as_ref(),
+                    *compressed,
+                    IdentityPolicy::Accept
+                )
+                .unwrap(),
+                zero_com
+            );
+            assert_eq!(
+                Commitment::deserialize_checked(
+                    &mut buf[..].as_ref(),
+                    *compressed,
+                    IdentityPolicy::Reject
+                ),
+                Err(PointproofsError::IdentityPoint)
+            );
+        }
+
+        let invalid_com = Commitment {
+            ciphersuite: 0x7f,
+            commit: com.commit,
+            params_id: None,
+        };
+        assert_eq!(
+            invalid_com.validate(),
+            Err(PointproofsError::InvalidCiphersuite(0x7f))
+        );
+    }
+}